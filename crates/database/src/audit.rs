@@ -0,0 +1,262 @@
+use std::marker::PhantomData;
+
+use chrono::Utc;
+use entities::audit_log::{ActiveModel, Column, Entity, Model};
+use migrations::Expr;
+use sea_orm::{
+    ActiveModelTrait,
+    ColumnTrait,
+    Condition,
+    ConnectionTrait,
+    DbErr,
+    EntityName,
+    EntityTrait,
+    JsonValue,
+    PrimaryKeyTrait,
+    QueryFilter,
+    QueryOrder,
+    QuerySelect,
+    Value,
+};
+use uuid::Uuid;
+
+use super::traits::{audit::Response, wishlists};
+
+#[derive(Clone, Copy)]
+pub(crate) enum Action {
+    Create,
+    Update,
+    Delete,
+    Restore,
+    Reorder,
+    Move,
+}
+
+impl From<Action> for String {
+    fn from(value: Action) -> Self {
+        match value {
+            Action::Create => "create",
+            Action::Update => "update",
+            Action::Delete => "delete",
+            Action::Restore => "restore",
+            Action::Reorder => "reorder",
+            Action::Move => "move",
+        }
+        .to_owned()
+    }
+}
+
+impl From<Model> for Response {
+    fn from(value: Model) -> Self {
+        Response {
+            id: value.id,
+            actor_id: value.actor_id,
+            entity_type: value.entity_type,
+            entity_id: value.entity_id,
+            action: value.action,
+            before: value.before,
+            after: value.after,
+            created_at: value.created_at,
+        }
+    }
+}
+
+async fn snapshot<E, C>(db: &C, id: Uuid) -> Result<Option<JsonValue>, DbErr>
+where
+    E: EntityTrait,
+    E::PrimaryKey: PrimaryKeyTrait<ValueType = Uuid>,
+    C: ConnectionTrait,
+{
+    E::find_by_id(id).into_json().one(db).await
+}
+
+// Audit entry for a single row, snapshotted before and after the mutation
+pub(crate) struct Entry<E> {
+    action: Action,
+    entity_id: Uuid,
+    actor_id: Option<Uuid>,
+    wishlist_id: Option<wishlists::Id>,
+    before: Option<JsonValue>,
+    entity: PhantomData<E>,
+}
+
+impl<E> Entry<E>
+where
+    E: EntityTrait,
+    E::PrimaryKey: PrimaryKeyTrait<ValueType = Uuid>,
+{
+    pub(crate) async fn capture<C>(db: &C, action: Action, entity_id: Uuid) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let before = match action {
+            Action::Create => None,
+            _ => snapshot::<E, C>(db, entity_id).await?,
+        };
+
+        Ok(Self {
+            action,
+            entity_id,
+            actor_id: None,
+            wishlist_id: None,
+            before,
+            entity: PhantomData,
+        })
+    }
+
+    pub(crate) fn actor(mut self, actor_id: impl Into<Option<Uuid>>) -> Self {
+        self.actor_id = actor_id.into();
+        self
+    }
+
+    pub(crate) fn wishlist(mut self, wishlist_id: wishlists::Id) -> Self {
+        self.wishlist_id = Some(wishlist_id);
+        self
+    }
+
+    pub(crate) async fn record<C>(self, db: &C) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let after = snapshot::<E, C>(db, self.entity_id).await?;
+
+        ActiveModel::from(Model {
+            id: Uuid::new_v4(),
+            actor_id: self.actor_id,
+            entity_type: E::default().table_name().to_owned(),
+            entity_id: self.entity_id,
+            wishlist_id: self.wishlist_id,
+            action: self.action.into(),
+            before: self.before,
+            after,
+            created_at: Utc::now().naive_utc(),
+        })
+        .insert(db)
+        .await
+        .map(|_| ())
+    }
+}
+
+// Blanks out references to the user in the snapshots of another entity, such
+// as the reserving user in those of reservations. Field names are spliced into
+// the SQL, they never come from a request.
+pub(crate) async fn redact_user<T, C>(db: &C, fields: &[&str], user_id: Uuid) -> Result<(), DbErr>
+where
+    T: EntityTrait,
+    C: ConnectionTrait,
+{
+    for field in fields {
+        for (column, name) in [(Column::Before, "before"), (Column::After, "after")] {
+            Entity::update_many()
+                .col_expr(
+                    column,
+                    Expr::cust(format!("jsonb_set(\"{name}\", '{{{field}}}', 'null')")),
+                )
+                .filter(Column::EntityType.eq(T::default().table_name()))
+                .filter(Expr::cust_with_values(
+                    format!("\"{name}\" ->> '{field}' = $1"),
+                    [user_id.to_string()],
+                ))
+                .exec(db)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Blanks out the snapshots of the wishlists owned by the user and of the items
+// in them, including wishlists that were purged since
+pub(crate) async fn redact_owned<C>(db: &C, user_id: Uuid) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let wishlists_table = entities::wishlists::Entity.table_name();
+    let items_table = entities::items::Entity.table_name();
+
+    let mut wishlist_ids: Vec<Uuid> = entities::wishlists::Entity::find()
+        .select_only()
+        .column(entities::wishlists::Column::Id)
+        .filter(entities::wishlists::Column::UserId.eq(user_id))
+        .into_tuple()
+        .all(db)
+        .await?;
+    wishlist_ids.extend(
+        Entity::find()
+            .select_only()
+            .column(Column::EntityId)
+            .distinct()
+            .filter(Column::EntityType.eq(wishlists_table))
+            .filter(
+                Condition::any()
+                    .add(Expr::cust_with_values(
+                        "\"before\" ->> 'user_id' = $1",
+                        [user_id.to_string()],
+                    ))
+                    .add(Expr::cust_with_values(
+                        "\"after\" ->> 'user_id' = $1",
+                        [user_id.to_string()],
+                    )),
+            )
+            .into_tuple::<Uuid>()
+            .all(db)
+            .await?,
+    );
+
+    if wishlist_ids.is_empty() {
+        return Ok(());
+    }
+
+    Entity::update_many()
+        .col_expr(Column::Before, Expr::value(Value::Json(None)))
+        .col_expr(Column::After, Expr::value(Value::Json(None)))
+        .filter(
+            Condition::any()
+                .add(
+                    Condition::all()
+                        .add(Column::EntityType.eq(wishlists_table))
+                        .add(Column::EntityId.is_in(wishlist_ids.clone())),
+                )
+                .add(
+                    Condition::all()
+                        .add(Column::EntityType.eq(items_table))
+                        .add(
+                            Condition::any()
+                                .add(
+                                    Expr::expr(Expr::cust("(\"before\" ->> 'wishlist_id')::uuid"))
+                                        .is_in(wishlist_ids.clone()),
+                                )
+                                .add(
+                                    Expr::expr(Expr::cust("(\"after\" ->> 'wishlist_id')::uuid"))
+                                        .is_in(wishlist_ids),
+                                ),
+                        ),
+                ),
+        )
+        .exec(db)
+        .await
+        .map(|_| ())
+}
+
+// Entries of the wishlist itself and of everything in it, including items moved out of it
+pub(crate) async fn list_by_wishlist<C>(
+    db: &C,
+    wishlist_id: wishlists::Id,
+) -> Result<Vec<Model>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Entity::find()
+        .filter(
+            Condition::any()
+                .add(Column::WishlistId.eq(wishlist_id))
+                .add(Expr::cust_with_values(
+                    "\"before\" ->> 'wishlist_id' = $1",
+                    [wishlist_id.to_string()],
+                )),
+        )
+        .order_by_desc(Column::CreatedAt)
+        .order_by_desc(Column::Id)
+        .all(db)
+        .await
+}
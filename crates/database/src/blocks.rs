@@ -0,0 +1,283 @@
+use async_trait::async_trait;
+use entities::blocks::{ActiveModel, Column, Entity, Model};
+use migrations::{Query, SelectStatement, UnionType};
+use sea_orm::{
+    ActiveModelTrait,
+    ColumnTrait,
+    Condition,
+    ConnectionTrait,
+    DbErr,
+    EntityTrait,
+    PaginatorTrait,
+    QueryFilter,
+    QueryOrder,
+    TransactionTrait,
+};
+
+use super::traits::{
+    blocks::{Error, Kind, Payload, RepositoryTrait, Response},
+    users,
+};
+use crate::{
+    audit::{Action, Entry},
+    Repository,
+};
+
+impl From<Kind> for String {
+    fn from(value: Kind) -> Self {
+        match value {
+            Kind::Block => "block",
+            Kind::Mute => "mute",
+        }
+        .to_owned()
+    }
+}
+
+impl From<Payload> for Model {
+    fn from(value: Payload) -> Self {
+        Model {
+            id: value.id,
+            user_id: value.user_id,
+            target_id: value.target_id,
+            kind: value.kind.into(),
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl TryFrom<Model> for Response {
+    type Error = Error;
+
+    fn try_from(value: Model) -> Result<Self, Self::Error> {
+        let kind = match value.kind.as_str() {
+            "block" => Kind::Block,
+            "mute" => Kind::Mute,
+            _ => return Err(Error::Unknown),
+        };
+
+        Ok(Response {
+            id: value.id,
+            user_id: value.user_id,
+            target_id: value.target_id,
+            kind,
+            created_at: value.created_at,
+        })
+    }
+}
+
+pub(crate) async fn is_blocked<C>(
+    db: &C,
+    user_id: users::Id,
+    other_id: users::Id,
+) -> Result<bool, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let count = Entity::find()
+        .filter(Column::Kind.eq(String::from(Kind::Block)))
+        .filter(
+            Condition::any()
+                .add(
+                    Condition::all()
+                        .add(Column::UserId.eq(user_id))
+                        .add(Column::TargetId.eq(other_id)),
+                )
+                .add(
+                    Condition::all()
+                        .add(Column::UserId.eq(other_id))
+                        .add(Column::TargetId.eq(user_id)),
+                ),
+        )
+        .count(db)
+        .await?;
+
+    Ok(count > 0)
+}
+
+// Users who blocked or muted the given user
+pub(crate) fn silencing(user_id: users::Id) -> SelectStatement {
+    Query::select()
+        .column(Column::UserId)
+        .from(Entity)
+        .and_where(Column::TargetId.eq(user_id))
+        .to_owned()
+}
+
+// Users blocked by the given user or blocking them
+pub(crate) fn blocked_ids(user_id: users::Id) -> SelectStatement {
+    Query::select()
+        .column(Column::TargetId)
+        .from(Entity)
+        .and_where(Column::UserId.eq(user_id))
+        .and_where(Column::Kind.eq(String::from(Kind::Block)))
+        .union(
+            UnionType::Distinct,
+            Query::select()
+                .column(Column::UserId)
+                .from(Entity)
+                .and_where(Column::TargetId.eq(user_id))
+                .and_where(Column::Kind.eq(String::from(Kind::Block)))
+                .to_owned(),
+        )
+        .to_owned()
+}
+
+#[async_trait]
+impl RepositoryTrait for Repository {
+    async fn create_block(&self, payload: Payload) -> Result<Response, Error> {
+        if payload.user_id == payload.target_id {
+            return Err(Error::SelfBlock);
+        }
+
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let users = entities::users::Entity::find()
+            .filter(entities::users::Column::Id.is_in([payload.user_id, payload.target_id]))
+            .filter(entities::users::Column::DeletedAt.is_null())
+            .count(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        if users < 2 {
+            return Err(Error::UserNotFound);
+        }
+
+        let exists = Entity::find()
+            .filter(Column::UserId.eq(payload.user_id))
+            .filter(Column::TargetId.eq(payload.target_id))
+            .filter(Column::Kind.eq(String::from(payload.kind)))
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .is_some();
+
+        if exists {
+            return Err(Error::AlreadyExists);
+        }
+
+        // Blocking ends following in both directions, including pending requests
+        if payload.kind == Kind::Block {
+            let subscriptions = entities::subscriptions::Entity::find()
+                .filter(
+                    Condition::any()
+                        .add(
+                            Condition::all()
+                                .add(entities::subscriptions::Column::UserId.eq(payload.user_id))
+                                .add(
+                                    entities::subscriptions::Column::SubscriberId
+                                        .eq(payload.target_id),
+                                ),
+                        )
+                        .add(
+                            Condition::all()
+                                .add(entities::subscriptions::Column::UserId.eq(payload.target_id))
+                                .add(
+                                    entities::subscriptions::Column::SubscriberId
+                                        .eq(payload.user_id),
+                                ),
+                        ),
+                )
+                .all(&transaction)
+                .await
+                .or(Err(Error::Unknown))?;
+
+            for subscription in subscriptions {
+                let entry = Entry::<entities::subscriptions::Entity>::capture(
+                    &transaction,
+                    Action::Delete,
+                    subscription.id,
+                )
+                .await
+                .or(Err(Error::Unknown))?
+                .actor(payload.user_id);
+
+                entities::subscriptions::Entity::delete_by_id(subscription.id)
+                    .exec(&transaction)
+                    .await
+                    .or(Err(Error::Unknown))?;
+
+                entry.record(&transaction).await.or(Err(Error::Unknown))?;
+            }
+        }
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Create, payload.id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(payload.user_id);
+
+        let model: Model = payload.into();
+        let active_model: ActiveModel = model.into();
+        let model = active_model
+            .insert(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        model.try_into()
+    }
+
+    async fn delete_block(
+        &self,
+        user_id: users::Id,
+        target_id: users::Id,
+        kind: Kind,
+    ) -> Result<(), Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let model = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::TargetId.eq(target_id))
+            .filter(Column::Kind.eq(String::from(kind)))
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Delete, model.id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(user_id);
+
+        Entity::delete_by_id(model.id)
+            .exec(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))
+    }
+
+    async fn list_user_blocks(
+        &self,
+        user_id: users::Id,
+        kind: Kind,
+    ) -> Result<Vec<Response>, Error> {
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::Kind.eq(String::from(kind)))
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .all(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect()
+    }
+
+    async fn is_blocked(&self, user_id: users::Id, other_id: users::Id) -> Result<bool, Error> {
+        is_blocked(&self.database_connection, user_id, other_id)
+            .await
+            .or(Err(Error::Unknown))
+    }
+}
@@ -0,0 +1,142 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use async_trait::async_trait;
+use sea_orm::{
+    AccessMode,
+    ConnectionTrait,
+    DatabaseConnection,
+    DatabaseTransaction,
+    DbBackend,
+    DbErr,
+    ExecResult,
+    IsolationLevel,
+    QueryResult,
+    Statement,
+    TransactionError,
+    TransactionTrait,
+};
+
+// A repository either runs every operation on the pool, or all of them inside
+// the transaction of a unit of work. The transactions the operations begin on
+// their own are then savepoints of that one.
+pub(crate) enum Connection {
+    Pool(DatabaseConnection),
+    Transaction(DatabaseConnection, Arc<DatabaseTransaction>),
+}
+
+impl Connection {
+    pub(crate) fn pool(&self) -> &DatabaseConnection {
+        match self {
+            Connection::Pool(pool) | Connection::Transaction(pool, _) => pool,
+        }
+    }
+
+    pub(crate) fn get_postgres_connection_pool(&self) -> &sqlx::PgPool {
+        self.pool().get_postgres_connection_pool()
+    }
+}
+
+#[async_trait]
+impl ConnectionTrait for Connection {
+    fn get_database_backend(&self) -> DbBackend {
+        match self {
+            Connection::Pool(pool) => pool.get_database_backend(),
+            Connection::Transaction(_, transaction) => transaction.get_database_backend(),
+        }
+    }
+
+    async fn execute(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        match self {
+            Connection::Pool(pool) => pool.execute(stmt).await,
+            Connection::Transaction(_, transaction) => transaction.execute(stmt).await,
+        }
+    }
+
+    async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        match self {
+            Connection::Pool(pool) => pool.execute_unprepared(sql).await,
+            Connection::Transaction(_, transaction) => transaction.execute_unprepared(sql).await,
+        }
+    }
+
+    async fn query_one(&self, stmt: Statement) -> Result<Option<QueryResult>, DbErr> {
+        match self {
+            Connection::Pool(pool) => pool.query_one(stmt).await,
+            Connection::Transaction(_, transaction) => transaction.query_one(stmt).await,
+        }
+    }
+
+    async fn query_all(&self, stmt: Statement) -> Result<Vec<QueryResult>, DbErr> {
+        match self {
+            Connection::Pool(pool) => pool.query_all(stmt).await,
+            Connection::Transaction(_, transaction) => transaction.query_all(stmt).await,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionTrait for Connection {
+    async fn begin(&self) -> Result<DatabaseTransaction, DbErr> {
+        match self {
+            Connection::Pool(pool) => pool.begin().await,
+            Connection::Transaction(_, transaction) => transaction.begin().await,
+        }
+    }
+
+    async fn begin_with_config(
+        &self,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<DatabaseTransaction, DbErr> {
+        match self {
+            Connection::Pool(pool) => pool.begin_with_config(isolation_level, access_mode).await,
+            Connection::Transaction(_, transaction) => {
+                transaction
+                    .begin_with_config(isolation_level, access_mode)
+                    .await
+            }
+        }
+    }
+
+    async fn transaction<F, T, E>(&self, callback: F) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(
+                &'c DatabaseTransaction,
+            ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>
+            + Send,
+        T: Send,
+        E: std::error::Error + Send,
+    {
+        match self {
+            Connection::Pool(pool) => pool.transaction(callback).await,
+            Connection::Transaction(_, transaction) => transaction.transaction(callback).await,
+        }
+    }
+
+    async fn transaction_with_config<F, T, E>(
+        &self,
+        callback: F,
+        isolation_level: Option<IsolationLevel>,
+        access_mode: Option<AccessMode>,
+    ) -> Result<T, TransactionError<E>>
+    where
+        F: for<'c> FnOnce(
+                &'c DatabaseTransaction,
+            ) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'c>>
+            + Send,
+        T: Send,
+        E: std::error::Error + Send,
+    {
+        match self {
+            Connection::Pool(pool) => {
+                pool.transaction_with_config(callback, isolation_level, access_mode)
+                    .await
+            }
+            Connection::Transaction(_, transaction) => {
+                transaction
+                    .transaction_with_config(callback, isolation_level, access_mode)
+                    .await
+            }
+        }
+    }
+}
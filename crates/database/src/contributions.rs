@@ -0,0 +1,177 @@
+use async_trait::async_trait;
+use entities::contributions::{ActiveModel, Column, Entity, Model};
+use sea_orm::{
+    ActiveModelTrait,
+    ColumnTrait,
+    EntityTrait,
+    QueryFilter,
+    QuerySelect,
+    TransactionTrait,
+};
+
+use super::traits::{
+    contributions::{Error, Id, Payload, RepositoryTrait, Response},
+    events::Kind as EventKind,
+    users,
+};
+use crate::{
+    audit::{Action, Entry},
+    events::{item_event, publish},
+    Repository,
+};
+
+impl From<Model> for Response {
+    fn from(value: Model) -> Self {
+        Response {
+            id: value.id,
+            item_id: value.item_id,
+            user_id: value.user_id,
+            amount: value.amount,
+            currency: value.currency,
+            note: value.note,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[async_trait]
+impl RepositoryTrait for Repository {
+    async fn create_contribution(&self, payload: Payload) -> Result<Response, Error> {
+        if payload.amount < 1 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let item = entities::items::Entity::find_by_id(payload.item_id)
+            .filter(entities::items::Column::Id.in_subquery(crate::items::active_ids()))
+            .lock_shared()
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::ItemNotFound)?;
+
+        if item.is_funded {
+            return Err(Error::ItemFunded);
+        }
+
+        let wishlist = crate::wishlists::find_active(&transaction, item.wishlist_id)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::ItemNotFound)?;
+
+        if crate::wishlists::is_archived(wishlist.event_date) {
+            return Err(Error::WishlistArchived);
+        }
+
+        if crate::blocks::is_blocked(&transaction, wishlist.user_id, payload.user_id)
+            .await
+            .or(Err(Error::Unknown))?
+        {
+            return Err(Error::Blocked);
+        }
+
+        // Only contributions in the currency of the item count toward its price
+        let currency = match (item.currency.clone(), payload.currency) {
+            (Some(expected), Some(currency)) if expected != currency => {
+                return Err(Error::CurrencyMismatch);
+            }
+            (Some(currency), _) | (None, Some(currency)) => currency,
+            (None, None) => crate::items::default_currency(&transaction, item.wishlist_id)
+                .await
+                .or(Err(Error::Unknown))?,
+        };
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Create, payload.id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(payload.user_id)
+            .wishlist(item.wishlist_id);
+
+        let model = Model {
+            id: payload.id,
+            item_id: payload.item_id,
+            user_id: Some(payload.user_id),
+            amount: payload.amount,
+            currency,
+            note: payload.note,
+            created_at: payload.created_at,
+        };
+        let active_model: ActiveModel = model.into();
+        let response = active_model
+            .insert(&transaction)
+            .await
+            .map(Into::into)
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        publish(&transaction, item_event(EventKind::Updated, &item))
+            .await
+            .or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(response)
+    }
+
+    async fn get_contribution(&self, id: Id) -> Result<Option<Response>, Error> {
+        Entity::find_by_id(id)
+            .filter(Column::ItemId.in_subquery(crate::items::active_ids()))
+            .one(&self.database_connection)
+            .await
+            .map(|x| x.map(Into::into))
+            .or(Err(Error::Unknown))
+    }
+
+    async fn delete_contribution(&self, id: Id, actor_id: users::Id) -> Result<(), Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let Some(model) = Entity::find_by_id(id)
+            .filter(Column::ItemId.in_subquery(crate::items::active_ids()))
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+        else {
+            return Ok(());
+        };
+
+        let item = entities::items::Entity::find_by_id(model.item_id)
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::ItemNotFound)?;
+
+        let wishlist = crate::wishlists::find_active(&transaction, item.wishlist_id)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::ItemNotFound)?;
+
+        if crate::wishlists::is_archived(wishlist.event_date) {
+            return Err(Error::WishlistArchived);
+        }
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Delete, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(actor_id)
+            .wishlist(item.wishlist_id);
+
+        Entity::delete_by_id(id)
+            .exec(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        publish(&transaction, item_event(EventKind::Updated, &item))
+            .await
+            .or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))
+    }
+}
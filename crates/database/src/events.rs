@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use sea_orm::{ConnectionTrait, DbErr, Statement};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+
+use super::traits::{
+    events::{Error, Event, EventStream, Kind, RepositoryTrait},
+    items,
+    wishlists,
+};
+use crate::Repository;
+
+const CHANNEL: &str = "item_events";
+
+#[derive(Serialize, Deserialize)]
+struct Payload {
+    kind: String,
+    wishlist_id: wishlists::Id,
+    item_id: items::Id,
+    is_hidden: bool,
+}
+
+impl From<Kind> for String {
+    fn from(value: Kind) -> Self {
+        match value {
+            Kind::Created => "created",
+            Kind::Updated => "updated",
+            Kind::Deleted => "deleted",
+            Kind::Reserved => "reserved",
+            Kind::Unreserved => "unreserved",
+        }
+        .to_owned()
+    }
+}
+
+impl From<Event> for Payload {
+    fn from(value: Event) -> Self {
+        Payload {
+            kind: value.kind.into(),
+            wishlist_id: value.wishlist_id,
+            item_id: value.item_id,
+            is_hidden: value.is_hidden,
+        }
+    }
+}
+
+impl TryFrom<Payload> for Event {
+    type Error = Error;
+
+    fn try_from(value: Payload) -> Result<Self, Self::Error> {
+        let kind = match value.kind.as_str() {
+            "created" => Kind::Created,
+            "updated" => Kind::Updated,
+            "deleted" => Kind::Deleted,
+            "reserved" => Kind::Reserved,
+            "unreserved" => Kind::Unreserved,
+            _ => return Err(Error::Unknown),
+        };
+
+        Ok(Event {
+            kind,
+            wishlist_id: value.wishlist_id,
+            item_id: value.item_id,
+            is_hidden: value.is_hidden,
+        })
+    }
+}
+
+pub(crate) fn item_event(kind: Kind, item: &entities::items::Model) -> Event {
+    Event {
+        kind,
+        wishlist_id: item.wishlist_id,
+        item_id: item.id,
+        is_hidden: item.is_hidden,
+    }
+}
+
+// Notifications are only sent once the surrounding transaction commits
+pub(crate) async fn publish<C>(db: &C, event: Event) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let payload = serde_json::to_string(&Payload::from(event.clone()))
+        .map_err(|err| DbErr::Custom(err.to_string()))?;
+
+    db.execute(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        "SELECT pg_notify($1, $2)",
+        [CHANNEL.into(), payload.into()],
+    ))
+    .await?;
+
+    crate::webhooks::enqueue(db, &event).await
+}
+
+#[async_trait]
+impl RepositoryTrait for Repository {
+    async fn listen_item_events(&self) -> Result<EventStream, Error> {
+        let mut listener =
+            PgListener::connect_with(self.database_connection.get_postgres_connection_pool())
+                .await
+                .or(Err(Error::Unknown))?;
+
+        listener.listen(CHANNEL).await.or(Err(Error::Unknown))?;
+
+        let stream = listener.into_stream().map(|notification| {
+            let notification = notification.or(Err(Error::Unknown))?;
+
+            serde_json::from_str::<Payload>(notification.payload())
+                .or(Err(Error::Unknown))
+                .and_then(TryInto::try_into)
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
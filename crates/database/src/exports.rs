@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use sea_orm::{ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, TransactionTrait};
+use uuid::Uuid;
+
+use super::traits::exports::{Error, Export, RepositoryTrait};
+use crate::Repository;
+
+#[async_trait]
+impl RepositoryTrait for Repository {
+    async fn export_user(&self, id: Uuid) -> Result<Export, Error> {
+        // A single transaction keeps the parts of the export consistent with each other
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let user = entities::users::Entity::find_by_id(id)
+            .filter(entities::users::Column::DeletedAt.is_null())
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::UserNotFound)?;
+
+        let wishlists = entities::wishlists::Entity::find()
+            .filter(entities::wishlists::Column::UserId.eq(id))
+            .filter(entities::wishlists::Column::DeletedAt.is_null())
+            .order_by_asc(entities::wishlists::Column::CreatedAt)
+            .all(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let items = crate::items::find()
+            .filter(entities::items::Column::WishlistId.is_in(wishlists.iter().map(|x| x.id)))
+            .order_by_asc(entities::items::Column::WishlistId)
+            .order_by_asc(entities::items::Column::Position)
+            .into_model::<crate::items::QueryResult>()
+            .all(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let subscriptions = entities::subscriptions::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(entities::subscriptions::Column::UserId.eq(id))
+                    .add(entities::subscriptions::Column::SubscriberId.eq(id)),
+            )
+            .order_by_asc(entities::subscriptions::Column::CreatedAt)
+            .all(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()
+            .or(Err(Error::Unknown))?;
+
+        let reservations = entities::reservations::Entity::find()
+            .filter(entities::reservations::Column::UserId.eq(id))
+            .order_by_asc(entities::reservations::Column::CreatedAt)
+            .all(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let contributions = entities::contributions::Entity::find()
+            .filter(entities::contributions::Column::UserId.eq(id))
+            .order_by_asc(entities::contributions::Column::CreatedAt)
+            .all(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(Export {
+            user: user.into(),
+            wishlists: wishlists.into_iter().map(Into::into).collect(),
+            items: items.into_iter().map(Into::into).collect(),
+            subscriptions,
+            reservations: reservations.into_iter().map(Into::into).collect(),
+            contributions: contributions.into_iter().map(Into::into).collect(),
+        })
+    }
+}
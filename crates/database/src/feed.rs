@@ -0,0 +1,121 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter, Statement};
+use uuid::Uuid;
+
+use super::traits::feed::{Entry, Error, Kind, Page, RepositoryTrait};
+use crate::Repository;
+
+// Each branch applies the page boundary and the limit on its own, so no more
+// than two pages of rows are merged and sorted for the final page
+const FEED_QUERY: &str = "
+SELECT *
+FROM (
+    (
+        SELECT
+            'wishlist' AS kind,
+            w.id AS id,
+            w.user_id,
+            w.id AS wishlist_id,
+            w.name AS wishlist_name,
+            NULL::uuid AS item_id,
+            NULL::varchar AS item_name,
+            w.created_at
+        FROM subscriptions s
+        JOIN users u ON u.id = s.user_id AND u.deleted_at IS NULL
+        JOIN wishlists w ON w.user_id = s.user_id AND w.deleted_at IS NULL
+        WHERE s.subscriber_id = $1 AND s.status = 'accepted'
+            AND NOT EXISTS (SELECT 1 FROM blocks b WHERE b.user_id = $1 AND b.target_id = s.user_id)
+            AND ($2::timestamp IS NULL OR (w.created_at, w.id) < ($2, $3))
+        ORDER BY w.created_at DESC, w.id DESC
+        LIMIT $4
+    )
+    UNION ALL
+    (
+        SELECT
+            'item' AS kind,
+            i.id AS id,
+            w.user_id,
+            w.id AS wishlist_id,
+            w.name AS wishlist_name,
+            i.id AS item_id,
+            i.name AS item_name,
+            i.created_at
+        FROM subscriptions s
+        JOIN users u ON u.id = s.user_id AND u.deleted_at IS NULL
+        JOIN wishlists w ON w.user_id = s.user_id AND w.deleted_at IS NULL
+        JOIN items i ON i.wishlist_id = w.id AND i.deleted_at IS NULL AND NOT i.is_hidden
+        WHERE s.subscriber_id = $1 AND s.status = 'accepted'
+            AND NOT EXISTS (SELECT 1 FROM blocks b WHERE b.user_id = $1 AND b.target_id = s.user_id)
+            AND ($2::timestamp IS NULL OR (i.created_at, i.id) < ($2, $3))
+        ORDER BY i.created_at DESC, i.id DESC
+        LIMIT $4
+    )
+) AS feed
+ORDER BY created_at DESC, id DESC
+LIMIT $4
+";
+
+#[derive(FromQueryResult)]
+struct QueryResult {
+    kind: String,
+    user_id: Uuid,
+    wishlist_id: Uuid,
+    wishlist_name: String,
+    item_id: Option<Uuid>,
+    item_name: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl TryFrom<QueryResult> for Entry {
+    type Error = Error;
+
+    fn try_from(value: QueryResult) -> Result<Self, Self::Error> {
+        let kind = match value.kind.as_str() {
+            "wishlist" => Kind::WishlistCreated,
+            "item" => Kind::ItemAdded,
+            _ => return Err(Error::Unknown),
+        };
+
+        Ok(Entry {
+            kind,
+            user_id: value.user_id,
+            wishlist_id: value.wishlist_id,
+            wishlist_name: value.wishlist_name,
+            item_id: value.item_id,
+            item_name: value.item_name,
+            created_at: value.created_at,
+        })
+    }
+}
+
+#[async_trait]
+impl RepositoryTrait for Repository {
+    async fn list_user_feed(&self, user_id: Uuid, page: Page) -> Result<Vec<Entry>, Error> {
+        entities::users::Entity::find_by_id(user_id)
+            .filter(entities::users::Column::DeletedAt.is_null())
+            .one(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::UserNotFound)?;
+
+        let statement = Statement::from_sql_and_values(
+            self.database_connection.get_database_backend(),
+            FEED_QUERY,
+            [
+                user_id.into(),
+                page.before.map(|x| x.created_at).into(),
+                page.before.map(|x| x.id).into(),
+                i64::try_from(page.limit).unwrap_or(i64::MAX).into(),
+            ],
+        );
+
+        QueryResult::find_by_statement(statement)
+            .all(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect()
+    }
+}
@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    sea_query::OnConflict,
+    ActiveValue::Set,
+    ColumnTrait,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+    Statement,
+};
+
+use super::traits::idempotency_keys::{Error, Payload, RepositoryTrait, Response};
+use crate::Repository;
+
+const LOCK_QUERY: &str = "SELECT pg_advisory_xact_lock(hashtextextended($1, 0))";
+
+#[async_trait]
+impl RepositoryTrait for Repository {
+    async fn lock_idempotency_key(
+        &self,
+        endpoint: &str,
+        key: &str,
+    ) -> Result<Option<Response>, Error> {
+        self.database_connection
+            .execute(Statement::from_sql_and_values(
+                self.database_connection.get_database_backend(),
+                LOCK_QUERY,
+                [format!("{endpoint} {key}").into()],
+            ))
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let response =
+            entities::idempotency_keys::Entity::find_by_id((endpoint.to_owned(), key.to_owned()))
+                .filter(entities::idempotency_keys::Column::ExpiresAt.gt(Utc::now().naive_utc()))
+                .one(&self.database_connection)
+                .await
+                .or(Err(Error::Unknown))?
+                .map(|x| Response {
+                    request_hash: x.request_hash,
+                    status_code: x.status_code,
+                    response: x.response,
+                });
+
+        Ok(response)
+    }
+
+    async fn save_idempotency_key(&self, payload: Payload) -> Result<(), Error> {
+        let active_model = entities::idempotency_keys::ActiveModel {
+            endpoint: Set(payload.endpoint),
+            key: Set(payload.key),
+            request_hash: Set(payload.request_hash),
+            status_code: Set(payload.status_code),
+            response: Set(payload.response),
+            created_at: Set(Utc::now().naive_utc()),
+            expires_at: Set(payload.expires_at),
+        };
+
+        // An expired key is taken over by the request reusing it
+        entities::idempotency_keys::Entity::insert(active_model)
+            .on_conflict(
+                OnConflict::columns([
+                    entities::idempotency_keys::Column::Endpoint,
+                    entities::idempotency_keys::Column::Key,
+                ])
+                .update_columns([
+                    entities::idempotency_keys::Column::RequestHash,
+                    entities::idempotency_keys::Column::StatusCode,
+                    entities::idempotency_keys::Column::Response,
+                    entities::idempotency_keys::Column::CreatedAt,
+                    entities::idempotency_keys::Column::ExpiresAt,
+                ])
+                .to_owned(),
+            )
+            .exec(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        Ok(())
+    }
+
+    async fn purge_idempotency_keys(&self, before: NaiveDateTime) -> Result<u64, Error> {
+        let response = entities::idempotency_keys::Entity::delete_many()
+            .filter(entities::idempotency_keys::Column::ExpiresAt.lt(before))
+            .exec(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        Ok(response.rows_affected)
+    }
+}
@@ -1,14 +1,41 @@
 use async_trait::async_trait;
 use aws_sdk_s3::primitives::ByteStream;
+use uuid::Uuid;
 
 use super::traits::item_pictures::{Error, Key, RepositoryTrait, Value};
 use crate::Repository;
 
+impl Repository {
+    pub(crate) async fn duplicate_item_picture(
+        &self,
+        key: Option<Key>,
+    ) -> Result<Option<Key>, Error> {
+        let Some(source) = key else {
+            return Ok(None);
+        };
+
+        let destination = Uuid::new_v4();
+        self.copy_item_picture(source, destination).await?;
+
+        Ok(Some(destination))
+    }
+
+    // Copies are made before the rows referencing them are written, so they
+    // are removed again when writing those rows fails. The original error is
+    // what matters to the caller, a copy left behind only wastes space.
+    pub(crate) async fn discard_item_pictures(&self, keys: impl IntoIterator<Item = Key>) {
+        for key in keys {
+            let _ = self.delete_item_picture(key).await;
+        }
+    }
+}
+
 #[async_trait]
 impl RepositoryTrait for Repository {
     async fn get_item_picture(&self, key: Key) -> Result<Value, Error> {
         self.blob_storage_client
             .get_object()
+            .bucket(&self.blob_storage_bucket)
             .key(key.to_string())
             .send()
             .await
@@ -19,6 +46,7 @@ impl RepositoryTrait for Repository {
     async fn put_item_picture(&self, key: Key, value: ByteStream) -> Result<(), Error> {
         self.blob_storage_client
             .put_object()
+            .bucket(&self.blob_storage_bucket)
             .key(key.to_string())
             .body(value)
             .send()
@@ -27,9 +55,22 @@ impl RepositoryTrait for Repository {
             .or(Err(Error::Unknown))
     }
 
+    async fn copy_item_picture(&self, source: Key, destination: Key) -> Result<(), Error> {
+        self.blob_storage_client
+            .copy_object()
+            .bucket(&self.blob_storage_bucket)
+            .copy_source(format!("{}/{source}", self.blob_storage_bucket))
+            .key(destination.to_string())
+            .send()
+            .await
+            .map(|_| ())
+            .or(Err(Error::Unknown))
+    }
+
     async fn delete_item_picture(&self, key: Key) -> Result<(), Error> {
         self.blob_storage_client
             .delete_object()
+            .bucket(&self.blob_storage_bucket)
             .key(key.to_string())
             .send()
             .await
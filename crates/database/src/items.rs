@@ -1,94 +1,1200 @@
 use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
 use entities::items::{ActiveModel, Column, Entity, Model};
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use migrations::{Alias, Expr, IntoIden, Query, SelectStatement, SimpleExpr};
+use sea_orm::{
+    sea_query::NullOrdering,
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set, Unchanged},
+    ColumnTrait,
+    Condition,
+    ConnectionTrait,
+    DatabaseTransaction,
+    DbErr,
+    EntityTrait,
+    FromQueryResult,
+    Order,
+    QueryFilter,
+    QueryOrder,
+    QuerySelect,
+    QueryTrait,
+    Select,
+    TransactionTrait,
+};
+use uuid::Uuid;
 
-use super::traits::items::{Error, Id, Payload, Predicate, RepositoryTrait, Response};
-use crate::Repository;
+use super::traits::{
+    contributions,
+    events::{Event, Kind as EventKind},
+    items::{
+        BatchError,
+        Error,
+        Filter,
+        Id,
+        Operation,
+        Outcome,
+        Patch,
+        Payload,
+        Priority,
+        RepositoryTrait,
+        Response,
+        Sort,
+    },
+    notifications::Kind,
+    reservations,
+    users,
+    wishlists,
+    Precondition,
+};
+use crate::{
+    audit::{Action, Entry},
+    check_version,
+    events::{item_event, publish},
+    notifications::{notify_subscribers, Event as Notification},
+    set_present,
+    Repository,
+};
+
+#[derive(FromQueryResult)]
+pub(crate) struct QueryResult {
+    id: Uuid,
+    wishlist_id: Uuid,
+    name: String,
+    description: Option<String>,
+    url: Option<String>,
+    price: Option<i64>,
+    currency: Option<String>,
+    quantity: i32,
+    reserved_quantity: i32,
+    contributed_amount: i64,
+    priority: i16,
+    position: i32,
+    is_hidden: bool,
+    is_funded: bool,
+    picture_id: Option<Uuid>,
+    version: i32,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl From<Priority> for i16 {
+    fn from(value: Priority) -> Self {
+        match value {
+            Priority::NiceToHave => 0,
+            Priority::Normal => 1,
+            Priority::MustHave => 2,
+        }
+    }
+}
+
+impl From<i16> for Priority {
+    fn from(value: i16) -> Self {
+        match value {
+            i16::MIN..=0 => Priority::NiceToHave,
+            1 => Priority::Normal,
+            2..=i16::MAX => Priority::MustHave,
+        }
+    }
+}
 
 impl From<Payload> for Model {
     fn from(value: Payload) -> Self {
         Model {
             id: value.id,
             wishlist_id: value.wishlist_id,
-            selected_by_id: value.selected_by_id,
             name: value.name,
             description: value.description,
+            url: value.url,
             price: value.price,
+            currency: value.currency,
+            quantity: value.quantity,
+            priority: value.priority.into(),
+            position: 0,
             is_hidden: value.is_hidden,
+            is_funded: value.is_funded,
             picture_id: value.picture_id,
             created_at: value.created_at,
             updated_at: value.updated_at,
+            deleted_at: None,
+            version: 1,
         }
     }
 }
 
-impl From<Model> for Response {
-    fn from(value: Model) -> Self {
+impl From<QueryResult> for Response {
+    fn from(value: QueryResult) -> Self {
         Response {
             id: value.id,
             wishlist_id: value.wishlist_id,
-            selected_by_id: value.selected_by_id,
             name: value.name,
             description: value.description,
+            url: value.url,
             price: value.price,
+            currency: value.currency,
+            quantity: value.quantity,
+            reserved_quantity: value.reserved_quantity,
+            contributed_amount: value.contributed_amount,
+            priority: value.priority.into(),
+            position: value.position,
             is_hidden: value.is_hidden,
+            is_funded: value.is_funded,
             picture_id: value.picture_id,
+            version: value.version,
             created_at: value.created_at,
             updated_at: value.updated_at,
         }
     }
 }
 
+fn sum_by_item<E, C>(
+    entity: E,
+    column: C,
+    item_id_column: C,
+    type_name: &str,
+    condition: Condition,
+) -> SimpleExpr
+where
+    E: IntoIden + Copy + 'static,
+    C: IntoIden + 'static,
+{
+    let query = Query::select()
+        .expr(
+            Expr::expr(Expr::expr(Expr::col(column).sum()).if_null(0))
+                .cast_as(Alias::new(type_name)),
+        )
+        .from(entity)
+        .and_where(Expr::col((entity, item_id_column)).equals((Entity, Column::Id)))
+        .cond_where(condition)
+        .to_owned();
+
+    SimpleExpr::SubQuery(None, Box::new(query.into_sub_query_statement()))
+}
+
+pub(crate) fn find() -> Select<Entity> {
+    Entity::find()
+        .filter(Column::DeletedAt.is_null())
+        .column_as(
+            sum_by_item(
+                entities::reservations::Entity,
+                entities::reservations::Column::Quantity,
+                entities::reservations::Column::ItemId,
+                "integer",
+                Condition::all(),
+            ),
+            "reserved_quantity",
+        )
+        .column_as(
+            sum_by_item(
+                entities::contributions::Entity,
+                entities::contributions::Column::Amount,
+                entities::contributions::Column::ItemId,
+                "bigint",
+                // Amounts in another currency can't be added up with the price
+                Condition::all().add(
+                    Expr::col((
+                        entities::contributions::Entity,
+                        entities::contributions::Column::Currency,
+                    ))
+                    .equals((Entity, Column::Currency)),
+                ),
+            ),
+            "contributed_amount",
+        )
+}
+
+// Items whose wishlist and its owner are live too. Rows hanging off an item
+// go missing along with any of them.
+pub(crate) fn active_ids() -> SelectStatement {
+    use entities::{users, wishlists};
+
+    Query::select()
+        .column((Entity, Column::Id))
+        .from(Entity)
+        .inner_join(
+            wishlists::Entity,
+            Expr::col((wishlists::Entity, wishlists::Column::Id))
+                .equals((Entity, Column::WishlistId)),
+        )
+        .inner_join(
+            users::Entity,
+            Expr::col((users::Entity, users::Column::Id))
+                .equals((wishlists::Entity, wishlists::Column::UserId)),
+        )
+        .and_where(Expr::col((Entity, Column::DeletedAt)).is_null())
+        .and_where(Expr::col((wishlists::Entity, wishlists::Column::DeletedAt)).is_null())
+        .and_where(Expr::col((users::Entity, users::Column::DeletedAt)).is_null())
+        .to_owned()
+}
+
+// Items in wishlists visible to the viewer, hidden ones only to the owner
+pub(crate) fn visible_to(viewer_id: users::Id) -> Condition {
+    Condition::all()
+        .add(Column::WishlistId.in_subquery(crate::wishlists::visible_ids(viewer_id)))
+        .add(
+            Condition::any().add(Column::IsHidden.eq(false)).add(
+                Column::WishlistId.in_subquery(
+                    Query::select()
+                        .column(entities::wishlists::Column::Id)
+                        .from(entities::wishlists::Entity)
+                        .and_where(entities::wishlists::Column::UserId.eq(viewer_id))
+                        .to_owned(),
+                ),
+            ),
+        )
+}
+
+fn visible_item_ids(viewer_id: users::Id) -> SelectStatement {
+    Query::select()
+        .column(Column::Id)
+        .from(Entity)
+        .cond_where(visible_to(viewer_id))
+        .to_owned()
+}
+
+pub(crate) fn filter(select: Select<Entity>, filter: Filter) -> Select<Entity> {
+    let mut condition = Condition::all();
+
+    if let Some(predicate) = filter.predicate {
+        condition = condition.add(Column::Name.contains(predicate));
+    }
+
+    if let Some(currency) = filter.currency {
+        condition = condition.add(Column::Currency.eq(currency));
+    }
+
+    if let Some(min_price) = filter.min_price {
+        condition = condition.add(Column::Price.gte(min_price));
+    }
+
+    if let Some(max_price) = filter.max_price {
+        condition = condition.add(Column::Price.lte(max_price));
+    }
+
+    let mut select = select.filter(condition);
+
+    let price_order = match filter.sort {
+        Sort::Default => return select,
+        Sort::PriceAsc => Order::Asc,
+        Sort::PriceDesc => Order::Desc,
+        Sort::Priority => return select.order_by_desc(Column::Priority),
+    };
+
+    QueryTrait::query(&mut select)
+        .order_by_with_nulls((Entity, Column::Currency), Order::Asc, NullOrdering::Last)
+        .order_by_with_nulls((Entity, Column::Price), price_order, NullOrdering::Last);
+
+    select
+}
+
+pub(crate) async fn next_position(
+    transaction: &DatabaseTransaction,
+    wishlist_id: wishlists::Id,
+) -> Result<i32, DbErr> {
+    entities::wishlists::Entity::find_by_id(wishlist_id)
+        .lock_exclusive()
+        .one(transaction)
+        .await?;
+
+    Entity::find()
+        .select_only()
+        .column_as(Column::Position.max(), "position")
+        .filter(Column::WishlistId.eq(wishlist_id))
+        .into_tuple::<Option<i32>>()
+        .one(transaction)
+        .await
+        .map(|x| x.flatten().map_or(0, |position| position + 1))
+}
+
+async fn check_writable(
+    transaction: &DatabaseTransaction,
+    wishlist_id: wishlists::Id,
+) -> Result<entities::wishlists::Model, Error> {
+    let wishlist = crate::wishlists::find_active(transaction, wishlist_id)
+        .await
+        .or(Err(Error::Unknown))?
+        .ok_or(Error::WishlistNotFound)?;
+
+    if crate::wishlists::is_archived(wishlist.event_date) {
+        Err(Error::WishlistArchived)
+    } else {
+        Ok(wishlist)
+    }
+}
+
+async fn check_owner(
+    transaction: &DatabaseTransaction,
+    wishlist_id: wishlists::Id,
+    user_id: users::Id,
+) -> Result<(), Error> {
+    let wishlist = crate::wishlists::find_active(transaction, wishlist_id)
+        .await
+        .or(Err(Error::Unknown))?
+        .ok_or(Error::WishlistNotFound)?;
+
+    if wishlist.user_id == user_id {
+        Ok(())
+    } else {
+        Err(Error::NotOwner)
+    }
+}
+
+async fn notify_item_added(
+    transaction: &DatabaseTransaction,
+    wishlist: &entities::wishlists::Model,
+    item: &Model,
+) -> Result<(), DbErr> {
+    if item.is_hidden {
+        return Ok(());
+    }
+
+    notify_subscribers(
+        transaction,
+        wishlist.user_id,
+        Notification {
+            kind: Kind::ItemAdded,
+            actor_id: wishlist.user_id,
+            wishlist_id: wishlist.id,
+            item_id: Some(item.id),
+        },
+        &[],
+    )
+    .await
+}
+
+async fn find_by_id<C>(db: &C, id: Id) -> Result<Option<QueryResult>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    find()
+        .filter(Column::Id.eq(id))
+        .into_model::<QueryResult>()
+        .one(db)
+        .await
+}
+
+async fn create(
+    transaction: &DatabaseTransaction,
+    payload: Payload,
+    actor_id: Option<users::Id>,
+) -> Result<Model, Error> {
+    if payload.quantity < 1 {
+        return Err(Error::InvalidQuantity);
+    }
+
+    let wishlist = check_writable(transaction, payload.wishlist_id).await?;
+
+    let position = next_position(transaction, payload.wishlist_id)
+        .await
+        .or(Err(Error::Unknown))?;
+
+    let entry = Entry::<Entity>::capture(transaction, Action::Create, payload.id)
+        .await
+        .or(Err(Error::Unknown))?
+        .actor(actor_id)
+        .wishlist(wishlist.id);
+
+    let model: Model = payload.into();
+    let mut active_model: ActiveModel = model.into();
+    active_model.position = Set(position);
+    let model = active_model
+        .insert(transaction)
+        .await
+        .or(Err(Error::Unknown))?;
+
+    entry.record(transaction).await.or(Err(Error::Unknown))?;
+    notify_item_added(transaction, &wishlist, &model)
+        .await
+        .or(Err(Error::Unknown))?;
+    publish(transaction, item_event(EventKind::Created, &model))
+        .await
+        .or(Err(Error::Unknown))?;
+
+    Ok(model)
+}
+
+async fn update(
+    transaction: &DatabaseTransaction,
+    id: Id,
+    payload: Payload,
+    actor_id: Option<users::Id>,
+) -> Result<(), Error> {
+    if payload.quantity < 1 {
+        return Err(Error::InvalidQuantity);
+    }
+
+    let item = find()
+        .filter(Column::Id.eq(id))
+        .lock_exclusive()
+        .into_model::<QueryResult>()
+        .one(transaction)
+        .await
+        .or(Err(Error::Unknown))?
+        .ok_or(Error::NotFound)?;
+
+    let wishlist = check_writable(transaction, item.wishlist_id).await?;
+    check_writable(transaction, payload.wishlist_id).await?;
+
+    let reserved_quantity = item.reserved_quantity;
+
+    if payload.quantity < reserved_quantity {
+        return Err(Error::QuantityBelowReserved);
+    }
+
+    let entry = Entry::<Entity>::capture(transaction, Action::Update, id)
+        .await
+        .or(Err(Error::Unknown))?
+        .actor(actor_id)
+        .wishlist(wishlist.id);
+
+    // Viewers are told about items that were visible before or after the update
+    let event = Event {
+        kind: EventKind::Updated,
+        wishlist_id: item.wishlist_id,
+        item_id: id,
+        is_hidden: item.is_hidden && payload.is_hidden,
+    };
+
+    let model: Model = payload.into();
+    let mut active_model = ActiveModel::from(model).reset_all();
+    active_model.position = NotSet;
+    active_model.deleted_at = NotSet;
+    active_model.version = NotSet;
+
+    Entity::update(active_model)
+        .filter(Column::Id.eq(id))
+        .filter(Column::DeletedAt.is_null())
+        .exec(transaction)
+        .await
+        .or(Err(Error::Unknown))?;
+
+    entry.record(transaction).await.or(Err(Error::Unknown))?;
+    publish(transaction, event).await.or(Err(Error::Unknown))?;
+
+    Ok(())
+}
+
+async fn delete(
+    transaction: &DatabaseTransaction,
+    id: Id,
+    actor_id: Option<users::Id>,
+) -> Result<(), Error> {
+    let Some(model) = Entity::find_by_id(id)
+        .filter(Column::DeletedAt.is_null())
+        .lock_exclusive()
+        .one(transaction)
+        .await
+        .or(Err(Error::Unknown))?
+    else {
+        return Ok(());
+    };
+
+    let wishlist = check_writable(transaction, model.wishlist_id).await?;
+
+    let entry = Entry::<Entity>::capture(transaction, Action::Delete, id)
+        .await
+        .or(Err(Error::Unknown))?
+        .actor(actor_id)
+        .wishlist(wishlist.id);
+
+    Entity::update_many()
+        .col_expr(Column::DeletedAt, Expr::value(Utc::now().naive_utc()))
+        .filter(Column::Id.eq(id))
+        .exec(transaction)
+        .await
+        .or(Err(Error::Unknown))?;
+
+    entry.record(transaction).await.or(Err(Error::Unknown))?;
+    publish(transaction, item_event(EventKind::Deleted, &model))
+        .await
+        .or(Err(Error::Unknown))
+}
+
+async fn find_response(transaction: &DatabaseTransaction, id: Id) -> Result<Response, Error> {
+    find_by_id(transaction, id)
+        .await
+        .or(Err(Error::Unknown))?
+        .map(Into::into)
+        .ok_or(Error::Unknown)
+}
+
+pub(crate) async fn default_currency(
+    transaction: &DatabaseTransaction,
+    wishlist_id: wishlists::Id,
+) -> Result<String, Error> {
+    let wishlist = crate::wishlists::find_active(transaction, wishlist_id)
+        .await
+        .or(Err(Error::Unknown))?
+        .ok_or(Error::WishlistNotFound)?;
+
+    entities::users::Entity::find_by_id(wishlist.user_id)
+        .one(transaction)
+        .await
+        .or(Err(Error::Unknown))?
+        .map(|user| user.currency)
+        .ok_or(Error::Unknown)
+}
+
+async fn apply(
+    transaction: &DatabaseTransaction,
+    operation: Operation,
+    actor_id: Option<users::Id>,
+) -> Result<Outcome, Error> {
+    match operation {
+        Operation::Create(mut payload) => {
+            if payload.price.is_some() && payload.currency.is_none() {
+                payload.currency = Some(default_currency(transaction, payload.wishlist_id).await?);
+            }
+
+            let model = create(transaction, payload, actor_id).await?;
+
+            Ok(Outcome::Created(
+                find_response(transaction, model.id).await?,
+            ))
+        }
+        Operation::Update(id, changes) => {
+            let item = find()
+                .filter(Column::Id.eq(id))
+                .lock_exclusive()
+                .into_model::<QueryResult>()
+                .one(transaction)
+                .await
+                .or(Err(Error::Unknown))?
+                .ok_or(Error::NotFound)?;
+
+            let currency = match (changes.price, changes.currency) {
+                (Some(_), None) => Some(default_currency(transaction, item.wishlist_id).await?),
+                (Some(_), currency) => currency,
+                (None, _) => None,
+            };
+
+            let url_changed = changes.url.is_some() && changes.url != item.url;
+
+            let payload = Payload {
+                id,
+                wishlist_id: item.wishlist_id,
+                name: changes.name,
+                description: changes.description,
+                url: changes.url,
+                price: changes.price,
+                currency,
+                quantity: changes.quantity,
+                priority: changes.priority.unwrap_or_else(|| item.priority.into()),
+                is_hidden: changes.is_hidden,
+                is_funded: changes.is_funded,
+                picture_id: item.picture_id,
+                created_at: item.created_at,
+                updated_at: Utc::now().naive_utc(),
+            };
+
+            update(transaction, id, payload, actor_id).await?;
+
+            Ok(Outcome::Updated {
+                item: find_response(transaction, id).await?,
+                url_changed,
+            })
+        }
+        Operation::Delete(id) => {
+            delete(transaction, id, actor_id).await?;
+
+            Ok(Outcome::Deleted(id))
+        }
+    }
+}
+
+async fn insert_copy(
+    transaction: DatabaseTransaction,
+    model: Model,
+    wishlist: &entities::wishlists::Model,
+    user_id: users::Id,
+    picture_id: Option<Uuid>,
+) -> Result<Response, Error> {
+    let now = Utc::now().naive_utc();
+    let copy_id = Uuid::new_v4();
+    let entry = Entry::<Entity>::capture(&transaction, Action::Create, copy_id)
+        .await
+        .or(Err(Error::Unknown))?
+        .actor(user_id)
+        .wishlist(wishlist.id);
+
+    let position = next_position(&transaction, wishlist.id)
+        .await
+        .or(Err(Error::Unknown))?;
+
+    let active_model: ActiveModel = Model {
+        id: copy_id,
+        wishlist_id: wishlist.id,
+        position,
+        is_funded: false,
+        picture_id,
+        created_at: now,
+        updated_at: now,
+        version: 1,
+        ..model
+    }
+    .into();
+    let model = active_model
+        .insert(&transaction)
+        .await
+        .or(Err(Error::Unknown))?;
+
+    entry.record(&transaction).await.or(Err(Error::Unknown))?;
+    notify_item_added(&transaction, wishlist, &model)
+        .await
+        .or(Err(Error::Unknown))?;
+    publish(&transaction, item_event(EventKind::Created, &model))
+        .await
+        .or(Err(Error::Unknown))?;
+
+    let response = find_by_id(&transaction, model.id)
+        .await
+        .or(Err(Error::Unknown))?
+        .map(Into::into)
+        .ok_or(Error::Unknown)?;
+
+    transaction.commit().await.or(Err(Error::Unknown))?;
+
+    Ok(response)
+}
+
 #[async_trait]
 impl RepositoryTrait for Repository {
-    async fn create_item(&self, payload: Payload) -> Result<Response, Error> {
-        let model: Model = payload.into();
-        let active_model: ActiveModel = model.into();
-        active_model
-            .insert(&self.database_connection)
+    async fn create_item(
+        &self,
+        payload: Payload,
+        actor_id: Option<users::Id>,
+    ) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
             .await
-            .map(Into::into)
-            .or(Err(Error::Unknown))
+            .or(Err(Error::Unknown))?;
+
+        let model = create(&transaction, payload, actor_id).await?;
+        let response = find_response(&transaction, model.id).await?;
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(response)
     }
 
     async fn get_item(&self, id: Id) -> Result<Option<Response>, Error> {
-        Entity::find_by_id(id)
+        find_by_id(&self.database_connection, id)
+            .await
+            .map(|x| x.map(Into::into))
+            .or(Err(Error::Unknown))
+    }
+
+    async fn get_visible_item(
+        &self,
+        id: Id,
+        viewer_id: users::Id,
+    ) -> Result<Option<Response>, Error> {
+        find()
+            .filter(Column::Id.eq(id))
+            .filter(visible_to(viewer_id))
+            .into_model::<QueryResult>()
             .one(&self.database_connection)
             .await
             .map(|x| x.map(Into::into))
             .or(Err(Error::Unknown))
     }
 
-    async fn list_items(&self, predicate: Option<Predicate>) -> Result<Vec<Response>, Error> {
-        match predicate {
-            Some(value) => Entity::find().filter(Column::Name.contains(value)),
-            None => Entity::find(),
-        }
-        .order_by_desc(Column::CreatedAt)
-        .order_by_desc(Column::Id)
-        .all(&self.database_connection)
+    async fn list_items(
+        &self,
+        viewer_id: users::Id,
+        filter: Filter,
+    ) -> Result<Vec<Response>, Error> {
+        self::filter(find(), filter)
+            .filter(visible_to(viewer_id))
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .into_model::<QueryResult>()
+            .all(&self.database_connection)
+            .await
+            .map(|x| x.into_iter().map(Into::into).collect())
+            .or(Err(Error::Unknown))
+    }
+
+    async fn update_item(
+        &self,
+        id: Id,
+        payload: Payload,
+        version: Option<Precondition>,
+        actor_id: Option<users::Id>,
+    ) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        check_version::<Entity, _>(
+            &transaction,
+            id,
+            version,
+            Error::NotFound,
+            Error::VersionMismatch,
+        )
         .await
-        .map(|x| x.into_iter().map(Into::into).collect())
-        .or(Err(Error::Unknown))
+        .or(Err(Error::Unknown))??;
+
+        update(&transaction, id, payload, actor_id).await?;
+        let response = find_response(&transaction, id).await?;
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(response)
     }
 
-    async fn update_item(&self, id: Id, payload: Payload) -> Result<Response, Error> {
-        let model: Model = payload.into();
-        let active_model: ActiveModel = model.into();
+    async fn patch_item(
+        &self,
+        id: Id,
+        patch: Patch,
+        version: Option<Precondition>,
+        actor_id: Option<users::Id>,
+    ) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
 
-        Entity::update(active_model)
+        check_version::<Entity, _>(
+            &transaction,
+            id,
+            version,
+            Error::NotFound,
+            Error::VersionMismatch,
+        )
+        .await
+        .or(Err(Error::Unknown))??;
+
+        let item = find()
             .filter(Column::Id.eq(id))
-            .exec(&self.database_connection)
+            .lock_exclusive()
+            .into_model::<QueryResult>()
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+
+        let wishlist = check_writable(&transaction, item.wishlist_id).await?;
+
+        match patch.quantity {
+            Some(quantity) if quantity < 1 => return Err(Error::InvalidQuantity),
+            Some(quantity) if quantity < item.reserved_quantity => {
+                return Err(Error::QuantityBelowReserved)
+            }
+            _ => {}
+        }
+
+        let currency = match (patch.price, patch.currency) {
+            (Some(Some(_)), Some(currency)) => Some(Some(currency)),
+            (Some(Some(_)), None) => Some(Some(
+                default_currency(&transaction, item.wishlist_id).await?,
+            )),
+            (Some(None), _) => Some(None),
+            (None, _) => None,
+        };
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Update, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(actor_id)
+            .wishlist(wishlist.id);
+
+        let event = Event {
+            kind: EventKind::Updated,
+            wishlist_id: item.wishlist_id,
+            item_id: id,
+            is_hidden: item.is_hidden && patch.is_hidden.unwrap_or(item.is_hidden),
+        };
+
+        let active_model = ActiveModel {
+            id: Unchanged(id),
+            name: set_present(patch.name),
+            description: set_present(patch.description),
+            url: set_present(patch.url),
+            price: set_present(patch.price),
+            currency: set_present(currency),
+            quantity: set_present(patch.quantity),
+            priority: set_present(patch.priority.map(Into::into)),
+            is_hidden: set_present(patch.is_hidden),
+            is_funded: set_present(patch.is_funded),
+            updated_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+
+        Entity::update(active_model)
+            .filter(Column::DeletedAt.is_null())
+            .exec(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        publish(&transaction, event).await.or(Err(Error::Unknown))?;
+
+        let response = find_response(&transaction, id).await?;
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(response)
+    }
+
+    async fn delete_item(
+        &self,
+        id: Id,
+        version: Option<Precondition>,
+        actor_id: Option<users::Id>,
+    ) -> Result<(), Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        check_version::<Entity, _>(
+            &transaction,
+            id,
+            version,
+            Error::NotFound,
+            Error::VersionMismatch,
+        )
+        .await
+        .or(Err(Error::Unknown))??;
+
+        delete(&transaction, id, actor_id).await?;
+
+        transaction.commit().await.or(Err(Error::Unknown))
+    }
+
+    async fn batch_items(
+        &self,
+        operations: Vec<Operation>,
+        actor_id: Option<users::Id>,
+    ) -> Result<Vec<Outcome>, BatchError> {
+        let transaction = self.database_connection.begin().await.or(Err(BatchError {
+            index: None,
+            error: Error::Unknown,
+        }))?;
+
+        let mut outcomes = Vec::with_capacity(operations.len());
+        for (index, operation) in operations.into_iter().enumerate() {
+            let outcome = apply(&transaction, operation, actor_id)
+                .await
+                .map_err(|error| BatchError {
+                    index: Some(index),
+                    error,
+                })?;
+
+            outcomes.push(outcome);
+        }
+
+        transaction.commit().await.or(Err(BatchError {
+            index: None,
+            error: Error::Unknown,
+        }))?;
+
+        Ok(outcomes)
+    }
+
+    async fn restore_item(&self, id: Id, user_id: users::Id) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let model = Entity::find_by_id(id)
+            .filter(Column::DeletedAt.is_not_null())
+            .lock_exclusive()
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+
+        check_owner(&transaction, model.wishlist_id, user_id).await?;
+        let wishlist = check_writable(&transaction, model.wishlist_id).await?;
+
+        let position = next_position(&transaction, model.wishlist_id)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Restore, id)
             .await
+            .or(Err(Error::Unknown))?
+            .actor(user_id)
+            .wishlist(wishlist.id);
+
+        let mut active_model: ActiveModel = model.into();
+        active_model.position = Set(position);
+        active_model.deleted_at = Set(None);
+        let model = active_model
+            .update(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        publish(&transaction, item_event(EventKind::Created, &model))
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let response = find_by_id(&transaction, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .map(Into::into)
+            .ok_or(Error::Unknown)?;
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(response)
+    }
+
+    async fn reorder_item(
+        &self,
+        id: Id,
+        position: i32,
+        user_id: users::Id,
+    ) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let model = Entity::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+        let wishlist_id = model.wishlist_id;
+
+        check_owner(&transaction, wishlist_id, user_id).await?;
+        let wishlist = check_writable(&transaction, wishlist_id).await?;
+
+        entities::wishlists::Entity::find_by_id(wishlist_id)
+            .lock_exclusive()
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let mut items = Entity::find()
+            .select_only()
+            .column(Column::Id)
+            .column(Column::Position)
+            .filter(Column::WishlistId.eq(wishlist_id))
+            .filter(Column::DeletedAt.is_null())
+            .order_by_asc(Column::Position)
+            .order_by_asc(Column::Id)
+            .into_tuple::<(Id, i32)>()
+            .all(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Reorder, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(user_id)
+            .wishlist(wishlist.id);
+
+        let index = items
+            .iter()
+            .position(|(item_id, _)| *item_id == id)
+            .ok_or(Error::NotFound)?;
+        let item = items.remove(index);
+        let index = usize::try_from(position).map_or(0, |x| x.min(items.len()));
+        items.insert(index, item);
+
+        for (index, (item_id, current_position)) in (0..).zip(items) {
+            if index == current_position {
+                continue;
+            }
+
+            Entity::update_many()
+                .col_expr(Column::Position, Expr::value(index))
+                .filter(Column::Id.eq(item_id))
+                .exec(&transaction)
+                .await
+                .or(Err(Error::Unknown))?;
+        }
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        publish(&transaction, item_event(EventKind::Updated, &model))
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let response = find_by_id(&transaction, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .map(Into::into)
+            .ok_or(Error::NotFound)?;
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(response)
+    }
+
+    async fn move_item(
+        &self,
+        id: Id,
+        user_id: users::Id,
+        wishlist_id: wishlists::Id,
+    ) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let model = Entity::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
+            .lock_exclusive()
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+
+        check_owner(&transaction, model.wishlist_id, user_id).await?;
+        check_owner(&transaction, wishlist_id, user_id).await?;
+        check_writable(&transaction, model.wishlist_id).await?;
+        check_writable(&transaction, wishlist_id).await?;
+
+        if model.wishlist_id != wishlist_id {
+            let reservations = entities::reservations::Entity::find()
+                .filter(entities::reservations::Column::ItemId.eq(id))
+                .all(&transaction)
+                .await
+                .or(Err(Error::Unknown))?;
+
+            for reservation in reservations {
+                let entry = Entry::<entities::reservations::Entity>::capture(
+                    &transaction,
+                    Action::Delete,
+                    reservation.id,
+                )
+                .await
+                .or(Err(Error::Unknown))?
+                .actor(user_id)
+                .wishlist(model.wishlist_id);
+
+                entities::reservations::Entity::delete_by_id(reservation.id)
+                    .exec(&transaction)
+                    .await
+                    .or(Err(Error::Unknown))?;
+
+                entry.record(&transaction).await.or(Err(Error::Unknown))?;
+            }
+
+            let entry = Entry::<Entity>::capture(&transaction, Action::Move, id)
+                .await
+                .or(Err(Error::Unknown))?
+                .actor(user_id)
+                .wishlist(wishlist_id);
+
+            let position = next_position(&transaction, wishlist_id)
+                .await
+                .or(Err(Error::Unknown))?;
+
+            publish(&transaction, item_event(EventKind::Deleted, &model))
+                .await
+                .or(Err(Error::Unknown))?;
+
+            let mut active_model: ActiveModel = model.into();
+            active_model.wishlist_id = Set(wishlist_id);
+            active_model.position = Set(position);
+            active_model.updated_at = Set(Utc::now().naive_utc());
+            let model = active_model
+                .update(&transaction)
+                .await
+                .or(Err(Error::Unknown))?;
+
+            entry.record(&transaction).await.or(Err(Error::Unknown))?;
+            publish(&transaction, item_event(EventKind::Created, &model))
+                .await
+                .or(Err(Error::Unknown))?;
+        }
+
+        let response = find_by_id(&transaction, id)
+            .await
+            .or(Err(Error::Unknown))?
             .map(Into::into)
+            .ok_or(Error::NotFound)?;
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(response)
+    }
+
+    async fn copy_item(
+        &self,
+        id: Id,
+        user_id: users::Id,
+        wishlist_id: wishlists::Id,
+    ) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let model = Entity::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+
+        check_owner(&transaction, model.wishlist_id, user_id).await?;
+        check_owner(&transaction, wishlist_id, user_id).await?;
+        let wishlist = check_writable(&transaction, wishlist_id).await?;
+
+        let picture_id = self
+            .duplicate_item_picture(model.picture_id)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let result = insert_copy(transaction, model, &wishlist, user_id, picture_id).await;
+        if result.is_err() {
+            self.discard_item_pictures(picture_id).await;
+        }
+
+        result
+    }
+
+    async fn list_item_reservations(
+        &self,
+        id: Id,
+        viewer_id: users::Id,
+    ) -> Result<Vec<reservations::Response>, Error> {
+        entities::reservations::Entity::find()
+            .filter(entities::reservations::Column::ItemId.eq(id))
+            .filter(entities::reservations::Column::ItemId.in_subquery(active_ids()))
+            .filter(entities::reservations::Column::ItemId.in_subquery(visible_item_ids(viewer_id)))
+            .order_by_asc(entities::reservations::Column::CreatedAt)
+            .order_by_asc(entities::reservations::Column::Id)
+            .all(&self.database_connection)
+            .await
+            .map(|x| x.into_iter().map(Into::into).collect())
             .or(Err(Error::Unknown))
     }
 
-    async fn delete_item(&self, id: Id) -> Result<(), Error> {
-        Entity::delete_by_id(id)
-            .exec(&self.database_connection)
+    async fn list_item_contributions(
+        &self,
+        id: Id,
+        viewer_id: users::Id,
+    ) -> Result<Vec<contributions::Response>, Error> {
+        entities::contributions::Entity::find()
+            .filter(entities::contributions::Column::ItemId.eq(id))
+            .filter(entities::contributions::Column::ItemId.in_subquery(active_ids()))
+            .filter(
+                entities::contributions::Column::ItemId.in_subquery(visible_item_ids(viewer_id)),
+            )
+            .order_by_asc(entities::contributions::Column::CreatedAt)
+            .order_by_asc(entities::contributions::Column::Id)
+            .all(&self.database_connection)
             .await
-            .map(|_| ())
+            .map(|x| x.into_iter().map(Into::into).collect())
             .or(Err(Error::Unknown))
     }
 }
@@ -8,57 +8,167 @@ pub use aws_sdk_s3::{
     },
     Client as BlobStorageClient,
 };
+use connection::Connection;
+use futures::future::BoxFuture;
+use sea_orm::{
+    sea_query::{Alias, Expr},
+    ActiveValue::{self, NotSet, Set},
+    DatabaseTransaction,
+    DbErr,
+    EntityTrait,
+    PrimaryKeyTrait,
+    QueryFilter,
+    QuerySelect,
+    Value,
+};
 pub use sea_orm::{ConnectOptions as DatabaseConnectOptions, Database, DatabaseConnection};
-use thiserror::Error;
+use traits::Precondition;
+use uuid::Uuid;
 
+mod audit;
+mod blocks;
+mod connection;
+mod contributions;
+mod events;
+mod exports;
+mod feed;
+mod idempotency_keys;
 mod item_pictures;
 mod items;
+mod notifications;
+mod purge;
+mod reservations;
 mod subscriptions;
+mod suggestions;
+mod templates;
 pub mod traits;
+mod transactions;
 mod user_avatars;
 mod users;
+mod webhooks;
 mod wishlists;
 
-#[derive(Debug, Error)]
-enum Error {
-    #[error("Unknown error")]
-    Unknown,
-}
-
 pub trait RepositoryTrait:
-    traits::item_pictures::RepositoryTrait
+    traits::blocks::RepositoryTrait
+    + traits::contributions::RepositoryTrait
+    + traits::events::RepositoryTrait
+    + traits::exports::RepositoryTrait
+    + traits::feed::RepositoryTrait
+    + traits::idempotency_keys::RepositoryTrait
+    + traits::item_pictures::RepositoryTrait
     + traits::items::RepositoryTrait
+    + traits::notifications::RepositoryTrait
+    + traits::purge::RepositoryTrait
+    + traits::reservations::RepositoryTrait
     + traits::subscriptions::RepositoryTrait
+    + traits::suggestions::RepositoryTrait
+    + traits::templates::RepositoryTrait
+    + traits::transactions::RepositoryTrait
     + traits::user_avatars::RepositoryTrait
     + traits::users::RepositoryTrait
+    + traits::webhooks::RepositoryTrait
     + traits::wishlists::RepositoryTrait
 {
 }
 
 impl RepositoryTrait for Repository {}
 
+impl dyn RepositoryTrait + Send + Sync {
+    /// Runs the work with a repository whose operations all happen in a single
+    /// transaction, committed when the work succeeds and rolled back otherwise.
+    ///
+    /// Blob storage is not part of the transaction. Pictures and avatars the
+    /// work puts, copies or deletes stay that way after a rollback, so
+    /// operations touching them, such as erasing a user or copying an item,
+    /// are best run on their own.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the work, or of beginning or committing the
+    /// transaction.
+    pub async fn unit_of_work<T, E, F>(&self, work: F) -> Result<T, E>
+    where
+        F: for<'a> FnOnce(&'a (dyn RepositoryTrait + Send + Sync)) -> BoxFuture<'a, Result<T, E>>
+            + Send,
+        E: From<traits::transactions::Error>,
+    {
+        let transaction = self.begin_transaction().await?;
+
+        match work(transaction.repository()).await {
+            Ok(value) => {
+                transaction.commit().await?;
+                Ok(value)
+            }
+            Err(err) => {
+                // The error of the work is the one worth reporting, the
+                // transaction is rolled back on drop anyway
+                transaction.rollback().await.ok();
+                Err(err)
+            }
+        }
+    }
+}
+
+// Fields missing from a partial update are left out of the UPDATE statement
+fn set_present<V>(value: Option<V>) -> ActiveValue<V>
+where
+    V: Into<Value>,
+{
+    value.map_or(NotSet, Set)
+}
+
+// Locks the row so that the version can't change before the write. A missing
+// row fails only a precondition asking for it to exist, otherwise it is simply
+// not found.
+async fn check_version<E, Err>(
+    transaction: &DatabaseTransaction,
+    id: Uuid,
+    precondition: Option<Precondition>,
+    not_found: Err,
+    mismatch: Err,
+) -> Result<Result<(), Err>, DbErr>
+where
+    E: EntityTrait,
+    E::PrimaryKey: PrimaryKeyTrait<ValueType = Uuid>,
+{
+    let Some(precondition) = precondition else {
+        return Ok(Ok(()));
+    };
+
+    let current = E::find_by_id(id)
+        .select_only()
+        .column_as(Expr::col(Alias::new("version")), "version")
+        .filter(Expr::col(Alias::new("deleted_at")).is_null())
+        .lock_exclusive()
+        .into_tuple::<i32>()
+        .one(transaction)
+        .await?;
+
+    Ok(match (precondition, current) {
+        (Precondition::Exists, None) => Err(mismatch),
+        (Precondition::Version(_), None) => Err(not_found),
+        (Precondition::Version(version), Some(current)) if version != current => Err(mismatch),
+        _ => Ok(()),
+    })
+}
+
 impl Repository {
     #[must_use]
     pub fn new(
         database_connection: DatabaseConnection,
         blob_storage_client: BlobStorageClient,
+        blob_storage_bucket: String,
     ) -> Self {
         Self {
-            database_connection,
+            database_connection: Connection::Pool(database_connection),
             blob_storage_client,
+            blob_storage_bucket,
         }
     }
-
-    // async fn healthcheck(&self) -> Result<(), Error> {
-    //     self.database_connection
-    //         .execute_unprepared("SELECT 1;")
-    //         .await
-    //         .map(|_| ())
-    //         .or(Err(Error::Unknown))
-    // }
 }
 
 pub struct Repository {
-    database_connection: DatabaseConnection,
+    database_connection: Connection,
     blob_storage_client: BlobStorageClient,
+    blob_storage_bucket: String,
 }
@@ -0,0 +1,234 @@
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use entities::notifications::{ActiveModel, Column, Entity, Model};
+use migrations::{Expr, LockBehavior, LockType, Query};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::Set,
+    ColumnTrait,
+    Condition,
+    ConnectionTrait,
+    DatabaseTransaction,
+    DbErr,
+    EntityTrait,
+    Order,
+    QueryFilter,
+    QueryOrder,
+};
+
+use super::traits::{
+    items,
+    notifications::{Error, Filter, Id, Kind, RepositoryTrait, Response},
+    subscriptions,
+    users,
+    wishlists,
+};
+use crate::Repository;
+
+impl From<Kind> for String {
+    fn from(value: Kind) -> Self {
+        match value {
+            Kind::WishlistCreated => "wishlist_created",
+            Kind::ItemAdded => "item_added",
+            Kind::ItemReserved => "item_reserved",
+        }
+        .to_owned()
+    }
+}
+
+impl TryFrom<Model> for Response {
+    type Error = Error;
+
+    fn try_from(value: Model) -> Result<Self, Self::Error> {
+        let kind = match value.kind.as_str() {
+            "wishlist_created" => Kind::WishlistCreated,
+            "item_added" => Kind::ItemAdded,
+            "item_reserved" => Kind::ItemReserved,
+            _ => return Err(Error::Unknown),
+        };
+
+        Ok(Response {
+            id: value.id,
+            user_id: value.user_id,
+            kind,
+            actor_id: value.actor_id,
+            wishlist_id: value.wishlist_id,
+            item_id: value.item_id,
+            is_read: value.read_at.is_some(),
+            created_at: value.created_at,
+        })
+    }
+}
+
+fn into_responses(models: Vec<Model>) -> Result<Vec<Response>, Error> {
+    models.into_iter().map(TryInto::try_into).collect()
+}
+
+pub(crate) struct Event {
+    pub(crate) kind: Kind,
+    pub(crate) actor_id: users::Id,
+    pub(crate) wishlist_id: wishlists::Id,
+    pub(crate) item_id: Option<items::Id>,
+}
+
+// Fans the event out to every subscriber of the user, except the ones given
+pub(crate) async fn notify_subscribers(
+    transaction: &DatabaseTransaction,
+    user_id: users::Id,
+    event: Event,
+    except: &[users::Id],
+) -> Result<(), DbErr> {
+    let mut select = Query::select()
+        .expr(Expr::cust("gen_random_uuid()"))
+        .column(entities::subscriptions::Column::SubscriberId)
+        .expr(Expr::val(String::from(event.kind)))
+        .expr(Expr::val(event.actor_id))
+        .expr(Expr::val(event.wishlist_id))
+        .expr(Expr::val(event.item_id))
+        .expr(Expr::val(Utc::now().naive_utc()))
+        .from(entities::subscriptions::Entity)
+        .and_where(entities::subscriptions::Column::UserId.eq(user_id))
+        .and_where(
+            entities::subscriptions::Column::Status
+                .eq(String::from(subscriptions::Status::Accepted)),
+        )
+        .and_where(
+            entities::subscriptions::Column::SubscriberId
+                .not_in_subquery(crate::blocks::silencing(user_id)),
+        )
+        .to_owned();
+
+    if !except.is_empty() {
+        select.and_where(
+            entities::subscriptions::Column::SubscriberId.is_not_in(except.iter().copied()),
+        );
+    }
+
+    let insert = Query::insert()
+        .into_table(Entity)
+        .columns([
+            Column::Id,
+            Column::UserId,
+            Column::Kind,
+            Column::ActorId,
+            Column::WishlistId,
+            Column::ItemId,
+            Column::CreatedAt,
+        ])
+        .select_from(select)
+        .map_err(|err| DbErr::Custom(err.to_string()))?
+        .to_owned();
+
+    transaction
+        .execute(transaction.get_database_backend().build(&insert))
+        .await
+        .map(|_| ())
+}
+
+#[async_trait]
+impl RepositoryTrait for Repository {
+    async fn get_notification(&self, id: Id) -> Result<Option<Response>, Error> {
+        Entity::find_by_id(id)
+            .one(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?
+            .map(TryInto::try_into)
+            .transpose()
+    }
+
+    async fn list_user_notifications(
+        &self,
+        user_id: users::Id,
+        filter: Filter,
+    ) -> Result<Vec<Response>, Error> {
+        let mut select = Entity::find().filter(Column::UserId.eq(user_id));
+
+        if filter.unread {
+            select = select.filter(Column::ReadAt.is_null());
+        }
+
+        select
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .all(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))
+            .and_then(into_responses)
+    }
+
+    async fn mark_notification(&self, id: Id, is_read: bool) -> Result<Response, Error> {
+        let model = Entity::find_by_id(id)
+            .one(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+
+        if model.read_at.is_some() == is_read {
+            return model.try_into();
+        }
+
+        let mut active_model: ActiveModel = model.into();
+        active_model.read_at = Set(is_read.then(|| Utc::now().naive_utc()));
+        active_model
+            .update(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?
+            .try_into()
+    }
+
+    async fn mark_user_notifications(&self, user_id: users::Id) -> Result<u64, Error> {
+        Entity::update_many()
+            .col_expr(Column::ReadAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ReadAt.is_null())
+            .exec(&self.database_connection)
+            .await
+            .map(|x| x.rows_affected)
+            .or(Err(Error::Unknown))
+    }
+
+    async fn claim_undelivered_notifications(
+        &self,
+        limit: u64,
+        claim: Duration,
+    ) -> Result<Vec<Response>, Error> {
+        let now = Utc::now().naive_utc();
+
+        // Rows another instance is claiming right now are skipped rather than waited for
+        let claimable = Query::select()
+            .column(Column::Id)
+            .from(Entity)
+            .and_where(Column::DeliveredAt.is_null())
+            .cond_where(
+                Condition::any()
+                    .add(Column::ClaimedUntil.is_null())
+                    .add(Column::ClaimedUntil.lt(now)),
+            )
+            .order_by(Column::CreatedAt, Order::Asc)
+            .order_by(Column::Id, Order::Asc)
+            .limit(limit)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .to_owned();
+
+        let mut models = Entity::update_many()
+            .col_expr(Column::ClaimedUntil, Expr::value(now + claim))
+            .filter(Column::Id.in_subquery(claimable))
+            .exec_with_returning(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        models.sort_by_key(|x| (x.created_at, x.id));
+
+        into_responses(models)
+    }
+
+    async fn mark_notifications_delivered(&self, ids: Vec<Id>) -> Result<(), Error> {
+        Entity::update_many()
+            .col_expr(Column::DeliveredAt, Expr::value(Utc::now().naive_utc()))
+            .filter(Column::Id.is_in(ids))
+            .exec(&self.database_connection)
+            .await
+            .map(|_| ())
+            .or(Err(Error::Unknown))
+    }
+}
@@ -0,0 +1,86 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect, TransactionTrait};
+use uuid::Uuid;
+
+use super::traits::{
+    item_pictures::RepositoryTrait as _,
+    purge::{Error, RepositoryTrait, Response},
+    user_avatars::RepositoryTrait as _,
+};
+use crate::Repository;
+
+#[async_trait]
+impl RepositoryTrait for Repository {
+    async fn purge_deleted(&self, before: NaiveDateTime) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let pictures = entities::items::Entity::find()
+            .select_only()
+            .column(entities::items::Column::PictureId)
+            .filter(entities::items::Column::DeletedAt.lt(before))
+            .filter(entities::items::Column::PictureId.is_not_null())
+            .into_tuple::<Uuid>()
+            .all(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let avatars = entities::users::Entity::find()
+            .select_only()
+            .column(entities::users::Column::AvatarId)
+            .filter(entities::users::Column::DeletedAt.lt(before))
+            .filter(entities::users::Column::AvatarId.is_not_null())
+            .into_tuple::<Uuid>()
+            .all(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let items = entities::items::Entity::delete_many()
+            .filter(entities::items::Column::DeletedAt.lt(before))
+            .exec(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .rows_affected;
+
+        let wishlists = entities::wishlists::Entity::delete_many()
+            .filter(entities::wishlists::Column::DeletedAt.lt(before))
+            .exec(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .rows_affected;
+
+        let users = entities::users::Entity::delete_many()
+            .filter(entities::users::Column::DeletedAt.lt(before))
+            .exec(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .rows_affected;
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        let mut blobs = 0;
+
+        for key in pictures {
+            if self.delete_item_picture(key).await.is_ok() {
+                blobs += 1;
+            }
+        }
+
+        for key in avatars {
+            if self.delete_user_avatar(key).await.is_ok() {
+                blobs += 1;
+            }
+        }
+
+        Ok(Response {
+            users,
+            wishlists,
+            items,
+            blobs,
+        })
+    }
+}
@@ -0,0 +1,199 @@
+use async_trait::async_trait;
+use entities::reservations::{ActiveModel, Column, Entity, Model};
+use sea_orm::{
+    ActiveModelTrait,
+    ColumnTrait,
+    EntityTrait,
+    QueryFilter,
+    QuerySelect,
+    TransactionTrait,
+};
+
+use super::traits::{
+    events::Kind as EventKind,
+    notifications::Kind,
+    reservations::{Error, Id, Payload, RepositoryTrait, Response},
+    users,
+};
+use crate::{
+    audit::{Action, Entry},
+    events::{item_event, publish},
+    notifications::{notify_subscribers, Event},
+    Repository,
+};
+
+impl From<Payload> for Model {
+    fn from(value: Payload) -> Self {
+        Model {
+            id: value.id,
+            item_id: value.item_id,
+            user_id: Some(value.user_id),
+            quantity: value.quantity,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<Model> for Response {
+    fn from(value: Model) -> Self {
+        Response {
+            id: value.id,
+            item_id: value.item_id,
+            user_id: value.user_id,
+            quantity: value.quantity,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[async_trait]
+impl RepositoryTrait for Repository {
+    async fn create_reservation(&self, payload: Payload) -> Result<Response, Error> {
+        if payload.quantity < 1 {
+            return Err(Error::InvalidQuantity);
+        }
+
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let item = entities::items::Entity::find_by_id(payload.item_id)
+            .filter(entities::items::Column::Id.in_subquery(crate::items::active_ids()))
+            .lock_exclusive()
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::ItemNotFound)?;
+
+        let wishlist = crate::wishlists::find_active(&transaction, item.wishlist_id)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::ItemNotFound)?;
+
+        if crate::wishlists::is_archived(wishlist.event_date) {
+            return Err(Error::WishlistArchived);
+        }
+
+        if crate::blocks::is_blocked(&transaction, wishlist.user_id, payload.user_id)
+            .await
+            .or(Err(Error::Unknown))?
+        {
+            return Err(Error::Blocked);
+        }
+
+        let reserved_quantity = Entity::find()
+            .select_only()
+            .column_as(Column::Quantity.sum(), "reserved_quantity")
+            .filter(Column::ItemId.eq(payload.item_id))
+            .into_tuple::<Option<i64>>()
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .flatten()
+            .unwrap_or_default();
+
+        if reserved_quantity + i64::from(payload.quantity) > i64::from(item.quantity) {
+            return Err(Error::InsufficientQuantity);
+        }
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Create, payload.id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(payload.user_id)
+            .wishlist(item.wishlist_id);
+
+        let user_id = payload.user_id;
+        let model: Model = payload.into();
+        let active_model: ActiveModel = model.into();
+        let response: Response = active_model
+            .insert(&transaction)
+            .await
+            .map(Into::into)
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        publish(&transaction, item_event(EventKind::Reserved, &item))
+            .await
+            .or(Err(Error::Unknown))?;
+
+        if !item.is_hidden {
+            notify_subscribers(
+                &transaction,
+                wishlist.user_id,
+                Event {
+                    kind: Kind::ItemReserved,
+                    actor_id: user_id,
+                    wishlist_id: wishlist.id,
+                    item_id: Some(item.id),
+                },
+                &[user_id, wishlist.user_id],
+            )
+            .await
+            .or(Err(Error::Unknown))?;
+        }
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(response)
+    }
+
+    async fn get_reservation(&self, id: Id) -> Result<Option<Response>, Error> {
+        Entity::find_by_id(id)
+            .filter(Column::ItemId.in_subquery(crate::items::active_ids()))
+            .one(&self.database_connection)
+            .await
+            .map(|x| x.map(Into::into))
+            .or(Err(Error::Unknown))
+    }
+
+    async fn delete_reservation(&self, id: Id, actor_id: users::Id) -> Result<(), Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let Some(model) = Entity::find_by_id(id)
+            .filter(Column::ItemId.in_subquery(crate::items::active_ids()))
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+        else {
+            return Ok(());
+        };
+
+        let item = entities::items::Entity::find_by_id(model.item_id)
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::ItemNotFound)?;
+
+        let wishlist = crate::wishlists::find_active(&transaction, item.wishlist_id)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::ItemNotFound)?;
+
+        if crate::wishlists::is_archived(wishlist.event_date) {
+            return Err(Error::WishlistArchived);
+        }
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Delete, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(actor_id)
+            .wishlist(item.wishlist_id);
+
+        Entity::delete_by_id(id)
+            .exec(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        publish(&transaction, item_event(EventKind::Unreserved, &item))
+            .await
+            .or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))
+    }
+}
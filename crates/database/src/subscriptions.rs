@@ -1,49 +1,269 @@
 use async_trait::async_trait;
-use entities::subscriptions::{ActiveModel, Entity, Model};
-use sea_orm::{ActiveModelTrait, EntityTrait};
+use entities::subscriptions::{ActiveModel, Column, Entity, Model};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::Set,
+    ColumnTrait,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+    QueryOrder,
+    TransactionTrait,
+};
 
-use super::traits::subscriptions::{Error, Id, Payload, RepositoryTrait, Response};
-use crate::Repository;
+use super::traits::{
+    subscriptions::{Error, Id, Payload, RepositoryTrait, Response, Status},
+    users,
+};
+use crate::{
+    audit::{Action, Entry},
+    Repository,
+};
 
-impl From<Payload> for Model {
-    fn from(value: Payload) -> Self {
-        Model {
-            id: value.id,
-            user_id: value.user_id,
-            subscriber_id: value.subscriber_id,
-            created_at: value.created_at,
+impl From<Status> for String {
+    fn from(value: Status) -> Self {
+        match value {
+            Status::Pending => "pending",
+            Status::Accepted => "accepted",
         }
+        .to_owned()
     }
 }
 
-impl From<Model> for Response {
-    fn from(value: Model) -> Self {
-        Response {
+impl TryFrom<Model> for Response {
+    type Error = Error;
+
+    fn try_from(value: Model) -> Result<Self, Self::Error> {
+        let status = match value.status.as_str() {
+            "pending" => Status::Pending,
+            "accepted" => Status::Accepted,
+            _ => return Err(Error::Unknown),
+        };
+
+        Ok(Response {
             id: value.id,
             user_id: value.user_id,
             subscriber_id: value.subscriber_id,
+            status,
             created_at: value.created_at,
-        }
+        })
     }
 }
 
+fn into_responses(models: Vec<Model>) -> Result<Vec<Response>, Error> {
+    models.into_iter().map(TryInto::try_into).collect()
+}
+
+async fn list_pending<C>(db: &C, column: Column, id: users::Id) -> Result<Vec<Response>, Error>
+where
+    C: ConnectionTrait,
+{
+    Entity::find()
+        .filter(column.eq(id))
+        .filter(Column::Status.eq(String::from(Status::Pending)))
+        .order_by_desc(Column::CreatedAt)
+        .order_by_desc(Column::Id)
+        .all(db)
+        .await
+        .or(Err(Error::Unknown))
+        .and_then(into_responses)
+}
+
 #[async_trait]
 impl RepositoryTrait for Repository {
     async fn create_subscription(&self, payload: Payload) -> Result<Response, Error> {
-        let model: Model = payload.into();
-        let active_model: ActiveModel = model.into();
-        active_model
-            .insert(&self.database_connection)
+        if payload.user_id == payload.subscriber_id {
+            return Err(Error::SelfSubscription);
+        }
+
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let users = entities::users::Entity::find()
+            .filter(entities::users::Column::Id.is_in([payload.user_id, payload.subscriber_id]))
+            .filter(entities::users::Column::DeletedAt.is_null())
+            .all(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        if users.len() < 2 {
+            return Err(Error::UserNotFound);
+        }
+
+        if crate::blocks::is_blocked(&transaction, payload.user_id, payload.subscriber_id)
+            .await
+            .or(Err(Error::Unknown))?
+        {
+            return Err(Error::Blocked);
+        }
+
+        let is_private = users
+            .iter()
+            .any(|x| x.id == payload.user_id && x.is_private);
+
+        let exists = Entity::find()
+            .filter(Column::UserId.eq(payload.user_id))
+            .filter(Column::SubscriberId.eq(payload.subscriber_id))
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .is_some();
+
+        if exists {
+            return Err(Error::AlreadyExists);
+        }
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Create, payload.id)
             .await
-            .map(Into::into)
-            .or(Err(Error::Unknown))
+            .or(Err(Error::Unknown))?
+            .actor(payload.subscriber_id);
+
+        let status = if is_private {
+            Status::Pending
+        } else {
+            Status::Accepted
+        };
+
+        let model = ActiveModel {
+            id: Set(payload.id),
+            user_id: Set(payload.user_id),
+            subscriber_id: Set(payload.subscriber_id),
+            created_at: Set(payload.created_at),
+            status: Set(status.into()),
+        }
+        .insert(&transaction)
+        .await
+        .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        model.try_into()
+    }
+
+    async fn get_subscription(&self, id: Id) -> Result<Option<Response>, Error> {
+        Entity::find_by_id(id)
+            .one(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?
+            .map(TryInto::try_into)
+            .transpose()
     }
 
     async fn delete_subscription(&self, id: Id) -> Result<(), Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let Some(model) = Entity::find_by_id(id)
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+        else {
+            return Ok(());
+        };
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Delete, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(model.subscriber_id);
+
+        Entity::delete_by_id(id)
+            .exec(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))
+    }
+
+    async fn accept_subscription(&self, id: Id) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let model = Entity::find_by_id(id)
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+
+        if model.status != String::from(Status::Pending) {
+            return Err(Error::NotPending);
+        }
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Update, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(model.user_id);
+
+        let mut active_model: ActiveModel = model.into();
+        active_model.status = Set(Status::Accepted.into());
+        let model = active_model
+            .update(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        model.try_into()
+    }
+
+    async fn reject_subscription(&self, id: Id) -> Result<(), Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let model = Entity::find_by_id(id)
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+
+        if model.status != String::from(Status::Pending) {
+            return Err(Error::NotPending);
+        }
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Delete, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(model.user_id);
+
         Entity::delete_by_id(id)
-            .exec(&self.database_connection)
+            .exec(&transaction)
             .await
-            .map(|_| ())
-            .or(Err(Error::Unknown))
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))
+    }
+
+    async fn list_incoming_subscription_requests(
+        &self,
+        user_id: users::Id,
+    ) -> Result<Vec<Response>, Error> {
+        list_pending(&self.database_connection, Column::UserId, user_id).await
+    }
+
+    async fn list_outgoing_subscription_requests(
+        &self,
+        subscriber_id: users::Id,
+    ) -> Result<Vec<Response>, Error> {
+        list_pending(
+            &self.database_connection,
+            Column::SubscriberId,
+            subscriber_id,
+        )
+        .await
     }
 }
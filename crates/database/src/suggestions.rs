@@ -0,0 +1,100 @@
+use async_trait::async_trait;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter, Statement};
+use uuid::Uuid;
+
+use super::traits::suggestions::{Error, RepositoryTrait, Suggestion};
+use crate::Repository;
+
+// How far the follow graph is walked from the user, friends-of-friends are
+// two hops away. Every further hop multiplies the paths by the fanout, as
+// each one is kept apart to count mutual follows.
+const MAX_DEPTH: i32 = 2;
+
+// The recursive part walks accepted subscriptions outwards, remembering the
+// followed user each path went through and the users visited so far to stop
+// on cycles. Candidates already followed (or requested), blocked in either
+// direction or muted by the user are left out
+const SUGGESTIONS_QUERY: &str = "
+WITH RECURSIVE graph (user_id, via_id, depth, path) AS (
+    SELECT s.user_id, s.user_id, 1, ARRAY[$1, s.user_id]
+    FROM subscriptions s
+    WHERE s.subscriber_id = $1 AND s.status = 'accepted'
+    UNION ALL
+    SELECT s.user_id, g.via_id, g.depth + 1, g.path || s.user_id
+    FROM graph g
+    JOIN subscriptions s ON s.subscriber_id = g.user_id AND s.status = 'accepted'
+    WHERE g.depth < $2 AND NOT s.user_id = ANY(g.path)
+)
+SELECT
+    g.user_id,
+    u.name,
+    COUNT(DISTINCT g.via_id) FILTER (WHERE g.depth = 2) AS mutual_count,
+    MIN(g.depth) AS distance
+FROM graph g
+JOIN users u ON u.id = g.user_id AND u.deleted_at IS NULL
+WHERE g.depth > 1
+    AND NOT EXISTS (
+        SELECT 1 FROM subscriptions s WHERE s.subscriber_id = $1 AND s.user_id = g.user_id
+    )
+    AND NOT EXISTS (
+        SELECT 1 FROM blocks b
+        WHERE (b.user_id = $1 AND b.target_id = g.user_id)
+            OR (b.user_id = g.user_id AND b.target_id = $1 AND b.kind = 'block')
+    )
+GROUP BY g.user_id, u.name
+ORDER BY distance, mutual_count DESC, COUNT(DISTINCT g.via_id) DESC, u.name, g.user_id
+LIMIT $3
+";
+
+#[derive(FromQueryResult)]
+struct QueryResult {
+    user_id: Uuid,
+    name: String,
+    mutual_count: i64,
+    distance: i32,
+}
+
+impl From<QueryResult> for Suggestion {
+    fn from(value: QueryResult) -> Self {
+        Suggestion {
+            user_id: value.user_id,
+            name: value.name,
+            mutual_count: value.mutual_count.try_into().unwrap_or_default(),
+            distance: value.distance.try_into().unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait]
+impl RepositoryTrait for Repository {
+    async fn list_user_suggestions(
+        &self,
+        user_id: Uuid,
+        limit: u64,
+    ) -> Result<Vec<Suggestion>, Error> {
+        entities::users::Entity::find_by_id(user_id)
+            .filter(entities::users::Column::DeletedAt.is_null())
+            .one(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::UserNotFound)?;
+
+        let statement = Statement::from_sql_and_values(
+            self.database_connection.get_database_backend(),
+            SUGGESTIONS_QUERY,
+            [
+                user_id.into(),
+                MAX_DEPTH.into(),
+                i64::try_from(limit).unwrap_or(i64::MAX).into(),
+            ],
+        );
+
+        Ok(QueryResult::find_by_statement(statement)
+            .all(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+}
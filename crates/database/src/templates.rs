@@ -0,0 +1,238 @@
+use async_trait::async_trait;
+use entities::{
+    template_items,
+    templates::{ActiveModel, Column, Entity, Model},
+};
+use sea_orm::{
+    ActiveModelTrait,
+    ColumnTrait,
+    DatabaseTransaction,
+    EntityTrait,
+    QueryFilter,
+    QueryOrder,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use super::traits::{
+    templates::{Error, Id, ItemPayload, ItemResponse, Payload, RepositoryTrait, Response},
+    users,
+    wishlists,
+};
+use crate::{
+    audit::{Action, Entry},
+    Repository,
+};
+
+impl From<template_items::Model> for ItemResponse {
+    fn from(value: template_items::Model) -> Self {
+        ItemResponse {
+            id: value.id,
+            name: value.name,
+            description: value.description,
+            url: value.url,
+            price: value.price,
+            currency: value.currency,
+            quantity: value.quantity,
+            priority: value.priority.into(),
+            position: value.position,
+        }
+    }
+}
+
+impl From<(Model, Vec<template_items::Model>)> for Response {
+    fn from((value, items): (Model, Vec<template_items::Model>)) -> Self {
+        Response {
+            id: value.id,
+            name: value.name,
+            description: value.description,
+            items: items.into_iter().map(Into::into).collect(),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+fn item_model(template_id: Id, position: i32, value: ItemPayload) -> template_items::Model {
+    template_items::Model {
+        id: value.id,
+        template_id,
+        name: value.name,
+        description: value.description,
+        url: value.url,
+        price: value.price,
+        currency: value.currency,
+        quantity: value.quantity,
+        priority: value.priority.into(),
+        position,
+    }
+}
+
+async fn check_admin(transaction: &DatabaseTransaction, user_id: users::Id) -> Result<(), Error> {
+    let is_admin = entities::users::Entity::find_by_id(user_id)
+        .one(transaction)
+        .await
+        .or(Err(Error::Unknown))?
+        .is_some_and(|x| x.is_admin && x.deleted_at.is_none());
+
+    if is_admin {
+        Ok(())
+    } else {
+        Err(Error::NotAdmin)
+    }
+}
+
+#[async_trait]
+impl RepositoryTrait for Repository {
+    async fn create_template(
+        &self,
+        user_id: users::Id,
+        payload: Payload,
+    ) -> Result<Response, Error> {
+        if payload.items.iter().any(|x| x.quantity < 1) {
+            return Err(Error::InvalidQuantity);
+        }
+
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        check_admin(&transaction, user_id).await?;
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Create, payload.id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(user_id);
+
+        let model = ActiveModel::from(Model {
+            id: payload.id,
+            name: payload.name,
+            description: payload.description,
+            created_at: payload.created_at,
+            updated_at: payload.updated_at,
+        })
+        .insert(&transaction)
+        .await
+        .or(Err(Error::Unknown))?;
+
+        let mut items = Vec::with_capacity(payload.items.len());
+        for (position, item) in (0..).zip(payload.items) {
+            let item = template_items::ActiveModel::from(item_model(model.id, position, item))
+                .insert(&transaction)
+                .await
+                .or(Err(Error::Unknown))?;
+            items.push(item);
+        }
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok((model, items).into())
+    }
+
+    async fn get_template(&self, id: Id) -> Result<Option<Response>, Error> {
+        Entity::find_by_id(id)
+            .find_with_related(template_items::Entity)
+            .order_by_asc(template_items::Column::Position)
+            .all(&self.database_connection)
+            .await
+            .map(|x| x.into_iter().next().map(Into::into))
+            .or(Err(Error::Unknown))
+    }
+
+    async fn list_templates(&self) -> Result<Vec<Response>, Error> {
+        Entity::find()
+            .find_with_related(template_items::Entity)
+            .order_by_asc(Column::Name)
+            .order_by_asc(Column::Id)
+            .order_by_asc(template_items::Column::Position)
+            .all(&self.database_connection)
+            .await
+            .map(|x| x.into_iter().map(Into::into).collect())
+            .or(Err(Error::Unknown))
+    }
+
+    async fn delete_template(&self, user_id: users::Id, id: Id) -> Result<(), Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        check_admin(&transaction, user_id).await?;
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Delete, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(user_id);
+
+        let result = Entity::delete_by_id(id)
+            .exec(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        if result.rows_affected == 0 {
+            return Err(Error::NotFound);
+        }
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))
+    }
+
+    async fn instantiate_template(
+        &self,
+        id: Id,
+        wishlist: wishlists::Payload,
+    ) -> Result<wishlists::Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        Entity::find_by_id(id)
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+
+        let items = template_items::Entity::find()
+            .filter(template_items::Column::TemplateId.eq(id))
+            .order_by_asc(template_items::Column::Position)
+            .order_by_asc(template_items::Column::Id)
+            .all(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .into_iter()
+            .map(|item| entities::items::Model {
+                id: Uuid::new_v4(),
+                wishlist_id: wishlist.id,
+                name: item.name,
+                description: item.description,
+                url: item.url,
+                price: item.price,
+                currency: item.currency,
+                quantity: item.quantity,
+                priority: item.priority,
+                position: item.position,
+                is_hidden: false,
+                is_funded: false,
+                picture_id: None,
+                created_at: wishlist.created_at,
+                updated_at: wishlist.updated_at,
+                deleted_at: None,
+                version: 1,
+            })
+            .collect();
+
+        let model = crate::wishlists::insert_with_items(&transaction, wishlist, items)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(model.into())
+    }
+}
@@ -0,0 +1,18 @@
+use chrono::NaiveDateTime;
+pub use sea_orm::JsonValue as Json;
+use uuid::Uuid;
+
+use super::users;
+
+pub type Id = Uuid;
+
+pub struct Response {
+    pub id: Id,
+    pub actor_id: Option<users::Id>,
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub action: String,
+    pub before: Option<Json>,
+    pub after: Option<Json>,
+    pub created_at: NaiveDateTime,
+}
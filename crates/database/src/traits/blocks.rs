@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::users;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown error")]
+    Unknown,
+    #[error("User not found")]
+    UserNotFound,
+    #[error("Users can't block or mute themselves")]
+    SelfBlock,
+    #[error("User is already blocked or muted")]
+    AlreadyExists,
+    #[error("User is not blocked or muted")]
+    NotFound,
+}
+
+pub type Id = Uuid;
+
+// Blocking cuts every tie between two users, muting only silences the target
+// in the feed and notifications of the user
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Block,
+    Mute,
+}
+
+pub struct Payload {
+    pub id: Id,
+    pub user_id: users::Id,
+    pub target_id: users::Id,
+    pub kind: Kind,
+    pub created_at: NaiveDateTime,
+}
+
+pub struct Response {
+    pub id: Id,
+    pub user_id: users::Id,
+    pub target_id: users::Id,
+    pub kind: Kind,
+    pub created_at: NaiveDateTime,
+}
+
+#[async_trait]
+pub trait RepositoryTrait {
+    async fn create_block(&self, payload: Payload) -> Result<Response, Error>;
+    async fn delete_block(
+        &self,
+        user_id: users::Id,
+        target_id: users::Id,
+        kind: Kind,
+    ) -> Result<(), Error>;
+    async fn list_user_blocks(
+        &self,
+        user_id: users::Id,
+        kind: Kind,
+    ) -> Result<Vec<Response>, Error>;
+    async fn is_blocked(&self, user_id: users::Id, other_id: users::Id) -> Result<bool, Error>;
+}
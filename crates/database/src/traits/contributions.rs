@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{items, users};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown error")]
+    Unknown,
+    #[error("Item not found")]
+    ItemNotFound,
+    #[error("Item is already fully funded")]
+    ItemFunded,
+    #[error("Contributed amount must be positive")]
+    InvalidAmount,
+    #[error("Wishlist is archived")]
+    WishlistArchived,
+    #[error("Currency does not match the item's")]
+    CurrencyMismatch,
+    #[error("User is blocked by the owner of the wishlist")]
+    Blocked,
+}
+
+pub type Id = Uuid;
+
+pub struct Payload {
+    pub id: Id,
+    pub item_id: items::Id,
+    pub user_id: users::Id,
+    pub amount: i64,
+    // Defaults to the currency of the item
+    pub currency: Option<String>,
+    pub note: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+pub struct Response {
+    pub id: Id,
+    pub item_id: items::Id,
+    pub user_id: Option<users::Id>,
+    pub amount: i64,
+    pub currency: String,
+    pub note: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[async_trait]
+pub trait RepositoryTrait {
+    async fn create_contribution(&self, payload: Payload) -> Result<Response, Error>;
+    async fn get_contribution(&self, id: Id) -> Result<Option<Response>, Error>;
+    async fn delete_contribution(&self, id: Id, actor_id: users::Id) -> Result<(), Error>;
+}
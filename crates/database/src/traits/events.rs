@@ -0,0 +1,37 @@
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use futures::Stream;
+use thiserror::Error;
+
+use super::{items, wishlists};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown error")]
+    Unknown,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Created,
+    Updated,
+    Deleted,
+    Reserved,
+    Unreserved,
+}
+
+#[derive(Clone)]
+pub struct Event {
+    pub kind: Kind,
+    pub wishlist_id: wishlists::Id,
+    pub item_id: items::Id,
+    pub is_hidden: bool,
+}
+
+pub type EventStream = Pin<Box<dyn Stream<Item = Result<Event, Error>> + Send>>;
+
+#[async_trait]
+pub trait RepositoryTrait {
+    async fn listen_item_events(&self) -> Result<EventStream, Error>;
+}
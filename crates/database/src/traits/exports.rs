@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+use thiserror::Error;
+
+use super::{contributions, items, reservations, subscriptions, users, wishlists};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown error")]
+    Unknown,
+    #[error("User not found")]
+    UserNotFound,
+}
+
+// Everything stored about a user, blobs are referenced by their keys
+pub struct Export {
+    pub user: users::Response,
+    pub wishlists: Vec<wishlists::Response>,
+    pub items: Vec<items::Response>,
+    pub subscriptions: Vec<subscriptions::Response>,
+    pub reservations: Vec<reservations::Response>,
+    pub contributions: Vec<contributions::Response>,
+}
+
+#[async_trait]
+pub trait RepositoryTrait {
+    async fn export_user(&self, id: users::Id) -> Result<Export, Error>;
+}
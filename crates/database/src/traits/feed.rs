@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{items, users, wishlists};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown error")]
+    Unknown,
+    #[error("User not found")]
+    UserNotFound,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    WishlistCreated,
+    ItemAdded,
+}
+
+// Entries are ordered by creation time, the id breaks ties between entries created at once
+#[derive(Clone, Copy)]
+pub struct Cursor {
+    pub created_at: NaiveDateTime,
+    pub id: Uuid,
+}
+
+pub struct Page {
+    pub before: Option<Cursor>,
+    pub limit: u64,
+}
+
+pub struct Entry {
+    pub kind: Kind,
+    pub user_id: users::Id,
+    pub wishlist_id: wishlists::Id,
+    pub wishlist_name: String,
+    pub item_id: Option<items::Id>,
+    pub item_name: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl Entry {
+    #[must_use]
+    pub fn cursor(&self) -> Cursor {
+        Cursor {
+            created_at: self.created_at,
+            id: self.item_id.unwrap_or(self.wishlist_id),
+        }
+    }
+}
+
+#[async_trait]
+pub trait RepositoryTrait {
+    async fn list_user_feed(&self, user_id: users::Id, page: Page) -> Result<Vec<Entry>, Error>;
+}
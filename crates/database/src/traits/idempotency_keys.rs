@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use thiserror::Error;
+
+use super::audit::Json;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown error")]
+    Unknown,
+}
+
+pub struct Payload {
+    pub endpoint: String,
+    pub key: String,
+    pub request_hash: String,
+    pub status_code: i16,
+    pub response: Json,
+    pub expires_at: NaiveDateTime,
+}
+
+pub struct Response {
+    pub request_hash: String,
+    pub status_code: i16,
+    pub response: Json,
+}
+
+#[async_trait]
+pub trait RepositoryTrait {
+    // Holds the key until the surrounding unit of work ends, so a retry racing
+    // the original request waits for its response instead of repeating it
+    async fn lock_idempotency_key(
+        &self,
+        endpoint: &str,
+        key: &str,
+    ) -> Result<Option<Response>, Error>;
+    async fn save_idempotency_key(&self, payload: Payload) -> Result<(), Error>;
+    async fn purge_idempotency_keys(&self, before: NaiveDateTime) -> Result<u64, Error>;
+}
@@ -16,5 +16,6 @@ pub enum Error {
 pub trait RepositoryTrait {
     async fn get_item_picture(&self, key: Key) -> Result<Value, Error>;
     async fn put_item_picture(&self, key: Key, value: Value) -> Result<(), Error>;
+    async fn copy_item_picture(&self, source: Key, destination: Key) -> Result<(), Error>;
     async fn delete_item_picture(&self, key: Key) -> Result<(), Error>;
 }
@@ -3,25 +3,70 @@ use chrono::NaiveDateTime;
 use thiserror::Error;
 use uuid::Uuid;
 
-use super::{item_pictures, users, wishlists};
+use super::{contributions, item_pictures, reservations, users, wishlists, Precondition};
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Unknown error")]
     Unknown,
+    #[error("Quantity must be positive")]
+    InvalidQuantity,
+    #[error("Quantity is less than already reserved")]
+    QuantityBelowReserved,
+    #[error("Item not found")]
+    NotFound,
+    #[error("Wishlist not found")]
+    WishlistNotFound,
+    #[error("Wishlist is not owned by the user")]
+    NotOwner,
+    #[error("Wishlist is archived")]
+    WishlistArchived,
+    #[error("Version does not match")]
+    VersionMismatch,
 }
 
 pub type Id = Uuid;
+pub type Version = i32;
 pub type Predicate = String;
 
+#[derive(Clone, Copy, Default)]
+pub enum Priority {
+    NiceToHave,
+    #[default]
+    Normal,
+    MustHave,
+}
+
+#[derive(Default)]
+pub enum Sort {
+    #[default]
+    Default,
+    PriceAsc,
+    PriceDesc,
+    Priority,
+}
+
+#[derive(Default)]
+pub struct Filter {
+    pub predicate: Option<Predicate>,
+    pub currency: Option<String>,
+    pub min_price: Option<i64>,
+    pub max_price: Option<i64>,
+    pub sort: Sort,
+}
+
 pub struct Payload {
     pub id: Id,
     pub wishlist_id: wishlists::Id,
-    pub selected_by_id: Option<users::Id>,
     pub name: String,
     pub description: Option<String>,
-    pub price: Option<i32>,
+    pub url: Option<String>,
+    pub price: Option<i64>,
+    pub currency: Option<String>,
+    pub quantity: i32,
+    pub priority: Priority,
     pub is_hidden: bool,
+    pub is_funded: bool,
     pub picture_id: Option<item_pictures::Key>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -30,21 +75,143 @@ pub struct Payload {
 pub struct Response {
     pub id: Id,
     pub wishlist_id: wishlists::Id,
-    pub selected_by_id: Option<users::Id>,
     pub name: String,
     pub description: Option<String>,
-    pub price: Option<i32>,
+    pub url: Option<String>,
+    pub price: Option<i64>,
+    pub currency: Option<String>,
+    pub quantity: i32,
+    pub reserved_quantity: i32,
+    pub contributed_amount: i64,
+    pub priority: Priority,
+    pub position: i32,
     pub is_hidden: bool,
+    pub is_funded: bool,
     pub picture_id: Option<item_pictures::Key>,
+    pub version: Version,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
 
+pub struct Update {
+    pub name: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub price: Option<i64>,
+    pub currency: Option<String>,
+    pub quantity: i32,
+    pub priority: Option<Priority>,
+    pub is_hidden: bool,
+    pub is_funded: bool,
+}
+
+// Absent fields are kept, the inner `None` of nullable ones clears them. A new
+// price comes with `currency`, the owner's one when that is missing.
+#[derive(Default)]
+pub struct Patch {
+    pub name: Option<String>,
+    pub description: Option<Option<String>>,
+    pub url: Option<Option<String>>,
+    pub price: Option<Option<i64>>,
+    pub currency: Option<String>,
+    pub quantity: Option<i32>,
+    pub priority: Option<Priority>,
+    pub is_hidden: Option<bool>,
+    pub is_funded: Option<bool>,
+}
+
+pub enum Operation {
+    Create(Payload),
+    Update(Id, Update),
+    Delete(Id),
+}
+
+pub enum Outcome {
+    Created(Response),
+    Updated { item: Response, url_changed: bool },
+    Deleted(Id),
+}
+
+// Index of the operation that failed, if the batch got that far
+#[derive(Debug, Error)]
+#[error("{error}")]
+pub struct BatchError {
+    pub index: Option<usize>,
+    pub error: Error,
+}
+
 #[async_trait]
 pub trait RepositoryTrait {
-    async fn create_item(&self, payload: Payload) -> Result<Response, Error>;
+    async fn create_item(
+        &self,
+        payload: Payload,
+        actor_id: Option<users::Id>,
+    ) -> Result<Response, Error>;
     async fn get_item(&self, id: Id) -> Result<Option<Response>, Error>;
-    async fn list_items(&self, predicate: Option<Predicate>) -> Result<Vec<Response>, Error>;
-    async fn update_item(&self, id: Id, payload: Payload) -> Result<Response, Error>;
-    async fn delete_item(&self, id: Id) -> Result<(), Error>;
+    async fn get_visible_item(
+        &self,
+        id: Id,
+        viewer_id: users::Id,
+    ) -> Result<Option<Response>, Error>;
+    async fn list_items(
+        &self,
+        viewer_id: users::Id,
+        filter: Filter,
+    ) -> Result<Vec<Response>, Error>;
+    async fn update_item(
+        &self,
+        id: Id,
+        payload: Payload,
+        version: Option<Precondition>,
+        actor_id: Option<users::Id>,
+    ) -> Result<Response, Error>;
+    async fn patch_item(
+        &self,
+        id: Id,
+        patch: Patch,
+        version: Option<Precondition>,
+        actor_id: Option<users::Id>,
+    ) -> Result<Response, Error>;
+    async fn delete_item(
+        &self,
+        id: Id,
+        version: Option<Precondition>,
+        actor_id: Option<users::Id>,
+    ) -> Result<(), Error>;
+    async fn batch_items(
+        &self,
+        operations: Vec<Operation>,
+        actor_id: Option<users::Id>,
+    ) -> Result<Vec<Outcome>, BatchError>;
+    async fn restore_item(&self, id: Id, user_id: users::Id) -> Result<Response, Error>;
+    async fn reorder_item(
+        &self,
+        id: Id,
+        position: i32,
+        user_id: users::Id,
+    ) -> Result<Response, Error>;
+    async fn move_item(
+        &self,
+        id: Id,
+        user_id: users::Id,
+        wishlist_id: wishlists::Id,
+    ) -> Result<Response, Error>;
+    // Copies the picture too, which a surrounding unit of work can't remove
+    async fn copy_item(
+        &self,
+        id: Id,
+        user_id: users::Id,
+        wishlist_id: wishlists::Id,
+    ) -> Result<Response, Error>;
+
+    async fn list_item_reservations(
+        &self,
+        id: Id,
+        viewer_id: users::Id,
+    ) -> Result<Vec<reservations::Response>, Error>;
+    async fn list_item_contributions(
+        &self,
+        id: Id,
+        viewer_id: users::Id,
+    ) -> Result<Vec<contributions::Response>, Error>;
 }
@@ -1,6 +1,28 @@
+pub mod audit;
+pub mod blocks;
+pub mod contributions;
+pub mod events;
+pub mod exports;
+pub mod feed;
+pub mod idempotency_keys;
 pub mod item_pictures;
 pub mod items;
+pub mod notifications;
+pub mod purge;
+pub mod reservations;
 pub mod subscriptions;
+pub mod suggestions;
+pub mod templates;
+pub mod transactions;
 pub mod user_avatars;
 pub mod users;
+pub mod webhooks;
 pub mod wishlists;
+
+// Condition a write puts on the stored version of a row
+#[derive(Clone, Copy)]
+pub enum Precondition {
+    // The row only has to exist, whatever its version
+    Exists,
+    Version(i32),
+}
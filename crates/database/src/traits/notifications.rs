@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{items, users, wishlists};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown error")]
+    Unknown,
+    #[error("Notification not found")]
+    NotFound,
+}
+
+pub type Id = Uuid;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    WishlistCreated,
+    ItemAdded,
+    ItemReserved,
+}
+
+#[derive(Default)]
+pub struct Filter {
+    pub unread: bool,
+}
+
+pub struct Response {
+    pub id: Id,
+    pub user_id: users::Id,
+    pub kind: Kind,
+    pub actor_id: Option<users::Id>,
+    pub wishlist_id: Option<wishlists::Id>,
+    pub item_id: Option<items::Id>,
+    pub is_read: bool,
+    pub created_at: NaiveDateTime,
+}
+
+#[async_trait]
+pub trait RepositoryTrait {
+    async fn get_notification(&self, id: Id) -> Result<Option<Response>, Error>;
+    async fn list_user_notifications(
+        &self,
+        user_id: users::Id,
+        filter: Filter,
+    ) -> Result<Vec<Response>, Error>;
+    async fn mark_notification(&self, id: Id, is_read: bool) -> Result<Response, Error>;
+    async fn mark_user_notifications(&self, user_id: users::Id) -> Result<u64, Error>;
+    // Claimed notifications aren't handed out again until the claim runs out
+    async fn claim_undelivered_notifications(
+        &self,
+        limit: u64,
+        claim: Duration,
+    ) -> Result<Vec<Response>, Error>;
+    async fn mark_notifications_delivered(&self, ids: Vec<Id>) -> Result<(), Error>;
+}
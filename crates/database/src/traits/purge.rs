@@ -0,0 +1,21 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown error")]
+    Unknown,
+}
+
+pub struct Response {
+    pub users: u64,
+    pub wishlists: u64,
+    pub items: u64,
+    pub blobs: u64,
+}
+
+#[async_trait]
+pub trait RepositoryTrait {
+    async fn purge_deleted(&self, before: NaiveDateTime) -> Result<Response, Error>;
+}
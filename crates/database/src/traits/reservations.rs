@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{items, users};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown error")]
+    Unknown,
+    #[error("Item not found")]
+    ItemNotFound,
+    #[error("Reserved quantity must be positive")]
+    InvalidQuantity,
+    #[error("Not enough quantity left to reserve")]
+    InsufficientQuantity,
+    #[error("Wishlist is archived")]
+    WishlistArchived,
+    #[error("User is blocked by the owner of the wishlist")]
+    Blocked,
+}
+
+pub type Id = Uuid;
+
+pub struct Payload {
+    pub id: Id,
+    pub item_id: items::Id,
+    pub user_id: users::Id,
+    pub quantity: i32,
+    pub created_at: NaiveDateTime,
+}
+
+pub struct Response {
+    pub id: Id,
+    pub item_id: items::Id,
+    pub user_id: Option<users::Id>,
+    pub quantity: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[async_trait]
+pub trait RepositoryTrait {
+    async fn create_reservation(&self, payload: Payload) -> Result<Response, Error>;
+    async fn get_reservation(&self, id: Id) -> Result<Option<Response>, Error>;
+    async fn delete_reservation(&self, id: Id, actor_id: users::Id) -> Result<(), Error>;
+}
@@ -7,6 +7,13 @@ use super::users;
 
 pub type Id = Uuid;
 
+// Subscriptions to private accounts stay pending until the followed user accepts them
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pending,
+    Accepted,
+}
+
 pub struct Payload {
     pub id: Id,
     pub user_id: users::Id,
@@ -18,6 +25,7 @@ pub struct Response {
     pub id: Id,
     pub user_id: users::Id,
     pub subscriber_id: users::Id,
+    pub status: Status,
     pub created_at: NaiveDateTime,
 }
 
@@ -25,10 +33,33 @@ pub struct Response {
 pub enum Error {
     #[error("Unknown error")]
     Unknown,
+    #[error("Subscription not found")]
+    NotFound,
+    #[error("User not found")]
+    UserNotFound,
+    #[error("Users can't subscribe to themselves")]
+    SelfSubscription,
+    #[error("Subscription already exists")]
+    AlreadyExists,
+    #[error("Subscription is not pending")]
+    NotPending,
+    #[error("User is blocked")]
+    Blocked,
 }
 
 #[async_trait]
 pub trait RepositoryTrait {
     async fn create_subscription(&self, payload: Payload) -> Result<Response, Error>;
+    async fn get_subscription(&self, id: Id) -> Result<Option<Response>, Error>;
     async fn delete_subscription(&self, id: Id) -> Result<(), Error>;
+    async fn accept_subscription(&self, id: Id) -> Result<Response, Error>;
+    async fn reject_subscription(&self, id: Id) -> Result<(), Error>;
+    async fn list_incoming_subscription_requests(
+        &self,
+        user_id: users::Id,
+    ) -> Result<Vec<Response>, Error>;
+    async fn list_outgoing_subscription_requests(
+        &self,
+        subscriber_id: users::Id,
+    ) -> Result<Vec<Response>, Error>;
 }
@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+use thiserror::Error;
+
+use super::users;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown error")]
+    Unknown,
+    #[error("User not found")]
+    UserNotFound,
+}
+
+pub struct Suggestion {
+    pub user_id: users::Id,
+    pub name: String,
+    // Number of followed users who follow the suggested user themselves
+    pub mutual_count: u64,
+    // Length of the shortest follow path to the suggested user
+    pub distance: u32,
+}
+
+#[async_trait]
+pub trait RepositoryTrait {
+    async fn list_user_suggestions(
+        &self,
+        user_id: users::Id,
+        limit: u64,
+    ) -> Result<Vec<Suggestion>, Error>;
+}
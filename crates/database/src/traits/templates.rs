@@ -0,0 +1,80 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{items, users, wishlists};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown error")]
+    Unknown,
+    #[error("Template not found")]
+    NotFound,
+    #[error("User is not an administrator")]
+    NotAdmin,
+    #[error("Quantity must be positive")]
+    InvalidQuantity,
+}
+
+pub type Id = Uuid;
+pub type ItemId = Uuid;
+
+pub struct ItemPayload {
+    pub id: ItemId,
+    pub name: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub price: Option<i64>,
+    pub currency: Option<String>,
+    pub quantity: i32,
+    pub priority: items::Priority,
+}
+
+pub struct Payload {
+    pub id: Id,
+    pub name: String,
+    pub description: Option<String>,
+    pub items: Vec<ItemPayload>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+pub struct ItemResponse {
+    pub id: ItemId,
+    pub name: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub price: Option<i64>,
+    pub currency: Option<String>,
+    pub quantity: i32,
+    pub priority: items::Priority,
+    pub position: i32,
+}
+
+pub struct Response {
+    pub id: Id,
+    pub name: String,
+    pub description: Option<String>,
+    pub items: Vec<ItemResponse>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[async_trait]
+pub trait RepositoryTrait {
+    async fn create_template(
+        &self,
+        user_id: users::Id,
+        payload: Payload,
+    ) -> Result<Response, Error>;
+    async fn get_template(&self, id: Id) -> Result<Option<Response>, Error>;
+    async fn list_templates(&self) -> Result<Vec<Response>, Error>;
+    async fn delete_template(&self, user_id: users::Id, id: Id) -> Result<(), Error>;
+
+    async fn instantiate_template(
+        &self,
+        id: Id,
+        wishlist: wishlists::Payload,
+    ) -> Result<wishlists::Response, Error>;
+}
@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown error")]
+    Unknown,
+}
+
+// Every operation of the repository handed out goes through the transaction,
+// none of their changes is visible elsewhere before the commit
+#[async_trait]
+pub trait Transaction: Send + Sync {
+    fn repository(&self) -> &(dyn crate::RepositoryTrait + Send + Sync);
+    async fn commit(self: Box<Self>) -> Result<(), Error>;
+    async fn rollback(self: Box<Self>) -> Result<(), Error>;
+}
+
+#[async_trait]
+pub trait RepositoryTrait {
+    async fn begin_transaction(&self) -> Result<Box<dyn Transaction>, Error>;
+}
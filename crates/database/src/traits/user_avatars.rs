@@ -14,7 +14,7 @@ pub enum Error {
 
 #[async_trait]
 pub trait RepositoryTrait {
-    async fn get_item_picture(&self, key: Key) -> Result<Value, Error>;
-    async fn put_item_picture(&self, key: Key, value: Value) -> Result<(), Error>;
-    async fn delete_item_picture(&self, key: Key) -> Result<(), Error>;
+    async fn get_user_avatar(&self, key: Key) -> Result<Value, Error>;
+    async fn put_user_avatar(&self, key: Key, value: Value) -> Result<(), Error>;
+    async fn delete_user_avatar(&self, key: Key) -> Result<(), Error>;
 }
@@ -1,31 +1,68 @@
 use async_trait::async_trait;
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use thiserror::Error;
 use uuid::Uuid;
 
-use super::{user_avatars, wishlists};
+use super::{user_avatars, wishlists, Precondition};
 
 pub type Id = Uuid;
+pub type Version = i32;
 pub type Predicate = String;
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Unknown error")]
     Unknown,
+    #[error("User not found")]
+    NotFound,
+    #[error("Username is already taken")]
+    UsernameTaken,
+    #[error("Version does not match")]
+    VersionMismatch,
 }
 
 pub struct Payload {
     pub id: Id,
     pub name: String,
     pub avatar_id: Option<user_avatars::Key>,
+    pub currency: String,
+    pub email: Option<String>,
+    pub is_admin: bool,
+    pub is_private: bool,
+    pub username: Option<String>,
+    pub bio: Option<String>,
+    pub birthday: Option<NaiveDate>,
+    pub locale: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
 
+// Absent fields are kept, the inner `None` of nullable ones clears them
+#[derive(Default)]
+pub struct Patch {
+    pub name: Option<String>,
+    pub currency: Option<String>,
+    pub email: Option<Option<String>>,
+    pub is_private: Option<bool>,
+    pub username: Option<Option<String>>,
+    pub bio: Option<Option<String>>,
+    pub birthday: Option<Option<NaiveDate>>,
+    pub locale: Option<Option<String>>,
+}
+
 pub struct Response {
     pub id: Id,
     pub name: String,
     pub avatar_id: Option<user_avatars::Key>,
+    pub currency: String,
+    pub email: Option<String>,
+    pub is_admin: bool,
+    pub is_private: bool,
+    pub username: Option<String>,
+    pub bio: Option<String>,
+    pub birthday: Option<NaiveDate>,
+    pub locale: Option<String>,
+    pub version: Version,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -34,13 +71,31 @@ pub struct Response {
 pub trait RepositoryTrait {
     async fn create_user(&self, payload: Payload) -> Result<Response, Error>;
     async fn get_user(&self, id: Id) -> Result<Option<Response>, Error>;
+    async fn get_user_by_username(&self, username: String) -> Result<Option<Response>, Error>;
+    async fn is_username_available(&self, username: String) -> Result<bool, Error>;
     async fn list_users(&self, predicate: Option<Predicate>) -> Result<Vec<Response>, Error>;
-    async fn update_user(&self, id: Id, payload: Payload) -> Result<Response, Error>;
-    async fn delete_user(&self, id: Id) -> Result<(), Error>;
+    async fn update_user(
+        &self,
+        id: Id,
+        payload: Payload,
+        version: Option<Precondition>,
+    ) -> Result<Response, Error>;
+    async fn patch_user(
+        &self,
+        id: Id,
+        patch: Patch,
+        version: Option<Precondition>,
+    ) -> Result<Response, Error>;
+    async fn delete_user(&self, id: Id, version: Option<Precondition>) -> Result<(), Error>;
+    async fn restore_user(&self, id: Id) -> Result<Response, Error>;
+    // Deletes the pictures and the avatar of the user too, which a surrounding
+    // unit of work can't bring back
+    async fn erase_user(&self, id: Id, version: Version) -> Result<(), Error>;
 
     async fn list_user_wishlists(
         &self,
         id: Id,
+        viewer_id: Id,
         predicate: Option<wishlists::Predicate>,
     ) -> Result<Vec<wishlists::Response>, Error>;
 
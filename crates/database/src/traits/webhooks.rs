@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{audit::Json, wishlists};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown error")]
+    Unknown,
+    #[error("Webhook not found")]
+    NotFound,
+    #[error("Wishlist not found")]
+    WishlistNotFound,
+    #[error("Delivery not found")]
+    DeliveryNotFound,
+}
+
+pub type Id = Uuid;
+pub type DeliveryId = Uuid;
+
+pub struct Payload {
+    pub id: Id,
+    pub wishlist_id: wishlists::Id,
+    pub url: String,
+    pub secret: String,
+}
+
+pub struct Response {
+    pub id: Id,
+    pub wishlist_id: wishlists::Id,
+    pub url: String,
+    pub secret: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+pub struct DeliveryResponse {
+    pub id: DeliveryId,
+    pub webhook_id: Id,
+    pub event: String,
+    pub payload: Json,
+    pub status: Status,
+    pub attempts: i32,
+    pub next_attempt_at: NaiveDateTime,
+    pub last_status_code: Option<i16>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+// A failed attempt without a retry time marks the delivery as failed for good
+pub struct Attempt {
+    pub status_code: Option<i16>,
+    pub error: Option<String>,
+    pub retry_at: Option<NaiveDateTime>,
+}
+
+#[async_trait]
+pub trait RepositoryTrait {
+    async fn create_webhook(&self, payload: Payload) -> Result<Response, Error>;
+    async fn get_webhook(&self, id: Id) -> Result<Option<Response>, Error>;
+    async fn list_wishlist_webhooks(
+        &self,
+        wishlist_id: wishlists::Id,
+    ) -> Result<Vec<Response>, Error>;
+    async fn delete_webhook(&self, id: Id) -> Result<(), Error>;
+    async fn list_webhook_deliveries(&self, id: Id) -> Result<Vec<DeliveryResponse>, Error>;
+    // Claimed deliveries aren't due again until the claim runs out, unless an
+    // attempt is recorded first
+    async fn claim_due_webhook_deliveries(
+        &self,
+        limit: u64,
+        claim: Duration,
+    ) -> Result<Vec<DeliveryResponse>, Error>;
+    async fn record_webhook_attempt(
+        &self,
+        id: DeliveryId,
+        attempt: Attempt,
+    ) -> Result<DeliveryResponse, Error>;
+}
@@ -1,31 +1,61 @@
 use async_trait::async_trait;
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use thiserror::Error;
 use uuid::Uuid;
 
-use super::{items, users};
+use super::{audit, items, users, Precondition};
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Unknown error")]
     Unknown,
+    #[error("Wishlist not found")]
+    NotFound,
+    #[error("User not found")]
+    UserNotFound,
+    #[error("Version does not match")]
+    VersionMismatch,
+    #[error("Wishlist is archived")]
+    Archived,
 }
 
 pub type Id = Uuid;
+pub type Version = i32;
 pub type Predicate = String;
 
+#[derive(Default)]
+pub struct Filter {
+    pub predicate: Option<Predicate>,
+    pub event_type: Option<String>,
+    pub upcoming: bool,
+}
+
 pub struct Payload {
     pub id: Id,
     pub name: String,
     pub user_id: users::Id,
+    pub event_type: Option<String>,
+    pub event_date: Option<NaiveDate>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
 
+// Absent fields are kept, the inner `None` of nullable ones clears them
+#[derive(Default)]
+pub struct Patch {
+    pub name: Option<String>,
+    pub event_type: Option<Option<String>>,
+    pub event_date: Option<Option<NaiveDate>>,
+}
+
 pub struct Response {
     pub id: Id,
     pub name: String,
     pub user_id: users::Id,
+    pub event_type: Option<String>,
+    pub event_date: Option<NaiveDate>,
+    pub is_archived: bool,
+    pub version: Version,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -34,13 +64,50 @@ pub struct Response {
 pub trait RepositoryTrait {
     async fn create_wishlist(&self, payload: Payload) -> Result<Response, Error>;
     async fn get_wishlist(&self, id: Id) -> Result<Option<Response>, Error>;
-    async fn list_wishlists(&self, predicate: Option<Predicate>) -> Result<Vec<Response>, Error>;
-    async fn update_wishlist(&self, id: Id, payload: Payload) -> Result<Response, Error>;
-    async fn delete_wishlist(&self, id: Id) -> Result<(), Error>;
+    async fn get_visible_wishlist(
+        &self,
+        id: Id,
+        viewer_id: users::Id,
+    ) -> Result<Option<Response>, Error>;
+    async fn list_wishlists(
+        &self,
+        viewer_id: users::Id,
+        filter: Filter,
+    ) -> Result<Vec<Response>, Error>;
+    async fn update_wishlist(
+        &self,
+        id: Id,
+        payload: Payload,
+        version: Option<Precondition>,
+        actor_id: users::Id,
+    ) -> Result<Response, Error>;
+    async fn patch_wishlist(
+        &self,
+        id: Id,
+        patch: Patch,
+        version: Option<Precondition>,
+        actor_id: users::Id,
+    ) -> Result<Response, Error>;
+    async fn delete_wishlist(
+        &self,
+        id: Id,
+        version: Option<Precondition>,
+        actor_id: users::Id,
+    ) -> Result<(), Error>;
+    async fn restore_wishlist(&self, id: Id, actor_id: users::Id) -> Result<Response, Error>;
+    // Copies the pictures too, which a surrounding unit of work can't remove
+    async fn clone_wishlist(&self, id: Id, payload: Payload) -> Result<Response, Error>;
+    async fn import_wishlist(
+        &self,
+        payload: Payload,
+        items: Vec<items::Payload>,
+    ) -> Result<Response, Error>;
 
     async fn list_wishlist_items(
         &self,
         id: Id,
-        predicate: Option<items::Predicate>,
+        viewer_id: users::Id,
+        filter: items::Filter,
     ) -> Result<Vec<items::Response>, Error>;
+    async fn list_wishlist_history(&self, id: Id) -> Result<Vec<audit::Response>, Error>;
 }
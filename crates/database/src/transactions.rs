@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sea_orm::{DatabaseTransaction, TransactionTrait};
+
+use super::traits::transactions::{Error, RepositoryTrait, Transaction};
+use crate::{connection::Connection, Repository};
+
+struct UnitOfWork {
+    repository: Repository,
+}
+
+impl UnitOfWork {
+    // The repository is the only holder of the transaction, so it can be taken
+    // back once the repository is given up
+    fn into_transaction(self) -> Result<DatabaseTransaction, Error> {
+        match self.repository.database_connection {
+            Connection::Transaction(_, transaction) => {
+                Arc::try_unwrap(transaction).or(Err(Error::Unknown))
+            }
+            Connection::Pool(_) => Err(Error::Unknown),
+        }
+    }
+}
+
+#[async_trait]
+impl Transaction for UnitOfWork {
+    fn repository(&self) -> &(dyn crate::RepositoryTrait + Send + Sync) {
+        &self.repository
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), Error> {
+        self.into_transaction()?
+            .commit()
+            .await
+            .or(Err(Error::Unknown))
+    }
+
+    async fn rollback(self: Box<Self>) -> Result<(), Error> {
+        self.into_transaction()?
+            .rollback()
+            .await
+            .or(Err(Error::Unknown))
+    }
+}
+
+#[async_trait]
+impl RepositoryTrait for Repository {
+    async fn begin_transaction(&self) -> Result<Box<dyn Transaction>, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        Ok(Box::new(UnitOfWork {
+            repository: Repository {
+                database_connection: Connection::Transaction(
+                    self.database_connection.pool().clone(),
+                    Arc::new(transaction),
+                ),
+                blob_storage_client: self.blob_storage_client.clone(),
+                blob_storage_bucket: self.blob_storage_bucket.clone(),
+            },
+        }))
+    }
+}
@@ -6,9 +6,10 @@ use crate::Repository;
 
 #[async_trait]
 impl RepositoryTrait for Repository {
-    async fn get_item_picture(&self, key: Key) -> Result<Value, Error> {
+    async fn get_user_avatar(&self, key: Key) -> Result<Value, Error> {
         self.blob_storage_client
             .get_object()
+            .bucket(&self.blob_storage_bucket)
             .key(key.to_string())
             .send()
             .await
@@ -16,9 +17,10 @@ impl RepositoryTrait for Repository {
             .or(Err(Error::Unknown))
     }
 
-    async fn put_item_picture(&self, key: Key, value: ByteStream) -> Result<(), Error> {
+    async fn put_user_avatar(&self, key: Key, value: ByteStream) -> Result<(), Error> {
         self.blob_storage_client
             .put_object()
+            .bucket(&self.blob_storage_bucket)
             .key(key.to_string())
             .body(value)
             .send()
@@ -27,9 +29,10 @@ impl RepositoryTrait for Repository {
             .or(Err(Error::Unknown))
     }
 
-    async fn delete_item_picture(&self, key: Key) -> Result<(), Error> {
+    async fn delete_user_avatar(&self, key: Key) -> Result<(), Error> {
         self.blob_storage_client
             .delete_object()
+            .bucket(&self.blob_storage_bucket)
             .key(key.to_string())
             .send()
             .await
@@ -1,13 +1,40 @@
 use async_trait::async_trait;
+use chrono::Utc;
 use entities::users::{ActiveModel, Column, Entity, Model};
-use migrations::{Expr, Query};
-use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder};
+use migrations::{Expr, Func, Query, SimpleExpr};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set, Unchanged},
+    ColumnTrait,
+    Condition,
+    DatabaseTransaction,
+    DbErr,
+    EntityName,
+    EntityTrait,
+    PaginatorTrait,
+    QueryFilter,
+    QueryOrder,
+    QuerySelect,
+    SqlErr,
+    TransactionTrait,
+    Value,
+};
+use uuid::Uuid;
 
 use super::traits::{
-    users::{Error, Id, Payload, Predicate, RepositoryTrait, Response},
+    item_pictures::RepositoryTrait as _,
+    subscriptions,
+    user_avatars::RepositoryTrait as _,
+    users::{Error, Id, Patch, Payload, Predicate, RepositoryTrait, Response, Version},
     wishlists,
+    Precondition,
+};
+use crate::{
+    audit::{redact_owned, redact_user, Action, Entry},
+    check_version,
+    set_present,
+    Repository,
 };
-use crate::Repository;
 
 impl From<Payload> for Model {
     fn from(value: Payload) -> Self {
@@ -15,8 +42,18 @@ impl From<Payload> for Model {
             id: value.id,
             name: value.name,
             avatar_id: value.avatar_id,
+            currency: value.currency,
+            email: value.email,
+            is_admin: value.is_admin,
+            is_private: value.is_private,
+            username: value.username,
+            bio: value.bio,
+            birthday: value.birthday,
+            locale: value.locale,
             created_at: value.created_at,
             updated_at: value.updated_at,
+            deleted_at: None,
+            version: 1,
         }
     }
 }
@@ -27,37 +64,113 @@ impl From<Model> for Response {
             id: value.id,
             name: value.name,
             avatar_id: value.avatar_id,
+            currency: value.currency,
+            email: value.email,
+            is_admin: value.is_admin,
+            is_private: value.is_private,
+            username: value.username,
+            bio: value.bio,
+            birthday: value.birthday,
+            locale: value.locale,
+            version: value.version,
             created_at: value.created_at,
             updated_at: value.updated_at,
         }
     }
 }
 
+// Public accounts don't review their followers, so pending requests go through
+async fn accept_pending(transaction: &DatabaseTransaction, id: Id) -> Result<(), DbErr> {
+    entities::subscriptions::Entity::update_many()
+        .col_expr(
+            entities::subscriptions::Column::Status,
+            Expr::value(String::from(subscriptions::Status::Accepted)),
+        )
+        .filter(entities::subscriptions::Column::UserId.eq(id))
+        .filter(
+            entities::subscriptions::Column::Status
+                .eq(String::from(subscriptions::Status::Pending)),
+        )
+        .exec(transaction)
+        .await
+        .map(|_| ())
+}
+
+// The case-insensitive username index is the only unique constraint besides the key
+fn into_error(err: &DbErr) -> Error {
+    match err.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => Error::UsernameTaken,
+        _ if matches!(err, DbErr::RecordNotUpdated) => Error::NotFound,
+        _ => Error::Unknown,
+    }
+}
+
+fn username_eq(username: &str) -> SimpleExpr {
+    Expr::expr(Func::lower(Expr::col(Column::Username))).eq(username.to_lowercase())
+}
+
 #[async_trait]
 impl RepositoryTrait for Repository {
     async fn create_user(&self, payload: Payload) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Create, payload.id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(payload.id);
+
         let model: Model = payload.into();
         let active_model: ActiveModel = model.into();
-        active_model
-            .insert(&self.database_connection)
+        let model = active_model
+            .insert(&transaction)
             .await
-            .map(Into::into)
-            .or(Err(Error::Unknown))
+            .map_err(|err| into_error(&err))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(model.into())
     }
 
     async fn get_user(&self, id: Id) -> Result<Option<Response>, Error> {
         Entity::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
             .one(&self.database_connection)
             .await
             .map(|x| x.map(Into::into))
             .or(Err(Error::Unknown))
     }
 
+    async fn get_user_by_username(&self, username: String) -> Result<Option<Response>, Error> {
+        Entity::find()
+            .filter(username_eq(&username))
+            .filter(Column::DeletedAt.is_null())
+            .one(&self.database_connection)
+            .await
+            .map(|x| x.map(Into::into))
+            .or(Err(Error::Unknown))
+    }
+
+    // Deleted users keep their username so that they can be restored
+    async fn is_username_available(&self, username: String) -> Result<bool, Error> {
+        Entity::find()
+            .filter(username_eq(&username))
+            .count(&self.database_connection)
+            .await
+            .map(|x| x == 0)
+            .or(Err(Error::Unknown))
+    }
+
     async fn list_users(&self, predicate: Option<Predicate>) -> Result<Vec<Response>, Error> {
         match predicate {
             Some(value) => Entity::find().filter(Column::Name.contains(value)),
             None => Entity::find(),
         }
+        .filter(Column::DeletedAt.is_null())
         .order_by_desc(Column::CreatedAt)
         .order_by_desc(Column::Id)
         .all(&self.database_connection)
@@ -66,33 +179,315 @@ impl RepositoryTrait for Repository {
         .or(Err(Error::Unknown))
     }
 
-    async fn update_user(&self, id: Id, payload: Payload) -> Result<Response, Error> {
+    async fn update_user(
+        &self,
+        id: Id,
+        payload: Payload,
+        version: Option<Precondition>,
+    ) -> Result<Response, Error> {
+        let is_private = payload.is_private;
         let model: Model = payload.into();
-        let active_model: ActiveModel = model.into();
+        let mut active_model = ActiveModel::from(model).reset_all();
+        active_model.deleted_at = NotSet;
+        active_model.version = NotSet;
+
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        check_version::<Entity, _>(
+            &transaction,
+            id,
+            version,
+            Error::NotFound,
+            Error::VersionMismatch,
+        )
+        .await
+        .or(Err(Error::Unknown))??;
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Update, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(id);
 
-        Entity::update(active_model)
+        let model = Entity::update(active_model)
             .filter(Column::Id.eq(id))
-            .exec(&self.database_connection)
+            .filter(Column::DeletedAt.is_null())
+            .exec(&transaction)
             .await
-            .map(Into::into)
-            .or(Err(Error::Unknown))
+            .map_err(|err| into_error(&err))?;
+
+        if !is_private {
+            accept_pending(&transaction, id)
+                .await
+                .or(Err(Error::Unknown))?;
+        }
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(model.into())
+    }
+
+    async fn patch_user(
+        &self,
+        id: Id,
+        patch: Patch,
+        version: Option<Precondition>,
+    ) -> Result<Response, Error> {
+        let is_private = patch.is_private;
+        let active_model = ActiveModel {
+            id: Unchanged(id),
+            name: set_present(patch.name),
+            currency: set_present(patch.currency),
+            email: set_present(patch.email),
+            is_private: set_present(patch.is_private),
+            username: set_present(patch.username),
+            bio: set_present(patch.bio),
+            birthday: set_present(patch.birthday),
+            locale: set_present(patch.locale),
+            updated_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        check_version::<Entity, _>(
+            &transaction,
+            id,
+            version,
+            Error::NotFound,
+            Error::VersionMismatch,
+        )
+        .await
+        .or(Err(Error::Unknown))??;
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Update, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(id);
+
+        let model = Entity::update(active_model)
+            .filter(Column::DeletedAt.is_null())
+            .exec(&transaction)
+            .await
+            .map_err(|err| into_error(&err))?;
+
+        if is_private == Some(false) {
+            accept_pending(&transaction, id)
+                .await
+                .or(Err(Error::Unknown))?;
+        }
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(model.into())
     }
 
-    async fn delete_user(&self, id: Id) -> Result<(), Error> {
+    async fn delete_user(&self, id: Id, version: Option<Precondition>) -> Result<(), Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        check_version::<Entity, _>(
+            &transaction,
+            id,
+            version,
+            Error::NotFound,
+            Error::VersionMismatch,
+        )
+        .await
+        .or(Err(Error::Unknown))??;
+
+        let deleted_at = Utc::now().naive_utc();
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Delete, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(id);
+
+        let result = Entity::update_many()
+            .col_expr(Column::DeletedAt, Expr::value(deleted_at))
+            .filter(Column::Id.eq(id))
+            .filter(Column::DeletedAt.is_null())
+            .exec(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        if result.rows_affected > 0 {
+            crate::wishlists::set_deleted_at(
+                &transaction,
+                entities::wishlists::Column::UserId.eq(id),
+                None,
+                Some(deleted_at),
+            )
+            .await
+            .or(Err(Error::Unknown))?;
+
+            entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        }
+
+        transaction.commit().await.or(Err(Error::Unknown))
+    }
+
+    async fn restore_user(&self, id: Id) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let model = Entity::find_by_id(id)
+            .filter(Column::DeletedAt.is_not_null())
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Restore, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(id);
+
+        crate::wishlists::set_deleted_at(
+            &transaction,
+            entities::wishlists::Column::UserId.eq(id),
+            model.deleted_at,
+            None,
+        )
+        .await
+        .or(Err(Error::Unknown))?;
+
+        let mut active_model: ActiveModel = model.into();
+        active_model.deleted_at = Set(None);
+        let model = active_model
+            .update(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(model.into())
+    }
+
+    async fn erase_user(&self, id: Id, version: Version) -> Result<(), Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let model = Entity::find_by_id(id)
+            .lock_exclusive()
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+
+        if model.version != version {
+            return Err(Error::VersionMismatch);
+        }
+
+        let pictures = entities::items::Entity::find()
+            .select_only()
+            .column(entities::items::Column::PictureId)
+            .inner_join(entities::wishlists::Entity)
+            .filter(entities::wishlists::Column::UserId.eq(id))
+            .filter(entities::items::Column::PictureId.is_not_null())
+            .into_tuple::<Uuid>()
+            .all(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        // The audit log keeps its entries but loses the snapshots of the user
+        // and the references to them as an actor
+        entities::audit_log::Entity::update_many()
+            .col_expr(
+                entities::audit_log::Column::Before,
+                Expr::value(Value::Json(None)),
+            )
+            .col_expr(
+                entities::audit_log::Column::After,
+                Expr::value(Value::Json(None)),
+            )
+            .filter(entities::audit_log::Column::EntityType.eq(Entity.table_name()))
+            .filter(entities::audit_log::Column::EntityId.eq(id))
+            .exec(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        entities::audit_log::Entity::update_many()
+            .col_expr(
+                entities::audit_log::Column::ActorId,
+                Expr::value(Value::Uuid(None)),
+            )
+            .filter(entities::audit_log::Column::ActorId.eq(id))
+            .exec(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        // Along with the snapshots of their wishlists and items, and the references
+        // to them in rows of other entities
+        redact_owned(&transaction, id)
+            .await
+            .or(Err(Error::Unknown))?;
+        redact_user::<entities::reservations::Entity, _>(&transaction, &["user_id"], id)
+            .await
+            .or(Err(Error::Unknown))?;
+        redact_user::<entities::contributions::Entity, _>(&transaction, &["user_id"], id)
+            .await
+            .or(Err(Error::Unknown))?;
+        redact_user::<entities::subscriptions::Entity, _>(
+            &transaction,
+            &["user_id", "subscriber_id"],
+            id,
+        )
+        .await
+        .or(Err(Error::Unknown))?;
+        redact_user::<entities::blocks::Entity, _>(&transaction, &["user_id", "target_id"], id)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        // Everything owned by the user cascades, reservations and contributions
+        // made on other people's items are kept without the reference to the user
         Entity::delete_by_id(id)
-            .exec(&self.database_connection)
+            .exec(&transaction)
             .await
-            .map(|_| ())
-            .or(Err(Error::Unknown))
+            .or(Err(Error::Unknown))?;
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        for key in pictures {
+            self.delete_item_picture(key).await.ok();
+        }
+
+        if let Some(key) = model.avatar_id {
+            self.delete_user_avatar(key).await.ok();
+        }
+
+        Ok(())
     }
 
     async fn list_user_wishlists(
         &self,
         id: Id,
+        viewer_id: Id,
         predicate: Option<wishlists::Predicate>,
     ) -> Result<Vec<wishlists::Response>, Error> {
         let condition = Condition::all()
             .add(entities::wishlists::Column::UserId.eq(id))
+            .add(crate::wishlists::visible_to(viewer_id))
+            .add(entities::wishlists::Column::DeletedAt.is_null())
             .add(entities::wishlists::Column::Name.like(predicate.unwrap_or_default()));
 
         entities::wishlists::Entity::find()
@@ -114,10 +509,17 @@ impl RepositoryTrait for Repository {
                 Column::Id.in_subquery(
                     Query::select()
                         .expr(Expr::col(entities::subscriptions::Column::SubscriberId))
+                        .from(entities::subscriptions::Entity)
                         .and_where(entities::subscriptions::Column::UserId.eq(id))
+                        .and_where(
+                            entities::subscriptions::Column::Status
+                                .eq(String::from(subscriptions::Status::Accepted)),
+                        )
                         .clone(),
                 ),
             )
+            .add(Column::Id.not_in_subquery(crate::blocks::blocked_ids(id)))
+            .add(Column::DeletedAt.is_null())
             .add(Column::Name.like(predicate.unwrap_or_default()));
 
         Entity::find()
@@ -140,10 +542,15 @@ impl RepositoryTrait for Repository {
                     Query::select()
                         .expr(Expr::col(entities::subscriptions::Column::UserId))
                         .and_where(entities::subscriptions::Column::SubscriberId.eq(id))
+                        .and_where(
+                            entities::subscriptions::Column::Status
+                                .eq(String::from(subscriptions::Status::Accepted)),
+                        )
                         .from(entities::subscriptions::Entity)
                         .clone(),
                 ),
             )
+            .add(Column::DeletedAt.is_null())
             .add(Column::Name.like(predicate.unwrap_or_default()));
 
         Entity::find()
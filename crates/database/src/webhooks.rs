@@ -0,0 +1,275 @@
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use entities::{
+    webhook_outbox,
+    webhooks::{ActiveModel, Column, Entity, Model},
+};
+use migrations::{Expr, LockBehavior, LockType, Query};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::Set,
+    ColumnTrait,
+    ConnectionTrait,
+    DbErr,
+    EntityTrait,
+    Order,
+    QueryFilter,
+    QueryOrder,
+};
+
+use super::traits::{
+    events::{Event, Kind},
+    webhooks::{
+        Attempt,
+        DeliveryId,
+        DeliveryResponse,
+        Error,
+        Id,
+        Payload,
+        RepositoryTrait,
+        Response,
+        Status,
+    },
+    wishlists,
+};
+use crate::Repository;
+
+impl From<Payload> for Model {
+    fn from(value: Payload) -> Self {
+        Model {
+            id: value.id,
+            wishlist_id: value.wishlist_id,
+            url: value.url,
+            secret: value.secret,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+}
+
+impl From<Model> for Response {
+    fn from(value: Model) -> Self {
+        Response {
+            id: value.id,
+            wishlist_id: value.wishlist_id,
+            url: value.url,
+            secret: value.secret,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<webhook_outbox::Model> for DeliveryResponse {
+    fn from(value: webhook_outbox::Model) -> Self {
+        let status = if value.delivered_at.is_some() {
+            Status::Delivered
+        } else if value.failed_at.is_some() {
+            Status::Failed
+        } else {
+            Status::Pending
+        };
+
+        DeliveryResponse {
+            id: value.id,
+            webhook_id: value.webhook_id,
+            event: value.event,
+            payload: value.payload,
+            status,
+            attempts: value.attempts,
+            next_attempt_at: value.next_attempt_at,
+            last_status_code: value.last_status_code,
+            last_error: value.last_error,
+            delivered_at: value.delivered_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+// Queues the event for every webhook of its wishlist, the dispatcher only sees
+// the deliveries once the surrounding transaction commits
+pub(crate) async fn enqueue<C>(db: &C, event: &Event) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    // Owners don't learn about reservations before the event is over, and
+    // archived wishlists can't be reserved from anymore
+    if matches!(event.kind, Kind::Reserved | Kind::Unreserved) {
+        return Ok(());
+    }
+
+    let now = Utc::now().naive_utc();
+    let name = format!("item.{}", String::from(event.kind));
+    let payload = serde_json::json!({
+        "event": name,
+        "wishlist_id": event.wishlist_id,
+        "item_id": event.item_id,
+        "occurred_at": now,
+    });
+
+    let select = Query::select()
+        .expr(Expr::cust("gen_random_uuid()"))
+        .column(Column::Id)
+        .expr(Expr::val(name))
+        .expr(Expr::val(payload))
+        .expr(Expr::val(now))
+        .expr(Expr::val(now))
+        .from(Entity)
+        .and_where(Column::WishlistId.eq(event.wishlist_id))
+        .to_owned();
+
+    let insert = Query::insert()
+        .into_table(webhook_outbox::Entity)
+        .columns([
+            webhook_outbox::Column::Id,
+            webhook_outbox::Column::WebhookId,
+            webhook_outbox::Column::Event,
+            webhook_outbox::Column::Payload,
+            webhook_outbox::Column::NextAttemptAt,
+            webhook_outbox::Column::CreatedAt,
+        ])
+        .select_from(select)
+        .map_err(|err| DbErr::Custom(err.to_string()))?
+        .to_owned();
+
+    db.execute(db.get_database_backend().build(&insert))
+        .await
+        .map(|_| ())
+}
+
+#[async_trait]
+impl RepositoryTrait for Repository {
+    async fn create_webhook(&self, payload: Payload) -> Result<Response, Error> {
+        crate::wishlists::find_active(&self.database_connection, payload.wishlist_id)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::WishlistNotFound)?;
+
+        let model: Model = payload.into();
+        let active_model: ActiveModel = model.into();
+
+        active_model
+            .insert(&self.database_connection)
+            .await
+            .map(Into::into)
+            .or(Err(Error::Unknown))
+    }
+
+    async fn get_webhook(&self, id: Id) -> Result<Option<Response>, Error> {
+        Entity::find_by_id(id)
+            .one(&self.database_connection)
+            .await
+            .map(|x| x.map(Into::into))
+            .or(Err(Error::Unknown))
+    }
+
+    async fn list_wishlist_webhooks(
+        &self,
+        wishlist_id: wishlists::Id,
+    ) -> Result<Vec<Response>, Error> {
+        Entity::find()
+            .filter(Column::WishlistId.eq(wishlist_id))
+            .order_by_asc(Column::CreatedAt)
+            .order_by_asc(Column::Id)
+            .all(&self.database_connection)
+            .await
+            .map(|x| x.into_iter().map(Into::into).collect())
+            .or(Err(Error::Unknown))
+    }
+
+    async fn delete_webhook(&self, id: Id) -> Result<(), Error> {
+        let result = Entity::delete_by_id(id)
+            .exec(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        if result.rows_affected == 0 {
+            return Err(Error::NotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn list_webhook_deliveries(&self, id: Id) -> Result<Vec<DeliveryResponse>, Error> {
+        webhook_outbox::Entity::find()
+            .filter(webhook_outbox::Column::WebhookId.eq(id))
+            .order_by_desc(webhook_outbox::Column::CreatedAt)
+            .order_by_desc(webhook_outbox::Column::Id)
+            .all(&self.database_connection)
+            .await
+            .map(|x| x.into_iter().map(Into::into).collect())
+            .or(Err(Error::Unknown))
+    }
+
+    async fn claim_due_webhook_deliveries(
+        &self,
+        limit: u64,
+        claim: Duration,
+    ) -> Result<Vec<DeliveryResponse>, Error> {
+        let now = Utc::now().naive_utc();
+
+        // Rows another instance is claiming right now are skipped rather than waited for
+        let due = Query::select()
+            .column(webhook_outbox::Column::Id)
+            .from(webhook_outbox::Entity)
+            .and_where(webhook_outbox::Column::DeliveredAt.is_null())
+            .and_where(webhook_outbox::Column::FailedAt.is_null())
+            .and_where(webhook_outbox::Column::NextAttemptAt.lte(now))
+            .order_by(webhook_outbox::Column::NextAttemptAt, Order::Asc)
+            .order_by(webhook_outbox::Column::Id, Order::Asc)
+            .limit(limit)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .to_owned();
+
+        let mut models = webhook_outbox::Entity::update_many()
+            .col_expr(
+                webhook_outbox::Column::NextAttemptAt,
+                Expr::value(now + claim),
+            )
+            .filter(webhook_outbox::Column::Id.in_subquery(due))
+            .exec_with_returning(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        models.sort_by_key(|x| (x.created_at, x.id));
+
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    async fn record_webhook_attempt(
+        &self,
+        id: DeliveryId,
+        attempt: Attempt,
+    ) -> Result<DeliveryResponse, Error> {
+        let model = webhook_outbox::Entity::find_by_id(id)
+            .one(&self.database_connection)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::DeliveryNotFound)?;
+
+        let now = Utc::now().naive_utc();
+        let attempts = model.attempts + 1;
+        let mut active_model: webhook_outbox::ActiveModel = model.into();
+
+        active_model.attempts = Set(attempts);
+        active_model.last_status_code = Set(attempt.status_code);
+        match (attempt.error, attempt.retry_at) {
+            (None, _) => {
+                active_model.last_error = Set(None);
+                active_model.delivered_at = Set(Some(now));
+            }
+            (Some(error), Some(retry_at)) => {
+                active_model.last_error = Set(Some(error));
+                active_model.next_attempt_at = Set(retry_at);
+            }
+            (Some(error), None) => {
+                active_model.last_error = Set(Some(error));
+                active_model.failed_at = Set(Some(now));
+            }
+        }
+
+        active_model
+            .update(&self.database_connection)
+            .await
+            .map(Into::into)
+            .or(Err(Error::Unknown))
+    }
+}
@@ -1,12 +1,37 @@
 use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, Utc};
 use entities::wishlists::{ActiveModel, Column, Entity, Model};
-use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder};
+use migrations::{Expr, Query, SelectStatement, SimpleExpr};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{NotSet, Set, Unchanged},
+    ColumnTrait,
+    Condition,
+    ConnectionTrait,
+    DatabaseTransaction,
+    DbErr,
+    EntityTrait,
+    QueryFilter,
+    QueryOrder,
+    TransactionTrait,
+};
+use uuid::Uuid;
 
 use super::traits::{
+    audit,
     items,
-    wishlists::{Error, Id, Payload, Predicate, RepositoryTrait, Response},
+    notifications::Kind,
+    users,
+    wishlists::{Error, Filter, Id, Patch, Payload, RepositoryTrait, Response},
+    Precondition,
+};
+use crate::{
+    audit::{Action, Entry},
+    check_version,
+    notifications::{notify_subscribers, Event},
+    set_present,
+    Repository,
 };
-use crate::Repository;
 
 impl From<Payload> for Model {
     fn from(value: Payload) -> Self {
@@ -14,8 +39,12 @@ impl From<Payload> for Model {
             id: value.id,
             name: value.name,
             user_id: value.user_id,
+            event_type: value.event_type,
+            event_date: value.event_date,
             created_at: value.created_at,
             updated_at: value.updated_at,
+            deleted_at: None,
+            version: 1,
         }
     }
 }
@@ -26,81 +55,554 @@ impl From<Model> for Response {
             id: value.id,
             name: value.name,
             user_id: value.user_id,
+            event_type: value.event_type,
+            event_date: value.event_date,
+            is_archived: is_archived(value.event_date),
+            version: value.version,
             created_at: value.created_at,
             updated_at: value.updated_at,
         }
     }
 }
 
+pub(crate) fn is_archived(event_date: Option<NaiveDate>) -> bool {
+    event_date.is_some_and(|date| date < Utc::now().date_naive())
+}
+
+pub(crate) async fn find_active<C>(db: &C, id: Id) -> Result<Option<Model>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Entity::find_by_id(id)
+        .filter(Column::DeletedAt.is_null())
+        .one(db)
+        .await
+}
+
+// Wishlists of users who blocked the viewer, or were blocked by them, are
+// left out as if they didn't exist
+pub(crate) fn visible_to(viewer_id: users::Id) -> SimpleExpr {
+    Column::UserId.not_in_subquery(crate::blocks::blocked_ids(viewer_id))
+}
+
+pub(crate) fn visible_ids(viewer_id: users::Id) -> SelectStatement {
+    Query::select()
+        .column(Column::Id)
+        .from(Entity)
+        .and_where(Column::DeletedAt.is_null())
+        .and_where(visible_to(viewer_id))
+        .to_owned()
+}
+
+// Archived wishlists are read-only, their event can't be moved to reopen them
+async fn check_writable(transaction: &DatabaseTransaction, id: Id) -> Result<Model, Error> {
+    let wishlist = find_active(transaction, id)
+        .await
+        .or(Err(Error::Unknown))?
+        .ok_or(Error::NotFound)?;
+
+    if is_archived(wishlist.event_date) {
+        Err(Error::Archived)
+    } else {
+        Ok(wishlist)
+    }
+}
+
+fn deleted_at_matches<C>(column: C, deleted_at: Option<NaiveDateTime>) -> SimpleExpr
+where
+    C: ColumnTrait,
+{
+    match deleted_at {
+        Some(deleted_at) => column.eq(deleted_at),
+        None => column.is_null(),
+    }
+}
+
+// Moves wishlists matching the condition, together with their items, between deletion states
+pub(crate) async fn set_deleted_at(
+    transaction: &DatabaseTransaction,
+    condition: SimpleExpr,
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+) -> Result<(), DbErr> {
+    entities::items::Entity::update_many()
+        .col_expr(entities::items::Column::DeletedAt, Expr::value(to))
+        .filter(
+            entities::items::Column::WishlistId.in_subquery(
+                Query::select()
+                    .column(Column::Id)
+                    .from(Entity)
+                    .and_where(condition.clone())
+                    .and_where(deleted_at_matches(Column::DeletedAt, from))
+                    .to_owned(),
+            ),
+        )
+        .filter(deleted_at_matches(entities::items::Column::DeletedAt, from))
+        .exec(transaction)
+        .await?;
+
+    Entity::update_many()
+        .col_expr(Column::DeletedAt, Expr::value(to))
+        .filter(condition)
+        .filter(deleted_at_matches(Column::DeletedAt, from))
+        .exec(transaction)
+        .await?;
+
+    Ok(())
+}
+
+pub(crate) async fn insert_with_items(
+    transaction: &DatabaseTransaction,
+    payload: Payload,
+    items: Vec<entities::items::Model>,
+) -> Result<Model, DbErr> {
+    let entry = Entry::<Entity>::capture(transaction, Action::Create, payload.id)
+        .await?
+        .actor(payload.user_id)
+        .wishlist(payload.id);
+
+    let model: Model = payload.into();
+    let active_model: ActiveModel = model.into();
+    let model = active_model.insert(transaction).await?;
+    entry.record(transaction).await?;
+
+    notify_subscribers(
+        transaction,
+        model.user_id,
+        Event {
+            kind: Kind::WishlistCreated,
+            actor_id: model.user_id,
+            wishlist_id: model.id,
+            item_id: None,
+        },
+        &[],
+    )
+    .await?;
+
+    if !items.is_empty() {
+        let ids: Vec<_> = items.iter().map(|x| x.id).collect();
+
+        entities::items::Entity::insert_many(
+            items.into_iter().map(entities::items::ActiveModel::from),
+        )
+        .exec(transaction)
+        .await?;
+
+        for id in ids {
+            Entry::<entities::items::Entity>::capture(transaction, Action::Create, id)
+                .await?
+                .actor(model.user_id)
+                .wishlist(model.id)
+                .record(transaction)
+                .await?;
+        }
+    }
+
+    Ok(model)
+}
+
 #[async_trait]
 impl RepositoryTrait for Repository {
     async fn create_wishlist(&self, payload: Payload) -> Result<Response, Error> {
-        let model: Model = payload.into();
-        let active_model: ActiveModel = model.into();
-        active_model
-            .insert(&self.database_connection)
+        let transaction = self
+            .database_connection
+            .begin()
             .await
-            .map(Into::into)
-            .or(Err(Error::Unknown))
+            .or(Err(Error::Unknown))?;
+
+        let model = insert_with_items(&transaction, payload, Vec::new())
+            .await
+            .or(Err(Error::Unknown))?;
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(model.into())
     }
 
     async fn get_wishlist(&self, id: Id) -> Result<Option<Response>, Error> {
+        find_active(&self.database_connection, id)
+            .await
+            .map(|x| x.map(Into::into))
+            .or(Err(Error::Unknown))
+    }
+
+    async fn get_visible_wishlist(
+        &self,
+        id: Id,
+        viewer_id: users::Id,
+    ) -> Result<Option<Response>, Error> {
         Entity::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
+            .filter(visible_to(viewer_id))
             .one(&self.database_connection)
             .await
             .map(|x| x.map(Into::into))
             .or(Err(Error::Unknown))
     }
 
-    async fn list_wishlists(&self, predicate: Option<Predicate>) -> Result<Vec<Response>, Error> {
-        match predicate {
-            Some(value) => Entity::find().filter(Column::Name.contains(value)),
-            None => Entity::find(),
+    async fn list_wishlists(
+        &self,
+        viewer_id: users::Id,
+        filter: Filter,
+    ) -> Result<Vec<Response>, Error> {
+        let mut condition = Condition::all()
+            .add(Column::DeletedAt.is_null())
+            .add(visible_to(viewer_id));
+
+        if let Some(predicate) = filter.predicate {
+            condition = condition.add(Column::Name.contains(predicate));
         }
-        .order_by_desc(Column::CreatedAt)
-        .order_by_desc(Column::Id)
-        .all(&self.database_connection)
-        .await
-        .map(|x| x.into_iter().map(Into::into).collect())
-        .or(Err(Error::Unknown))
+
+        if let Some(event_type) = filter.event_type {
+            condition = condition.add(Column::EventType.eq(event_type));
+        }
+
+        let mut select = Entity::find();
+
+        if filter.upcoming {
+            condition = condition.add(Column::EventDate.gte(Utc::now().date_naive()));
+            select = select.order_by_asc(Column::EventDate);
+        }
+
+        select
+            .filter(condition)
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .all(&self.database_connection)
+            .await
+            .map(|x| x.into_iter().map(Into::into).collect())
+            .or(Err(Error::Unknown))
     }
 
-    async fn update_wishlist(&self, id: Id, payload: Payload) -> Result<Response, Error> {
+    async fn update_wishlist(
+        &self,
+        id: Id,
+        payload: Payload,
+        version: Option<Precondition>,
+        actor_id: users::Id,
+    ) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        check_version::<Entity, _>(
+            &transaction,
+            id,
+            version,
+            Error::NotFound,
+            Error::VersionMismatch,
+        )
+        .await
+        .or(Err(Error::Unknown))??;
+
+        check_writable(&transaction, id).await?;
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Update, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(actor_id)
+            .wishlist(id);
+
         let model: Model = payload.into();
-        let active_model: ActiveModel = model.into();
+        let mut active_model = ActiveModel::from(model).reset_all();
+        active_model.deleted_at = NotSet;
+        active_model.version = NotSet;
 
-        Entity::update(active_model)
+        let model = Entity::update(active_model)
             .filter(Column::Id.eq(id))
-            .exec(&self.database_connection)
+            .filter(Column::DeletedAt.is_null())
+            .exec(&transaction)
             .await
-            .map(Into::into)
-            .or(Err(Error::Unknown))
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(model.into())
     }
 
-    async fn delete_wishlist(&self, id: Id) -> Result<(), Error> {
-        Entity::delete_by_id(id)
-            .exec(&self.database_connection)
+    async fn patch_wishlist(
+        &self,
+        id: Id,
+        patch: Patch,
+        version: Option<Precondition>,
+        actor_id: users::Id,
+    ) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
             .await
-            .map(|_| ())
-            .or(Err(Error::Unknown))
+            .or(Err(Error::Unknown))?;
+
+        check_version::<Entity, _>(
+            &transaction,
+            id,
+            version,
+            Error::NotFound,
+            Error::VersionMismatch,
+        )
+        .await
+        .or(Err(Error::Unknown))??;
+
+        check_writable(&transaction, id).await?;
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Update, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(actor_id)
+            .wishlist(id);
+
+        let active_model = ActiveModel {
+            id: Unchanged(id),
+            name: set_present(patch.name),
+            event_type: set_present(patch.event_type),
+            event_date: set_present(patch.event_date),
+            updated_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+
+        let model = Entity::update(active_model)
+            .filter(Column::DeletedAt.is_null())
+            .exec(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(model.into())
+    }
+
+    async fn delete_wishlist(
+        &self,
+        id: Id,
+        version: Option<Precondition>,
+        actor_id: users::Id,
+    ) -> Result<(), Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        check_version::<Entity, _>(
+            &transaction,
+            id,
+            version,
+            Error::NotFound,
+            Error::VersionMismatch,
+        )
+        .await
+        .or(Err(Error::Unknown))??;
+
+        let Some(model) = find_active(&transaction, id)
+            .await
+            .or(Err(Error::Unknown))?
+        else {
+            return Ok(());
+        };
+
+        if is_archived(model.event_date) {
+            return Err(Error::Archived);
+        }
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Delete, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(actor_id)
+            .wishlist(id);
+
+        set_deleted_at(
+            &transaction,
+            Column::Id.eq(id),
+            None,
+            Some(Utc::now().naive_utc()),
+        )
+        .await
+        .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+        transaction.commit().await.or(Err(Error::Unknown))
+    }
+
+    async fn restore_wishlist(&self, id: Id, actor_id: users::Id) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let model = Entity::find_by_id(id)
+            .filter(Column::DeletedAt.is_not_null())
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+
+        entities::users::Entity::find_by_id(model.user_id)
+            .filter(entities::users::Column::DeletedAt.is_null())
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::UserNotFound)?;
+
+        let entry = Entry::<Entity>::capture(&transaction, Action::Restore, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .actor(actor_id)
+            .wishlist(id);
+
+        set_deleted_at(&transaction, Column::Id.eq(id), model.deleted_at, None)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        entry.record(&transaction).await.or(Err(Error::Unknown))?;
+
+        let model = find_active(&transaction, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::Unknown)?;
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(model.into())
+    }
+
+    async fn clone_wishlist(&self, id: Id, payload: Payload) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let source = find_active(&transaction, id)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::NotFound)?;
+
+        let is_owner = source.user_id == payload.user_id;
+
+        // A blocked user can't tell the wishlist apart from a deleted one
+        if !is_owner
+            && crate::blocks::is_blocked(&transaction, source.user_id, payload.user_id)
+                .await
+                .or(Err(Error::Unknown))?
+        {
+            return Err(Error::NotFound);
+        }
+
+        let mut query = entities::items::Entity::find()
+            .filter(entities::items::Column::WishlistId.eq(id))
+            .filter(entities::items::Column::DeletedAt.is_null());
+        if !is_owner {
+            query = query.filter(entities::items::Column::IsHidden.eq(false));
+        }
+
+        let sources = query
+            .order_by_asc(entities::items::Column::Position)
+            .order_by_asc(entities::items::Column::Id)
+            .all(&transaction)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        let mut items = Vec::with_capacity(sources.len());
+        let mut pictures = Vec::new();
+        for (position, item) in (0..).zip(sources) {
+            let Ok(picture_id) = self.duplicate_item_picture(item.picture_id).await else {
+                self.discard_item_pictures(pictures).await;
+                return Err(Error::Unknown);
+            };
+            pictures.extend(picture_id);
+
+            items.push(entities::items::Model {
+                id: Uuid::new_v4(),
+                wishlist_id: payload.id,
+                position,
+                is_funded: false,
+                picture_id,
+                created_at: payload.created_at,
+                updated_at: payload.updated_at,
+                version: 1,
+                ..item
+            });
+        }
+
+        let result = async {
+            let model = insert_with_items(&transaction, payload, items).await?;
+            transaction.commit().await?;
+            Ok::<_, DbErr>(model)
+        }
+        .await;
+
+        if let Ok(model) = result {
+            Ok(model.into())
+        } else {
+            self.discard_item_pictures(pictures).await;
+            Err(Error::Unknown)
+        }
+    }
+
+    async fn import_wishlist(
+        &self,
+        payload: Payload,
+        items: Vec<items::Payload>,
+    ) -> Result<Response, Error> {
+        let transaction = self
+            .database_connection
+            .begin()
+            .await
+            .or(Err(Error::Unknown))?;
+
+        entities::users::Entity::find_by_id(payload.user_id)
+            .filter(entities::users::Column::DeletedAt.is_null())
+            .one(&transaction)
+            .await
+            .or(Err(Error::Unknown))?
+            .ok_or(Error::UserNotFound)?;
+
+        // Items keep the order they were listed in
+        let items = (0..)
+            .zip(items)
+            .map(|(position, item)| entities::items::Model {
+                wishlist_id: payload.id,
+                position,
+                ..item.into()
+            })
+            .collect();
+
+        let model = insert_with_items(&transaction, payload, items)
+            .await
+            .or(Err(Error::Unknown))?;
+
+        transaction.commit().await.or(Err(Error::Unknown))?;
+
+        Ok(model.into())
     }
 
     async fn list_wishlist_items(
         &self,
         id: Id,
-        predicate: Option<items::Predicate>,
+        viewer_id: users::Id,
+        filter: items::Filter,
     ) -> Result<Vec<items::Response>, Error> {
-        let condition = Condition::all()
-            .add(entities::items::Column::WishlistId.eq(id))
-            .add(entities::items::Column::Name.like(predicate.unwrap_or_default()));
-
-        entities::items::Entity::find()
-            .filter(condition)
-            .order_by_desc(entities::items::Column::UpdatedAt)
-            .order_by_desc(entities::items::Column::Id)
+        crate::items::filter(crate::items::find(), filter)
+            .filter(entities::items::Column::WishlistId.eq(id))
+            .filter(crate::items::visible_to(viewer_id))
+            .order_by_asc(entities::items::Column::Position)
+            .order_by_asc(entities::items::Column::Id)
+            .into_model::<crate::items::QueryResult>()
             .all(&self.database_connection)
             .await
             .map(|x| x.into_iter().map(Into::into).collect())
             .or(Err(Error::Unknown))
     }
+
+    async fn list_wishlist_history(&self, id: Id) -> Result<Vec<audit::Response>, Error> {
+        crate::audit::list_by_wishlist(&self.database_connection, id)
+            .await
+            .map(|x| x.into_iter().map(Into::into).collect())
+            .or(Err(Error::Unknown))
+    }
 }
@@ -0,0 +1,80 @@
+// Shared by the test binaries, each of which uses only some of it
+#![allow(dead_code)]
+
+use chrono::Utc;
+use database::{
+    traits::{items, users, wishlists},
+    BlobStorageClient,
+    BlobStorageConfig,
+    BlobStorageRegion,
+    Database,
+    Repository,
+};
+
+pub async fn repository() -> Repository {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let database_connection = Database::connect(url).await.unwrap();
+
+    // Nothing here touches blob storage
+    let blob_storage_client = BlobStorageClient::from_conf(
+        BlobStorageConfig::builder()
+            .behavior_version_latest()
+            .region(BlobStorageRegion::new("us-east-1"))
+            .build(),
+    );
+
+    Repository::new(
+        database_connection,
+        blob_storage_client,
+        "wishlists".to_owned(),
+    )
+}
+
+pub fn user(id: users::Id) -> users::Payload {
+    users::Payload {
+        id,
+        name: "Test user".to_owned(),
+        avatar_id: None,
+        currency: "EUR".to_owned(),
+        email: None,
+        is_admin: false,
+        is_private: false,
+        username: None,
+        bio: None,
+        birthday: None,
+        locale: None,
+        created_at: Utc::now().naive_utc(),
+        updated_at: Utc::now().naive_utc(),
+    }
+}
+
+pub fn wishlist(id: wishlists::Id, user_id: users::Id) -> wishlists::Payload {
+    wishlists::Payload {
+        id,
+        name: "Test wishlist".to_owned(),
+        user_id,
+        event_type: None,
+        event_date: None,
+        created_at: Utc::now().naive_utc(),
+        updated_at: Utc::now().naive_utc(),
+    }
+}
+
+pub fn item(id: items::Id, wishlist_id: wishlists::Id, quantity: i32) -> items::Payload {
+    items::Payload {
+        id,
+        wishlist_id,
+        name: "Test item".to_owned(),
+        description: None,
+        url: None,
+        price: None,
+        currency: None,
+        quantity,
+        priority: items::Priority::default(),
+        is_hidden: false,
+        is_funded: false,
+        picture_id: None,
+        created_at: Utc::now().naive_utc(),
+        updated_at: Utc::now().naive_utc(),
+    }
+}
@@ -0,0 +1,62 @@
+//! Runs against a migrated PostgreSQL database given in `DATABASE_URL`:
+//!
+//! ```sh
+//! DATABASE_URL=postgres://postgres@localhost:5432/wishlists cargo test -p database -- --ignored
+//! ```
+
+use common::{item, repository, user, wishlist};
+use database::RepositoryTrait;
+use uuid::Uuid;
+
+mod common;
+
+#[tokio::test]
+#[ignore = "needs a database"]
+async fn erasure_redacts_snapshots_of_owned_wishlists_and_items() {
+    let repository = repository().await;
+    let repository: &(dyn RepositoryTrait + Send + Sync) = &repository;
+    let user_id = Uuid::new_v4();
+    let wishlist_id = Uuid::new_v4();
+    let other_user_id = Uuid::new_v4();
+    let other_wishlist_id = Uuid::new_v4();
+
+    let erased = repository.create_user(user(user_id)).await.unwrap();
+    repository
+        .create_wishlist(wishlist(wishlist_id, user_id))
+        .await
+        .unwrap();
+    repository
+        .create_item(item(Uuid::new_v4(), wishlist_id, 1), Some(user_id))
+        .await
+        .unwrap();
+
+    let other = repository.create_user(user(other_user_id)).await.unwrap();
+    repository
+        .create_wishlist(wishlist(other_wishlist_id, other_user_id))
+        .await
+        .unwrap();
+
+    repository
+        .erase_user(user_id, erased.version)
+        .await
+        .unwrap();
+
+    let history = repository.list_wishlist_history(wishlist_id).await.unwrap();
+    assert!(history.iter().any(|entry| entry.entity_type == "items"));
+    assert!(history.iter().any(|entry| entry.entity_type == "wishlists"));
+    assert!(history
+        .iter()
+        .all(|entry| entry.before.is_none() && entry.after.is_none()));
+
+    // Wishlists of other users keep their snapshots
+    let history = repository
+        .list_wishlist_history(other_wishlist_id)
+        .await
+        .unwrap();
+    assert!(history.iter().all(|entry| entry.after.is_some()));
+
+    repository
+        .erase_user(other_user_id, other.version)
+        .await
+        .unwrap();
+}
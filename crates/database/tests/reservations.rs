@@ -0,0 +1,77 @@
+//! Runs against a migrated PostgreSQL database given in `DATABASE_URL`:
+//!
+//! ```sh
+//! DATABASE_URL=postgres://postgres@localhost:5432/wishlists cargo test -p database -- --ignored
+//! ```
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use common::{item, repository, user, wishlist};
+use database::{traits::reservations, RepositoryTrait};
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+mod common;
+
+const RESERVERS: usize = 8;
+
+#[tokio::test]
+#[ignore = "needs a database"]
+async fn concurrent_reservations_cannot_exceed_the_quantity() {
+    let repository: Arc<dyn RepositoryTrait + Send + Sync> = Arc::new(repository().await);
+    let owner_id = Uuid::new_v4();
+    let wishlist_id = Uuid::new_v4();
+    let item_id = Uuid::new_v4();
+
+    let mut users = vec![repository.create_user(user(owner_id)).await.unwrap()];
+    repository
+        .create_wishlist(wishlist(wishlist_id, owner_id))
+        .await
+        .unwrap();
+    repository
+        .create_item(item(item_id, wishlist_id, 1), Some(owner_id))
+        .await
+        .unwrap();
+
+    let mut reservations = JoinSet::new();
+    for _ in 0..RESERVERS {
+        let user = repository.create_user(user(Uuid::new_v4())).await.unwrap();
+        let repository = repository.clone();
+        let payload = reservations::Payload {
+            id: Uuid::new_v4(),
+            item_id,
+            user_id: user.id,
+            quantity: 1,
+            created_at: Utc::now().naive_utc(),
+        };
+        users.push(user);
+
+        // Each one races for the last unit on a connection of its own
+        reservations.spawn(async move { repository.create_reservation(payload).await });
+    }
+
+    let mut reserved = 0;
+    while let Some(result) = reservations.join_next().await {
+        match result.unwrap() {
+            Ok(_) => reserved += 1,
+            Err(reservations::Error::InsufficientQuantity) => {}
+            Err(err) => panic!("Unexpected error: {err}"),
+        }
+    }
+
+    assert_eq!(reserved, 1);
+    assert_eq!(
+        repository
+            .get_item(item_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .reserved_quantity,
+        1
+    );
+
+    for user in users {
+        repository.erase_user(user.id, user.version).await.unwrap();
+    }
+}
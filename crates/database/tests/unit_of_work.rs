@@ -0,0 +1,129 @@
+//! Runs against a migrated PostgreSQL database given in `DATABASE_URL`:
+//!
+//! ```sh
+//! DATABASE_URL=postgres://postgres@localhost:5432/wishlists cargo test -p database -- --ignored
+//! ```
+
+use common::{repository, user, wishlist};
+use database::{
+    traits::{transactions, users, wishlists},
+    RepositoryTrait,
+};
+use uuid::Uuid;
+
+mod common;
+
+#[derive(Debug)]
+enum Error {
+    Transaction,
+    Users,
+    Wishlists,
+    Aborted,
+}
+
+impl From<transactions::Error> for Error {
+    fn from(_: transactions::Error) -> Self {
+        Error::Transaction
+    }
+}
+
+impl From<users::Error> for Error {
+    fn from(_: users::Error) -> Self {
+        Error::Users
+    }
+}
+
+impl From<wishlists::Error> for Error {
+    fn from(_: wishlists::Error) -> Self {
+        Error::Wishlists
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs a database"]
+async fn failing_operation_undoes_the_earlier_ones() {
+    let repository = repository().await;
+    let repository: &(dyn RepositoryTrait + Send + Sync) = &repository;
+    let user_id = Uuid::new_v4();
+
+    let result = repository
+        .unit_of_work(move |repository| {
+            Box::pin(async move {
+                repository.create_user(user(user_id)).await?;
+                // Nobody owns the wishlist, so creating it fails
+                repository
+                    .create_wishlist(wishlist(Uuid::new_v4(), Uuid::new_v4()))
+                    .await?;
+
+                Ok::<_, Error>(())
+            })
+        })
+        .await;
+
+    assert!(matches!(result, Err(Error::Wishlists)));
+    assert!(repository.get_user(user_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+#[ignore = "needs a database"]
+async fn failing_work_undoes_committed_operations() {
+    let repository = repository().await;
+    let repository: &(dyn RepositoryTrait + Send + Sync) = &repository;
+    let user_id = Uuid::new_v4();
+    let wishlist_id = Uuid::new_v4();
+
+    // Operations commit their own transactions, which are only savepoints
+    // inside the unit of work
+    let result = repository
+        .unit_of_work(move |repository| {
+            Box::pin(async move {
+                repository.create_user(user(user_id)).await?;
+                repository
+                    .create_wishlist(wishlist(wishlist_id, user_id))
+                    .await?;
+
+                Err::<(), _>(Error::Aborted)
+            })
+        })
+        .await;
+
+    assert!(matches!(result, Err(Error::Aborted)));
+    assert!(repository.get_user(user_id).await.unwrap().is_none());
+    assert!(repository
+        .get_wishlist(wishlist_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+#[ignore = "needs a database"]
+async fn successful_work_is_committed() {
+    let repository = repository().await;
+    let repository: &(dyn RepositoryTrait + Send + Sync) = &repository;
+    let user_id = Uuid::new_v4();
+    let wishlist_id = Uuid::new_v4();
+
+    repository
+        .unit_of_work(move |repository| {
+            Box::pin(async move {
+                repository.create_user(user(user_id)).await?;
+                repository
+                    .create_wishlist(wishlist(wishlist_id, user_id))
+                    .await?;
+
+                Ok::<_, Error>(())
+            })
+        })
+        .await
+        .unwrap();
+
+    assert!(repository.get_user(user_id).await.unwrap().is_some());
+    assert!(repository
+        .get_wishlist(wishlist_id)
+        .await
+        .unwrap()
+        .is_some());
+
+    repository.erase_user(user_id, 1).await.unwrap();
+}
@@ -8,26 +8,31 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: Uuid,
     pub wishlist_id: Uuid,
-    pub selected_by_id: Option<Uuid>,
     pub name: String,
     pub description: Option<String>,
-    pub price: Option<i32>,
+    pub price: Option<i64>,
     pub is_hidden: bool,
     pub picture_id: Option<Uuid>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub quantity: i32,
+    pub is_funded: bool,
+    pub currency: Option<String>,
+    pub url: Option<String>,
+    pub priority: i16,
+    pub position: i32,
+    pub deleted_at: Option<DateTime>,
+    pub version: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
-    #[sea_orm(
-        belongs_to = "super::users::Entity",
-        from = "Column::SelectedById",
-        to = "super::users::Column::Id",
-        on_update = "NoAction",
-        on_delete = "Cascade"
-    )]
-    Users,
+    #[sea_orm(has_many = "super::contributions::Entity")]
+    Contributions,
+    #[sea_orm(has_many = "super::notifications::Entity")]
+    Notifications,
+    #[sea_orm(has_many = "super::reservations::Entity")]
+    Reservations,
     #[sea_orm(
         belongs_to = "super::wishlists::Entity",
         from = "Column::WishlistId",
@@ -38,9 +43,21 @@ pub enum Relation {
     Wishlists,
 }
 
-impl Related<super::users::Entity> for Entity {
+impl Related<super::contributions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Contributions.def()
+    }
+}
+
+impl Related<super::notifications::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notifications.def()
+    }
+}
+
+impl Related<super::reservations::Entity> for Entity {
     fn to() -> RelationDef {
-        Relation::Users.def()
+        Relation::Reservations.def()
     }
 }
 
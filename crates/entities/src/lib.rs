@@ -2,7 +2,17 @@
 
 pub mod prelude;
 
+pub mod audit_log;
+pub mod blocks;
+pub mod contributions;
+pub mod idempotency_keys;
 pub mod items;
+pub mod notifications;
+pub mod reservations;
 pub mod subscriptions;
+pub mod template_items;
+pub mod templates;
 pub mod users;
+pub mod webhook_outbox;
+pub mod webhooks;
 pub mod wishlists;
@@ -1,6 +1,16 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.11.3
 
+pub use super::audit_log::Entity as AuditLog;
+pub use super::blocks::Entity as Blocks;
+pub use super::contributions::Entity as Contributions;
+pub use super::idempotency_keys::Entity as IdempotencyKeys;
 pub use super::items::Entity as Items;
+pub use super::notifications::Entity as Notifications;
+pub use super::reservations::Entity as Reservations;
 pub use super::subscriptions::Entity as Subscriptions;
+pub use super::template_items::Entity as TemplateItems;
+pub use super::templates::Entity as Templates;
 pub use super::users::Entity as Users;
+pub use super::webhook_outbox::Entity as WebhookOutbox;
+pub use super::webhooks::Entity as Webhooks;
 pub use super::wishlists::Entity as Wishlists;
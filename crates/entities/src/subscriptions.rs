@@ -10,6 +10,7 @@ pub struct Model {
     pub user_id: Uuid,
     pub subscriber_id: Uuid,
     pub created_at: DateTime,
+    pub status: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -11,19 +11,46 @@ pub struct Model {
     pub avatar_id: Option<Uuid>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub currency: String,
+    pub is_admin: bool,
+    pub deleted_at: Option<DateTime>,
+    pub email: Option<String>,
+    pub is_private: bool,
+    pub username: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub bio: Option<String>,
+    pub birthday: Option<Date>,
+    pub locale: Option<String>,
+    pub version: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
-    #[sea_orm(has_many = "super::items::Entity")]
-    Items,
+    #[sea_orm(has_many = "super::contributions::Entity")]
+    Contributions,
+    #[sea_orm(has_many = "super::notifications::Entity")]
+    Notifications,
+    #[sea_orm(has_many = "super::reservations::Entity")]
+    Reservations,
     #[sea_orm(has_many = "super::wishlists::Entity")]
     Wishlists,
 }
 
-impl Related<super::items::Entity> for Entity {
+impl Related<super::contributions::Entity> for Entity {
     fn to() -> RelationDef {
-        Relation::Items.def()
+        Relation::Contributions.def()
+    }
+}
+
+impl Related<super::notifications::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notifications.def()
+    }
+}
+
+impl Related<super::reservations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Reservations.def()
     }
 }
 
@@ -11,12 +11,18 @@ pub struct Model {
     pub user_id: Uuid,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub event_type: Option<String>,
+    pub event_date: Option<Date>,
+    pub deleted_at: Option<DateTime>,
+    pub version: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::items::Entity")]
     Items,
+    #[sea_orm(has_many = "super::notifications::Entity")]
+    Notifications,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::UserId",
@@ -25,6 +31,8 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Users,
+    #[sea_orm(has_many = "super::webhooks::Entity")]
+    Webhooks,
 }
 
 impl Related<super::items::Entity> for Entity {
@@ -33,10 +41,22 @@ impl Related<super::items::Entity> for Entity {
     }
 }
 
+impl Related<super::notifications::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notifications.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Users.def()
     }
 }
 
+impl Related<super::webhooks::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Webhooks.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,12 +1,56 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20230910_182812_base;
+mod m20231020_100000_item_reservations;
+mod m20231022_090000_item_contributions;
+mod m20231024_120000_money;
+mod m20231026_150000_item_urls;
+mod m20231028_110000_item_ordering;
+mod m20231030_090000_templates;
+mod m20231101_100000_wishlist_events;
+mod m20231103_120000_soft_delete;
+mod m20231105_090000_audit_log;
+mod m20231107_100000_notifications;
+mod m20231109_090000_webhooks;
+mod m20231111_090000_feed;
+mod m20231113_090000_follow_requests;
+mod m20231115_090000_blocks;
+mod m20231117_090000_user_profiles;
+mod m20231119_090000_account_erasure;
+mod m20231121_090000_versions;
+mod m20231123_090000_idempotency_keys;
+mod m20231125_090000_contribution_currencies;
+mod m20231127_090000_notification_claims;
+mod m20231129_090000_audit_redaction;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20230910_182812_base::Migration)]
+        vec![
+            Box::new(m20230910_182812_base::Migration),
+            Box::new(m20231020_100000_item_reservations::Migration),
+            Box::new(m20231022_090000_item_contributions::Migration),
+            Box::new(m20231024_120000_money::Migration),
+            Box::new(m20231026_150000_item_urls::Migration),
+            Box::new(m20231028_110000_item_ordering::Migration),
+            Box::new(m20231030_090000_templates::Migration),
+            Box::new(m20231101_100000_wishlist_events::Migration),
+            Box::new(m20231103_120000_soft_delete::Migration),
+            Box::new(m20231105_090000_audit_log::Migration),
+            Box::new(m20231107_100000_notifications::Migration),
+            Box::new(m20231109_090000_webhooks::Migration),
+            Box::new(m20231111_090000_feed::Migration),
+            Box::new(m20231113_090000_follow_requests::Migration),
+            Box::new(m20231115_090000_blocks::Migration),
+            Box::new(m20231117_090000_user_profiles::Migration),
+            Box::new(m20231119_090000_account_erasure::Migration),
+            Box::new(m20231121_090000_versions::Migration),
+            Box::new(m20231123_090000_idempotency_keys::Migration),
+            Box::new(m20231125_090000_contribution_currencies::Migration),
+            Box::new(m20231127_090000_notification_claims::Migration),
+            Box::new(m20231129_090000_audit_redaction::Migration),
+        ]
     }
 }
@@ -0,0 +1,194 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Items::Table)
+                    .add_column(
+                        ColumnDef::new(Items::Quantity)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Reservations::Table)
+                    .col(ColumnDef::new(Reservations::Id).uuid().primary_key())
+                    .col(ColumnDef::new(Reservations::ItemId).uuid().not_null())
+                    .col(ColumnDef::new(Reservations::UserId).uuid().not_null())
+                    .col(ColumnDef::new(Reservations::Quantity).integer().not_null())
+                    .col(
+                        ColumnDef::new(Reservations::CreatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_tbl(Reservations::Table)
+                            .from_col(Reservations::ItemId)
+                            .to_tbl(Items::Table)
+                            .to_col(Items::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_tbl(Reservations::Table)
+                            .from_col(Reservations::UserId)
+                            .to_tbl(Users::Table)
+                            .to_col(Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Reservations::Table)
+                    .name("idx_reservations_item_id")
+                    .col(Reservations::ItemId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(Reservations::Table)
+                    .columns([
+                        Reservations::Id,
+                        Reservations::ItemId,
+                        Reservations::UserId,
+                        Reservations::Quantity,
+                        Reservations::CreatedAt,
+                    ])
+                    .select_from(
+                        Query::select()
+                            .expr(Func::cust(GenRandomUuid))
+                            .column(Items::Id)
+                            .column(Items::SelectedById)
+                            .column(Items::Quantity)
+                            .column(Items::UpdatedAt)
+                            .from(Items::Table)
+                            .and_where(Expr::col(Items::SelectedById).is_not_null())
+                            .to_owned(),
+                    )
+                    .map_err(|err| DbErr::Migration(err.to_string()))?
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Items::Table)
+                    .drop_column(Items::SelectedById)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Items::Table)
+                    .add_column(ColumnDef::new(Items::SelectedById).uuid())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .from_tbl(Items::Table)
+                            .from_col(Items::SelectedById)
+                            .to_tbl(Users::Table)
+                            .to_col(Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Items::Table)
+                    .value(
+                        Items::SelectedById,
+                        SimpleExpr::SubQuery(
+                            None,
+                            Box::new(
+                                Query::select()
+                                    .column(Reservations::UserId)
+                                    .from(Reservations::Table)
+                                    .and_where(
+                                        Expr::col((Reservations::Table, Reservations::ItemId))
+                                            .equals((Items::Table, Items::Id)),
+                                    )
+                                    .order_by(Reservations::CreatedAt, Order::Asc)
+                                    .limit(1)
+                                    .to_owned()
+                                    .into_sub_query_statement(),
+                            ),
+                        ),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Reservations::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Items::Table)
+                    .drop_column(Items::Quantity)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+#[iden = "gen_random_uuid"]
+struct GenRandomUuid;
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Items {
+    Table,
+    Id,
+    SelectedById,
+    Quantity,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Reservations {
+    Table,
+    Id,
+    ItemId,
+    UserId,
+    Quantity,
+    CreatedAt,
+}
@@ -0,0 +1,110 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Items::Table)
+                    .add_column(
+                        ColumnDef::new(Items::IsFunded)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Contributions::Table)
+                    .col(ColumnDef::new(Contributions::Id).uuid().primary_key())
+                    .col(ColumnDef::new(Contributions::ItemId).uuid().not_null())
+                    .col(ColumnDef::new(Contributions::UserId).uuid().not_null())
+                    .col(ColumnDef::new(Contributions::Amount).integer().not_null())
+                    .col(ColumnDef::new(Contributions::Note).string_len(300))
+                    .col(
+                        ColumnDef::new(Contributions::CreatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_tbl(Contributions::Table)
+                            .from_col(Contributions::ItemId)
+                            .to_tbl(Items::Table)
+                            .to_col(Items::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_tbl(Contributions::Table)
+                            .from_col(Contributions::UserId)
+                            .to_tbl(Users::Table)
+                            .to_col(Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Contributions::Table)
+                    .name("idx_contributions_item_id")
+                    .col(Contributions::ItemId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Contributions::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Items::Table)
+                    .drop_column(Items::IsFunded)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Items {
+    Table,
+    Id,
+    IsFunded,
+}
+
+#[derive(Iden)]
+enum Contributions {
+    Table,
+    Id,
+    ItemId,
+    UserId,
+    Amount,
+    Note,
+    CreatedAt,
+}
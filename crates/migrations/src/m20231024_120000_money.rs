@@ -0,0 +1,278 @@
+use sea_orm_migration::prelude::*;
+
+// Currencies whose minor unit is not a hundredth of the major one
+static ZERO_DECIMAL_CURRENCIES: &[&str] = &[
+    "BIF", "CLP", "DJF", "GNF", "ISK", "JPY", "KMF", "KRW", "PYG", "RWF", "UGX", "VND", "VUV",
+    "XAF", "XOF", "XPF",
+];
+static THREE_DECIMAL_CURRENCIES: &[&str] = &["BHD", "IQD", "JOD", "KWD", "LYD", "OMR", "TND"];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// Number of minor units in a whole unit of the given currency
+fn minor_units_factor(currency: SimpleExpr) -> SimpleExpr {
+    Expr::case(
+        Expr::expr(currency.clone()).is_in(ZERO_DECIMAL_CURRENCIES.iter().copied()),
+        1,
+    )
+    .case(
+        Expr::expr(currency).is_in(THREE_DECIMAL_CURRENCIES.iter().copied()),
+        1000,
+    )
+    .finally(100)
+    .into()
+}
+
+// Currency of the item a contribution went to, the owner's one for unpriced items
+fn contribution_currency() -> SimpleExpr {
+    SimpleExpr::SubQuery(
+        None,
+        Box::new(
+            Query::select()
+                .expr(
+                    Expr::col((Items::Table, Items::Currency))
+                        .if_null(Expr::col((Users::Table, Users::Currency))),
+                )
+                .from(Items::Table)
+                .inner_join(
+                    Wishlists::Table,
+                    Expr::col((Wishlists::Table, Wishlists::Id))
+                        .equals((Items::Table, Items::WishlistId)),
+                )
+                .inner_join(
+                    Users::Table,
+                    Expr::col((Users::Table, Users::Id))
+                        .equals((Wishlists::Table, Wishlists::UserId)),
+                )
+                .and_where(
+                    Expr::col((Items::Table, Items::Id))
+                        .equals((Contributions::Table, Contributions::ItemId)),
+                )
+                .to_owned()
+                .into_sub_query_statement(),
+        ),
+    )
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(
+                        ColumnDef::new(Users::Currency)
+                            .string_len(3)
+                            .not_null()
+                            .default("USD"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Items::Table)
+                    .modify_column(ColumnDef::new(Items::Price).big_integer())
+                    .add_column(ColumnDef::new(Items::Currency).string_len(3))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Contributions::Table)
+                    .modify_column(ColumnDef::new(Contributions::Amount).big_integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Prices used to be whole units of an unspecified currency, so they are
+        // converted to minor units of the owner's default currency.
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Items::Table)
+                    .value(
+                        Items::Currency,
+                        SimpleExpr::SubQuery(
+                            None,
+                            Box::new(
+                                Query::select()
+                                    .column((Users::Table, Users::Currency))
+                                    .from(Users::Table)
+                                    .inner_join(
+                                        Wishlists::Table,
+                                        Expr::col((Wishlists::Table, Wishlists::UserId))
+                                            .equals((Users::Table, Users::Id)),
+                                    )
+                                    .and_where(
+                                        Expr::col((Wishlists::Table, Wishlists::Id))
+                                            .equals((Items::Table, Items::WishlistId)),
+                                    )
+                                    .to_owned()
+                                    .into_sub_query_statement(),
+                            ),
+                        ),
+                    )
+                    .and_where(Expr::col(Items::Price).is_not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Items::Table)
+                    .value(
+                        Items::Price,
+                        Expr::col(Items::Price)
+                            .mul(minor_units_factor(Expr::col(Items::Currency).into())),
+                    )
+                    .and_where(Expr::col(Items::Price).is_not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Contributions::Table)
+                    .value(
+                        Contributions::Amount,
+                        Expr::col(Contributions::Amount)
+                            .mul(minor_units_factor(contribution_currency())),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE items ADD CONSTRAINT items_price_currency_check \
+                 CHECK ((price IS NULL) = (currency IS NULL))",
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Items::Table)
+                    .name("idx_items_currency_price")
+                    .col(Items::Currency)
+                    .col(Items::Price)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(Items::Table)
+                    .name("idx_items_currency_price")
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("ALTER TABLE items DROP CONSTRAINT items_price_currency_check")
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Contributions::Table)
+                    .value(
+                        Contributions::Amount,
+                        Expr::col(Contributions::Amount)
+                            .div(minor_units_factor(contribution_currency())),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Items::Table)
+                    .value(
+                        Items::Price,
+                        Expr::col(Items::Price)
+                            .div(minor_units_factor(Expr::col(Items::Currency).into())),
+                    )
+                    .and_where(Expr::col(Items::Price).is_not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Contributions::Table)
+                    .modify_column(ColumnDef::new(Contributions::Amount).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Items::Table)
+                    .modify_column(ColumnDef::new(Items::Price).integer())
+                    .drop_column(Items::Currency)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::Currency)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+    Currency,
+}
+
+#[derive(Iden)]
+enum Wishlists {
+    Table,
+    Id,
+    UserId,
+}
+
+#[derive(Iden)]
+enum Items {
+    Table,
+    Id,
+    WishlistId,
+    Price,
+    Currency,
+}
+
+#[derive(Iden)]
+enum Contributions {
+    Table,
+    ItemId,
+    Amount,
+}
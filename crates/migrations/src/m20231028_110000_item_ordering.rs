@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Items::Table)
+                    .add_column(
+                        ColumnDef::new(Items::Priority)
+                            .small_integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .add_column(
+                        ColumnDef::new(Items::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE items SET position = ordered.position \
+                 FROM (SELECT id, ROW_NUMBER() OVER (PARTITION BY wishlist_id \
+                 ORDER BY updated_at DESC, id DESC) - 1 AS position FROM items) AS ordered \
+                 WHERE items.id = ordered.id",
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Items::Table)
+                    .name("idx_items_wishlist_id_position")
+                    .col(Items::WishlistId)
+                    .col(Items::Position)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(Items::Table)
+                    .name("idx_items_wishlist_id_position")
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Items::Table)
+                    .drop_column(Items::Priority)
+                    .drop_column(Items::Position)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Items {
+    Table,
+    WishlistId,
+    Priority,
+    Position,
+}
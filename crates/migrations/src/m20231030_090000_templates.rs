@@ -0,0 +1,140 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(
+                        ColumnDef::new(Users::IsAdmin)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Templates::Table)
+                    .col(ColumnDef::new(Templates::Id).uuid().primary_key())
+                    .col(ColumnDef::new(Templates::Name).string_len(100).not_null())
+                    .col(ColumnDef::new(Templates::Description).string_len(300))
+                    .col(ColumnDef::new(Templates::CreatedAt).timestamp().not_null())
+                    .col(ColumnDef::new(Templates::UpdatedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TemplateItems::Table)
+                    .col(ColumnDef::new(TemplateItems::Id).uuid().primary_key())
+                    .col(ColumnDef::new(TemplateItems::TemplateId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(TemplateItems::Name)
+                            .string_len(100)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TemplateItems::Description).string_len(300))
+                    .col(ColumnDef::new(TemplateItems::Url).string_len(2048))
+                    .col(ColumnDef::new(TemplateItems::Price).big_integer())
+                    .col(ColumnDef::new(TemplateItems::Currency).string_len(3))
+                    .col(
+                        ColumnDef::new(TemplateItems::Quantity)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(TemplateItems::Priority)
+                            .small_integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(ColumnDef::new(TemplateItems::Position).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_tbl(TemplateItems::Table)
+                            .from_col(TemplateItems::TemplateId)
+                            .to_tbl(Templates::Table)
+                            .to_col(Templates::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(TemplateItems::Table)
+                    .name("idx_template_items_template_id")
+                    .col(TemplateItems::TemplateId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TemplateItems::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Templates::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::IsAdmin)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    IsAdmin,
+}
+
+#[derive(Iden)]
+enum Templates {
+    Table,
+    Id,
+    Name,
+    Description,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum TemplateItems {
+    Table,
+    Id,
+    TemplateId,
+    Name,
+    Description,
+    Url,
+    Price,
+    Currency,
+    Quantity,
+    Priority,
+    Position,
+}
@@ -0,0 +1,61 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Wishlists::Table)
+                    .add_column(ColumnDef::new(Wishlists::EventType).string_len(32))
+                    .add_column(ColumnDef::new(Wishlists::EventDate).date())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Wishlists::Table)
+                    .name("idx_wishlists_event_date")
+                    .col(Wishlists::EventDate)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(Wishlists::Table)
+                    .name("idx_wishlists_event_date")
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Wishlists::Table)
+                    .drop_column(Wishlists::EventType)
+                    .drop_column(Wishlists::EventDate)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Wishlists {
+    Table,
+    EventType,
+    EventDate,
+}
@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [Tables::Users, Tables::Wishlists, Tables::Items] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .add_column(ColumnDef::new(DeletedAt).timestamp())
+                        .to_owned(),
+                )
+                .await?;
+
+            manager
+                .create_index(
+                    Index::create()
+                        .table(table)
+                        .name(format!("idx_{}_deleted_at", table.to_string()))
+                        .col(DeletedAt)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [Tables::Users, Tables::Wishlists, Tables::Items] {
+            manager
+                .drop_index(
+                    Index::drop()
+                        .table(table)
+                        .name(format!("idx_{}_deleted_at", table.to_string()))
+                        .to_owned(),
+                )
+                .await?;
+
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(table)
+                        .drop_column(DeletedAt)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+struct DeletedAt;
+
+#[derive(Iden, Clone, Copy)]
+enum Tables {
+    Users,
+    Wishlists,
+    Items,
+}
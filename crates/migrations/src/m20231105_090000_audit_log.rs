@@ -0,0 +1,99 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLog::Table)
+                    .col(ColumnDef::new(AuditLog::Id).uuid().primary_key())
+                    .col(ColumnDef::new(AuditLog::ActorId).uuid())
+                    .col(
+                        ColumnDef::new(AuditLog::EntityType)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AuditLog::EntityId).uuid().not_null())
+                    .col(ColumnDef::new(AuditLog::WishlistId).uuid())
+                    .col(ColumnDef::new(AuditLog::Action).string_len(16).not_null())
+                    .col(ColumnDef::new(AuditLog::Before).json_binary())
+                    .col(ColumnDef::new(AuditLog::After).json_binary())
+                    .col(ColumnDef::new(AuditLog::CreatedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(AuditLog::Table)
+                    .name("idx_audit_log_wishlist_id_created_at")
+                    .col(AuditLog::WishlistId)
+                    .col(AuditLog::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(AuditLog::Table)
+                    .name("idx_audit_log_entity_type_entity_id")
+                    .col(AuditLog::EntityType)
+                    .col(AuditLog::EntityId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE FUNCTION audit_log_append_only() RETURNS trigger AS $$ \
+                 BEGIN RAISE EXCEPTION 'audit_log is append-only'; END; \
+                 $$ LANGUAGE plpgsql",
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE TRIGGER audit_log_append_only \
+                 BEFORE UPDATE OR DELETE ON audit_log \
+                 FOR EACH ROW EXECUTE FUNCTION audit_log_append_only()",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditLog::Table).to_owned())
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("DROP FUNCTION audit_log_append_only")
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum AuditLog {
+    Table,
+    Id,
+    ActorId,
+    EntityType,
+    EntityId,
+    WishlistId,
+    Action,
+    Before,
+    After,
+    CreatedAt,
+}
@@ -0,0 +1,140 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(ColumnDef::new(Users::Email).string_len(254))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notifications::Table)
+                    .col(ColumnDef::new(Notifications::Id).uuid().primary_key())
+                    .col(ColumnDef::new(Notifications::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(Notifications::Kind)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Notifications::ActorId).uuid())
+                    .col(ColumnDef::new(Notifications::WishlistId).uuid())
+                    .col(ColumnDef::new(Notifications::ItemId).uuid())
+                    .col(ColumnDef::new(Notifications::ReadAt).timestamp())
+                    .col(ColumnDef::new(Notifications::DeliveredAt).timestamp())
+                    .col(
+                        ColumnDef::new(Notifications::CreatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_tbl(Notifications::Table)
+                            .from_col(Notifications::UserId)
+                            .to_tbl(Users::Table)
+                            .to_col(Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_tbl(Notifications::Table)
+                            .from_col(Notifications::WishlistId)
+                            .to_tbl(Wishlists::Table)
+                            .to_col(Wishlists::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_tbl(Notifications::Table)
+                            .from_col(Notifications::ItemId)
+                            .to_tbl(Items::Table)
+                            .to_col(Items::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Notifications::Table)
+                    .name("idx_notifications_user_id_created_at")
+                    .col(Notifications::UserId)
+                    .col(Notifications::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Notifications::Table)
+                    .name("idx_notifications_delivered_at")
+                    .col(Notifications::DeliveredAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Notifications::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::Email)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+    Email,
+}
+
+#[derive(Iden)]
+enum Wishlists {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Items {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Notifications {
+    Table,
+    Id,
+    UserId,
+    Kind,
+    ActorId,
+    WishlistId,
+    ItemId,
+    ReadAt,
+    DeliveredAt,
+    CreatedAt,
+}
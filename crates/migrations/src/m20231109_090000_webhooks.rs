@@ -0,0 +1,155 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Webhooks::Table)
+                    .col(ColumnDef::new(Webhooks::Id).uuid().primary_key())
+                    .col(ColumnDef::new(Webhooks::WishlistId).uuid().not_null())
+                    .col(ColumnDef::new(Webhooks::Url).string_len(2048).not_null())
+                    .col(ColumnDef::new(Webhooks::Secret).string_len(64).not_null())
+                    .col(ColumnDef::new(Webhooks::CreatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_tbl(Webhooks::Table)
+                            .from_col(Webhooks::WishlistId)
+                            .to_tbl(Wishlists::Table)
+                            .to_col(Wishlists::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Webhooks::Table)
+                    .name("idx_webhooks_wishlist_id")
+                    .col(Webhooks::WishlistId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookOutbox::Table)
+                    .col(ColumnDef::new(WebhookOutbox::Id).uuid().primary_key())
+                    .col(ColumnDef::new(WebhookOutbox::WebhookId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(WebhookOutbox::Event)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookOutbox::Payload)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookOutbox::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookOutbox::NextAttemptAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WebhookOutbox::LastStatusCode).small_integer())
+                    .col(ColumnDef::new(WebhookOutbox::LastError).text())
+                    .col(ColumnDef::new(WebhookOutbox::DeliveredAt).timestamp())
+                    .col(ColumnDef::new(WebhookOutbox::FailedAt).timestamp())
+                    .col(
+                        ColumnDef::new(WebhookOutbox::CreatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_tbl(WebhookOutbox::Table)
+                            .from_col(WebhookOutbox::WebhookId)
+                            .to_tbl(Webhooks::Table)
+                            .to_col(Webhooks::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(WebhookOutbox::Table)
+                    .name("idx_webhook_outbox_webhook_id_created_at")
+                    .col(WebhookOutbox::WebhookId)
+                    .col(WebhookOutbox::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(WebhookOutbox::Table)
+                    .name("idx_webhook_outbox_next_attempt_at")
+                    .col(WebhookOutbox::NextAttemptAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebhookOutbox::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Webhooks::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Wishlists {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Webhooks {
+    Table,
+    Id,
+    WishlistId,
+    Url,
+    Secret,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum WebhookOutbox {
+    Table,
+    Id,
+    WebhookId,
+    Event,
+    Payload,
+    Attempts,
+    NextAttemptAt,
+    LastStatusCode,
+    LastError,
+    DeliveredAt,
+    FailedAt,
+    CreatedAt,
+}
@@ -0,0 +1,94 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                Index::create()
+                    .table(Subscriptions::Table)
+                    .name("idx_subscriptions_subscriber_id")
+                    .col(Subscriptions::SubscriberId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Wishlists::Table)
+                    .name("idx_wishlists_user_id_created_at")
+                    .col(Wishlists::UserId)
+                    .col(Wishlists::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Items::Table)
+                    .name("idx_items_wishlist_id_created_at")
+                    .col(Items::WishlistId)
+                    .col(Items::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(Items::Table)
+                    .name("idx_items_wishlist_id_created_at")
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(Wishlists::Table)
+                    .name("idx_wishlists_user_id_created_at")
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(Subscriptions::Table)
+                    .name("idx_subscriptions_subscriber_id")
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Subscriptions {
+    Table,
+    SubscriberId,
+}
+
+#[derive(Iden)]
+enum Wishlists {
+    Table,
+    UserId,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum Items {
+    Table,
+    WishlistId,
+    CreatedAt,
+}
@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Blocks::Table)
+                    .col(ColumnDef::new(Blocks::Id).uuid().primary_key())
+                    .col(ColumnDef::new(Blocks::UserId).uuid().not_null())
+                    .col(ColumnDef::new(Blocks::TargetId).uuid().not_null())
+                    .col(ColumnDef::new(Blocks::Kind).string_len(16).not_null())
+                    .col(ColumnDef::new(Blocks::CreatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_tbl(Blocks::Table)
+                            .from_col(Blocks::UserId)
+                            .to_tbl(Users::Table)
+                            .to_col(Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_tbl(Blocks::Table)
+                            .from_col(Blocks::TargetId)
+                            .to_tbl(Users::Table)
+                            .to_col(Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Blocks::Table)
+                    .name("idx_blocks_user_id_target_id_kind")
+                    .col(Blocks::UserId)
+                    .col(Blocks::TargetId)
+                    .col(Blocks::Kind)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Blocks::Table)
+                    .name("idx_blocks_target_id")
+                    .col(Blocks::TargetId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Blocks::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Blocks {
+    Table,
+    Id,
+    UserId,
+    TargetId,
+    Kind,
+    CreatedAt,
+}
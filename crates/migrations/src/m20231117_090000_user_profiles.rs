@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Existing users have no username until they pick one
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(ColumnDef::new(Users::Username).string_len(32))
+                    .add_column(ColumnDef::new(Users::Bio).text())
+                    .add_column(ColumnDef::new(Users::Birthday).date())
+                    .add_column(ColumnDef::new(Users::Locale).string_len(35))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Usernames keep the case they were chosen with but are unique regardless
+        // of it, the lookup by username goes through the same expression
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE UNIQUE INDEX idx_users_lower_username ON users (LOWER(username))",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(Users::Table)
+                    .name("idx_users_lower_username")
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::Username)
+                    .drop_column(Users::Bio)
+                    .drop_column(Users::Birthday)
+                    .drop_column(Users::Locale)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Username,
+    Bio,
+    Birthday,
+    Locale,
+}
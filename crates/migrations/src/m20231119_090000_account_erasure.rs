@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Reservations and contributions on other people's items outlive the
+        // account that made them, they only lose the reference to it
+        for table in ["reservations", "contributions"] {
+            manager
+                .get_connection()
+                .execute_unprepared(&format!(
+                    "ALTER TABLE {table} ALTER COLUMN user_id DROP NOT NULL, \
+                     DROP CONSTRAINT {table}_user_id_fkey, \
+                     ADD CONSTRAINT {table}_user_id_fkey FOREIGN KEY (user_id) \
+                     REFERENCES users (id) ON DELETE SET NULL"
+                ))
+                .await?;
+        }
+
+        // Audit entries stay append-only, but erasing an account may redact the
+        // snapshots and the actor of existing ones
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS trigger AS $$ \
+                 BEGIN \
+                 IF TG_OP = 'UPDATE' \
+                 AND (NEW.id, NEW.entity_type, NEW.entity_id, NEW.wishlist_id, NEW.action, \
+                 NEW.created_at) IS NOT DISTINCT FROM (OLD.id, OLD.entity_type, OLD.entity_id, \
+                 OLD.wishlist_id, OLD.action, OLD.created_at) \
+                 AND (NEW.actor_id IS NULL OR NEW.actor_id = OLD.actor_id) \
+                 AND (NEW.before IS NULL OR NEW.before = OLD.before) \
+                 AND (NEW.after IS NULL OR NEW.after = OLD.after) THEN \
+                 RETURN NEW; \
+                 END IF; \
+                 RAISE EXCEPTION 'audit_log is append-only'; \
+                 END; \
+                 $$ LANGUAGE plpgsql",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS trigger AS $$ \
+                 BEGIN RAISE EXCEPTION 'audit_log is append-only'; END; \
+                 $$ LANGUAGE plpgsql",
+            )
+            .await?;
+
+        for table in ["reservations", "contributions"] {
+            manager
+                .get_connection()
+                .execute_unprepared(&format!(
+                    "DELETE FROM {table} WHERE user_id IS NULL; \
+                     ALTER TABLE {table} ALTER COLUMN user_id SET NOT NULL, \
+                     DROP CONSTRAINT {table}_user_id_fkey, \
+                     ADD CONSTRAINT {table}_user_id_fkey FOREIGN KEY (user_id) \
+                     REFERENCES users (id) ON DELETE CASCADE"
+                ))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
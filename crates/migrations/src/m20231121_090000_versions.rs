@@ -0,0 +1,116 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const TABLES: [&str; 3] = ["users", "wishlists", "items"];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in TABLES {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .add_column(
+                            ColumnDef::new(Alias::new("version"))
+                                .integer()
+                                .not_null()
+                                .default(1),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // Every write bumps the version, whichever code path it comes from
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE FUNCTION bump_version() RETURNS trigger AS $$ \
+                 BEGIN \
+                 NEW.version := OLD.version + 1; \
+                 RETURN NEW; \
+                 END; \
+                 $$ LANGUAGE plpgsql",
+            )
+            .await?;
+
+        for table in TABLES {
+            manager
+                .get_connection()
+                .execute_unprepared(&format!(
+                    "CREATE TRIGGER {table}_version BEFORE UPDATE ON {table} \
+                     FOR EACH ROW EXECUTE FUNCTION bump_version()"
+                ))
+                .await?;
+        }
+
+        // Reserved quantities and contributed amounts are part of an item, so
+        // changing them makes for a new version of it as well
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE FUNCTION bump_item_version() RETURNS trigger AS $$ \
+                 BEGIN \
+                 IF TG_OP <> 'INSERT' THEN \
+                 UPDATE items SET version = version + 1 WHERE id = OLD.item_id; \
+                 END IF; \
+                 IF TG_OP = 'INSERT' OR (TG_OP = 'UPDATE' AND NEW.item_id <> OLD.item_id) THEN \
+                 UPDATE items SET version = version + 1 WHERE id = NEW.item_id; \
+                 END IF; \
+                 RETURN NULL; \
+                 END; \
+                 $$ LANGUAGE plpgsql",
+            )
+            .await?;
+
+        for table in ["reservations", "contributions"] {
+            manager
+                .get_connection()
+                .execute_unprepared(&format!(
+                    "CREATE TRIGGER {table}_item_version \
+                     AFTER INSERT OR UPDATE OR DELETE ON {table} \
+                     FOR EACH ROW EXECUTE FUNCTION bump_item_version()"
+                ))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in ["reservations", "contributions"] {
+            manager
+                .get_connection()
+                .execute_unprepared(&format!("DROP TRIGGER {table}_item_version ON {table}"))
+                .await?;
+        }
+
+        for table in TABLES {
+            manager
+                .get_connection()
+                .execute_unprepared(&format!("DROP TRIGGER {table}_version ON {table}"))
+                .await?;
+        }
+
+        manager
+            .get_connection()
+            .execute_unprepared("DROP FUNCTION bump_item_version(); DROP FUNCTION bump_version()")
+            .await?;
+
+        for table in TABLES {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .drop_column(Alias::new("version"))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Contributions::Table)
+                    .add_column(ColumnDef::new(Contributions::Currency).string_len(3))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Contributions so far were made in the currency of their item, or the
+        // owner's one when the item had no price
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Contributions::Table)
+                    .value(
+                        Contributions::Currency,
+                        SimpleExpr::SubQuery(
+                            None,
+                            Box::new(
+                                Query::select()
+                                    .expr(
+                                        Expr::col((Items::Table, Items::Currency))
+                                            .if_null(Expr::col((Users::Table, Users::Currency))),
+                                    )
+                                    .from(Items::Table)
+                                    .inner_join(
+                                        Wishlists::Table,
+                                        Expr::col((Wishlists::Table, Wishlists::Id))
+                                            .equals((Items::Table, Items::WishlistId)),
+                                    )
+                                    .inner_join(
+                                        Users::Table,
+                                        Expr::col((Users::Table, Users::Id))
+                                            .equals((Wishlists::Table, Wishlists::UserId)),
+                                    )
+                                    .and_where(
+                                        Expr::col((Items::Table, Items::Id))
+                                            .equals((Contributions::Table, Contributions::ItemId)),
+                                    )
+                                    .to_owned()
+                                    .into_sub_query_statement(),
+                            ),
+                        ),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Contributions::Table)
+                    .modify_column(ColumnDef::new(Contributions::Currency).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Contributions::Table)
+                    .drop_column(Contributions::Currency)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Users {
+    Table,
+    Id,
+    Currency,
+}
+
+#[derive(Iden)]
+enum Wishlists {
+    Table,
+    Id,
+    UserId,
+}
+
+#[derive(Iden)]
+enum Items {
+    Table,
+    Id,
+    WishlistId,
+    Currency,
+}
+
+#[derive(Iden)]
+enum Contributions {
+    Table,
+    ItemId,
+    Currency,
+}
@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // A snapshot may also be redacted field by field, as long as the fields
+        // are only set to null. Rows referencing an erased account keep the rest.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE FUNCTION audit_log_redacts(new jsonb, old jsonb) RETURNS boolean AS $$ \
+                 SELECT new IS NULL OR new = old OR ( \
+                 jsonb_typeof(new) = 'object' AND jsonb_typeof(old) = 'object' \
+                 AND NOT EXISTS (SELECT 1 FROM jsonb_each(new) n \
+                 WHERE NOT old ? n.key OR (n.value <> 'null' AND n.value <> old -> n.key)) \
+                 AND NOT EXISTS (SELECT 1 FROM jsonb_object_keys(old) k WHERE NOT new ? k)) \
+                 $$ LANGUAGE sql IMMUTABLE",
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS trigger AS $$ \
+                 BEGIN \
+                 IF TG_OP = 'UPDATE' \
+                 AND (NEW.id, NEW.entity_type, NEW.entity_id, NEW.wishlist_id, NEW.action, \
+                 NEW.created_at) IS NOT DISTINCT FROM (OLD.id, OLD.entity_type, OLD.entity_id, \
+                 OLD.wishlist_id, OLD.action, OLD.created_at) \
+                 AND (NEW.actor_id IS NULL OR NEW.actor_id = OLD.actor_id) \
+                 AND audit_log_redacts(NEW.before, OLD.before) \
+                 AND audit_log_redacts(NEW.after, OLD.after) THEN \
+                 RETURN NEW; \
+                 END IF; \
+                 RAISE EXCEPTION 'audit_log is append-only'; \
+                 END; \
+                 $$ LANGUAGE plpgsql",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS trigger AS $$ \
+                 BEGIN \
+                 IF TG_OP = 'UPDATE' \
+                 AND (NEW.id, NEW.entity_type, NEW.entity_id, NEW.wishlist_id, NEW.action, \
+                 NEW.created_at) IS NOT DISTINCT FROM (OLD.id, OLD.entity_type, OLD.entity_id, \
+                 OLD.wishlist_id, OLD.action, OLD.created_at) \
+                 AND (NEW.actor_id IS NULL OR NEW.actor_id = OLD.actor_id) \
+                 AND (NEW.before IS NULL OR NEW.before = OLD.before) \
+                 AND (NEW.after IS NULL OR NEW.after = OLD.after) THEN \
+                 RETURN NEW; \
+                 END IF; \
+                 RAISE EXCEPTION 'audit_log is append-only'; \
+                 END; \
+                 $$ LANGUAGE plpgsql",
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("DROP FUNCTION audit_log_redacts")
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use std::{net::SocketAddr, time::Duration};
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
 
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use database::{
@@ -8,6 +8,9 @@ use database::{
     DatabaseConnectOptions,
 };
 use tracing_subscriber::filter::LevelFilter;
+use uuid::Uuid;
+
+use crate::transfer::Format;
 
 const ENV_PREFIX: &str = "WISHLISTS";
 const ENV_SEPARATOR: &str = "__";
@@ -15,12 +18,22 @@ const ENV_SEPARATOR: &str = "__";
 const DATABASE_ENV_PREFIX: &str = "DATABASE";
 const BLOB_STORAGE_ENV_PREFIX: &str = "BS";
 const RUN_ENV_PREFIX: &str = "RUN";
+const PURGE_ENV_PREFIX: &str = "PURGE";
+const NOTIFICATIONS_ENV_PREFIX: &str = "NOTIFICATIONS";
+const WEBHOOKS_ENV_PREFIX: &str = "WEBHOOKS";
+const IMPORT_ENV_PREFIX: &str = "IMPORT";
+const EXPORT_ENV_PREFIX: &str = "EXPORT";
 const LOG_ENV_PREFIX: &str = "LOG";
 
 const LONG_SEPARATOR: &str = "-";
 const BLOB_STORAGE_LONG_PREFIX: &str = "bs";
 const DATABASE_LONG_PREFIX: &str = "database";
 const RUN_LONG_PREFIX: &str = "run";
+const PURGE_LONG_PREFIX: &str = "purge";
+const NOTIFICATIONS_LONG_PREFIX: &str = "notifications";
+const WEBHOOKS_LONG_PREFIX: &str = "webhooks";
+const IMPORT_LONG_PREFIX: &str = "import";
+const EXPORT_LONG_PREFIX: &str = "export";
 const LOG_LONG_PREFIX: &str = "log";
 
 struct ArgMetadata {
@@ -64,7 +77,7 @@ where
         if let Some(prefix) = metadata.prefix {
             res.push_str(&prefix);
             res.push_str(&metadata.separator);
-        };
+        }
         res.push_str(&values.join(&metadata.separator));
         res
     }
@@ -91,6 +104,12 @@ pub enum Commands {
     Run(RunArgs),
     #[command(about = "Run database migrations and exit")]
     Migrate,
+    #[command(about = "Permanently remove soft-deleted objects and exit")]
+    Purge(PurgeArgs),
+    #[command(about = "Create a wishlist from a JSON or CSV file and exit")]
+    Import(ImportArgs),
+    #[command(about = "Write a wishlist to a JSON or CSV file and exit")]
+    Export(ExportArgs),
 }
 
 #[derive(Args)]
@@ -206,8 +225,9 @@ pub struct BlobStorageArgs {
     )]
     session_token: Option<String>,
     #[arg(
-        long = LongArg::construct(&[BLOB_STORAGE_LONG_PREFIX,"session-token"]),
-        env = EnvArg::construct(&[BLOB_STORAGE_ENV_PREFIX,"SESSION_TOKEN"]),
+        long = LongArg::construct(&[BLOB_STORAGE_LONG_PREFIX,"endpoint-url"]),
+        env = EnvArg::construct(&[BLOB_STORAGE_ENV_PREFIX,"ENDPOINT_URL"]),
+        default_value = "",
         help = "Blob storage endpoint url",
         global = true
     )]
@@ -215,7 +235,7 @@ pub struct BlobStorageArgs {
     #[arg(
         long = LongArg::construct(&[BLOB_STORAGE_LONG_PREFIX,"region"]),
         env = EnvArg::construct(&[BLOB_STORAGE_ENV_PREFIX,"REGION"]),
-        default_value = None,
+        default_value = "",
         help = "Blob storage region",
         global = true
     )]
@@ -228,6 +248,14 @@ pub struct BlobStorageArgs {
         global = true
     )]
     pub force_path_style: bool,
+    #[arg(
+        long = LongArg::construct(&[BLOB_STORAGE_LONG_PREFIX,"bucket"]),
+        env = EnvArg::construct(&[BLOB_STORAGE_ENV_PREFIX,"BUCKET"]),
+        default_value = "wishlists",
+        help = "Blob storage bucket for avatars and item pictures",
+        global = true
+    )]
+    pub bucket: String,
 }
 
 impl From<BlobStorageArgs> for BlobStorageConfig {
@@ -241,6 +269,7 @@ impl From<BlobStorageArgs> for BlobStorageConfig {
         );
 
         BlobStorageConfig::builder()
+            .behavior_version_latest()
             .endpoint_url(value.endpoint_url)
             .region(BlobStorageRegion::new(value.region))
             .force_path_style(value.force_path_style)
@@ -289,6 +318,119 @@ pub struct RunArgs {
         help = "Address where app listens to incoming connections (<host>:<port>)"
     )]
     pub bind_address: SocketAddr,
+    #[command(flatten)]
+    pub notifications: NotificationsArgs,
+    #[command(flatten)]
+    pub webhooks: WebhooksArgs,
+}
+
+#[derive(Args, PartialEq, Eq)]
+pub struct NotificationsArgs {
+    #[arg(
+        long = LongArg::construct(&[NOTIFICATIONS_LONG_PREFIX,"interval"]),
+        env = EnvArg::construct(&[NOTIFICATIONS_ENV_PREFIX,"INTERVAL"]),
+        default_value = "5",
+        help = "Number of seconds between notification deliveries"
+    )]
+    pub interval: u64,
+    #[arg(
+        long = LongArg::construct(&[NOTIFICATIONS_LONG_PREFIX,"smtp-address"]),
+        env = EnvArg::construct(&[NOTIFICATIONS_ENV_PREFIX,"SMTP_ADDRESS"]),
+        help = "Address of the SMTP relay for email notifications (<host>:<port>), disabled if not set"
+    )]
+    pub smtp_address: Option<String>,
+    #[arg(
+        long = LongArg::construct(&[NOTIFICATIONS_LONG_PREFIX,"smtp-from"]),
+        env = EnvArg::construct(&[NOTIFICATIONS_ENV_PREFIX,"SMTP_FROM"]),
+        default_value = "wishlists@localhost",
+        help = "Sender address of email notifications"
+    )]
+    pub smtp_from: String,
+    #[arg(
+        long = LongArg::construct(&[NOTIFICATIONS_LONG_PREFIX,"webhook-url"]),
+        env = EnvArg::construct(&[NOTIFICATIONS_ENV_PREFIX,"WEBHOOK_URL"]),
+        help = "URL every notification is posted to, disabled if not set"
+    )]
+    pub webhook_url: Option<String>,
+}
+
+#[derive(Args, PartialEq, Eq)]
+pub struct WebhooksArgs {
+    #[arg(
+        id = "webhooks_interval",
+        value_name = "INTERVAL",
+        long = LongArg::construct(&[WEBHOOKS_LONG_PREFIX,"interval"]),
+        env = EnvArg::construct(&[WEBHOOKS_ENV_PREFIX,"INTERVAL"]),
+        default_value = "5",
+        help = "Number of seconds between webhook deliveries"
+    )]
+    pub interval: u64,
+    #[arg(
+        long = LongArg::construct(&[WEBHOOKS_LONG_PREFIX,"max-attempts"]),
+        env = EnvArg::construct(&[WEBHOOKS_ENV_PREFIX,"MAX_ATTEMPTS"]),
+        default_value = "8",
+        help = "Number of attempts before a webhook delivery is given up"
+    )]
+    pub max_attempts: u32,
+}
+
+#[derive(Args, PartialEq, Eq)]
+pub struct PurgeArgs {
+    #[arg(
+        long = LongArg::construct(&[PURGE_LONG_PREFIX,"retention-days"]),
+        env = EnvArg::construct(&[PURGE_ENV_PREFIX,"RETENTION_DAYS"]),
+        default_value = "30",
+        help = "Number of days soft-deleted objects are kept before removal"
+    )]
+    pub retention_days: u32,
+}
+
+#[derive(Args, PartialEq, Eq)]
+pub struct ImportArgs {
+    #[arg(
+        long = LongArg::construct(&[IMPORT_LONG_PREFIX,"user-id"]),
+        env = EnvArg::construct(&[IMPORT_ENV_PREFIX,"USER_ID"]),
+        help = "Owner of the created wishlist"
+    )]
+    pub user_id: Uuid,
+    #[arg(
+        id = "import_format",
+        value_name = "FORMAT",
+        long = LongArg::construct(&[IMPORT_LONG_PREFIX,"format"]),
+        env = EnvArg::construct(&[IMPORT_ENV_PREFIX,"FORMAT"]),
+        default_value = "json",
+        help = "Format of the file"
+    )]
+    pub format: Format,
+    #[arg(
+        long = LongArg::construct(&[IMPORT_LONG_PREFIX,"name"]),
+        env = EnvArg::construct(&[IMPORT_ENV_PREFIX,"NAME"]),
+        help = "Name of the created wishlist, required for CSV files"
+    )]
+    pub name: Option<String>,
+    #[arg(help = "File to read, standard input if '-'", default_value = "-")]
+    pub path: PathBuf,
+}
+
+#[derive(Args, PartialEq, Eq)]
+pub struct ExportArgs {
+    #[arg(
+        long = LongArg::construct(&[EXPORT_LONG_PREFIX,"wishlist-id"]),
+        env = EnvArg::construct(&[EXPORT_ENV_PREFIX,"WISHLIST_ID"]),
+        help = "Wishlist to export"
+    )]
+    pub wishlist_id: Uuid,
+    #[arg(
+        id = "export_format",
+        value_name = "FORMAT",
+        long = LongArg::construct(&[EXPORT_LONG_PREFIX,"format"]),
+        env = EnvArg::construct(&[EXPORT_ENV_PREFIX,"FORMAT"]),
+        default_value = "json",
+        help = "Format of the file"
+    )]
+    pub format: Format,
+    #[arg(help = "File to write, standard output if '-'", default_value = "-")]
+    pub path: PathBuf,
 }
 
 #[derive(Args, Clone, PartialEq, Eq)]
@@ -0,0 +1,219 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use async_trait::async_trait;
+use reqwest::{Client, ClientBuilder, Url};
+
+use crate::outbound;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_REDIRECTS: usize = 5;
+pub const MAX_BODY_SIZE: usize = 5 * 1024 * 1024;
+
+pub struct Page {
+    pub url: Url,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+#[async_trait]
+pub trait Fetcher {
+    async fn fetch(&self, url: &Url) -> anyhow::Result<Page>;
+}
+
+pub struct HttpFetcher {
+    client: Client,
+}
+
+impl HttpFetcher {
+    pub fn new() -> anyhow::Result<Self> {
+        Self::with_client_builder(outbound::client_builder())
+    }
+
+    fn with_client_builder(builder: ClientBuilder) -> anyhow::Result<Self> {
+        let client = builder
+            .redirect(outbound::redirect_policy(MAX_REDIRECTS))
+            .timeout(TIMEOUT)
+            .user_agent(concat!("wishlists/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        Ok(HttpFetcher { client })
+    }
+}
+
+#[async_trait]
+impl Fetcher for HttpFetcher {
+    async fn fetch(&self, url: &Url) -> anyhow::Result<Page> {
+        outbound::check_url(url)?;
+
+        let mut response = self
+            .client
+            .get(url.clone())
+            .send()
+            .await?
+            .error_for_status()?;
+
+        if response
+            .content_length()
+            .is_some_and(|length| length > MAX_BODY_SIZE as u64)
+        {
+            bail!("Response from '{url}' is too large");
+        }
+
+        let url = response.url().clone();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned);
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > MAX_BODY_SIZE {
+                return Err(anyhow!("Response from '{url}' is too large"));
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(Page {
+            url,
+            content_type,
+            body,
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{convert::Infallible, net::TcpListener};
+
+    use axum::{
+        body::{Bytes, StreamBody},
+        extract::Path,
+        http::header,
+        response::{IntoResponse, Redirect},
+        routing::get,
+        Router,
+        Server,
+    };
+    use futures::stream;
+
+    use super::*;
+
+    // Name under which the stub server is reached. The resolver lets only this
+    // one through to the loopback interface, everything else is checked as usual.
+    pub(crate) const HOST: &str = "shop.test";
+
+    // Serves the router on a local port, returning a fetcher for it along with
+    // the base URL of the server under HOST
+    pub(crate) fn serve(router: Router) -> (HttpFetcher, Url) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = Server::from_tcp(listener)
+            .unwrap()
+            .serve(router.into_make_service());
+        tokio::spawn(async move { server.await.unwrap() });
+
+        let fetcher =
+            HttpFetcher::with_client_builder(outbound::client_builder().resolve(HOST, addr))
+                .unwrap();
+        let url = format!("http://{HOST}:{}/", addr.port()).parse().unwrap();
+
+        (fetcher, url)
+    }
+
+    fn router() -> Router {
+        Router::new()
+            .route(
+                "/page",
+                get(|| async { ([(header::CONTENT_TYPE, "text/html")], "<html></html>") }),
+            )
+            .route("/moved", get(|| async { Redirect::temporary("/page") }))
+            .route(
+                "/loop/:n",
+                get(|Path(n): Path<usize>| async move {
+                    Redirect::temporary(&format!("/loop/{}", n + 1))
+                }),
+            )
+            .route(
+                "/to/:host",
+                get(|Path(host): Path<String>| async move {
+                    Redirect::temporary(&format!("http://{host}/page"))
+                }),
+            )
+            .route("/large", get(|| async { vec![b' '; MAX_BODY_SIZE + 1] }))
+            // Chunked, without a length to turn it down by
+            .route(
+                "/streamed",
+                get(|| async {
+                    StreamBody::new(stream::iter(
+                        (0..=MAX_BODY_SIZE / 1024)
+                            .map(|_| Ok::<_, Infallible>(Bytes::from(vec![b' '; 1024]))),
+                    ))
+                    .into_response()
+                }),
+            )
+    }
+
+    #[tokio::test]
+    async fn follows_redirects_to_the_page() {
+        let (fetcher, url) = serve(router());
+
+        let page = fetcher.fetch(&url.join("moved").unwrap()).await.unwrap();
+
+        assert_eq!(page.url, url.join("page").unwrap());
+        assert_eq!(page.content_type.as_deref(), Some("text/html"));
+        assert_eq!(page.body, b"<html></html>");
+    }
+
+    #[tokio::test]
+    async fn refuses_internal_addresses() {
+        let (fetcher, url) = serve(router());
+        let port = url.port().unwrap();
+
+        for internal in [
+            format!("http://127.0.0.1:{port}/page"),
+            format!("http://localhost:{port}/page"),
+        ] {
+            assert!(
+                fetcher.fetch(&internal.parse().unwrap()).await.is_err(),
+                "{internal}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn refuses_redirects_to_internal_addresses() {
+        let (fetcher, url) = serve(router());
+        let port = url.port().unwrap();
+
+        for host in [format!("127.0.0.1:{port}"), format!("localhost:{port}")] {
+            assert!(
+                fetcher
+                    .fetch(&url.join(&format!("to/{host}")).unwrap())
+                    .await
+                    .is_err(),
+                "{host}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn stops_following_redirects() {
+        let (fetcher, url) = serve(router());
+
+        assert!(fetcher.fetch(&url.join("loop/0").unwrap()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_responses() {
+        let (fetcher, url) = serve(router());
+
+        for path in ["large", "streamed"] {
+            assert!(
+                fetcher.fetch(&url.join(path).unwrap()).await.is_err(),
+                "{path}"
+            );
+        }
+    }
+}
@@ -0,0 +1,473 @@
+use std::sync::Arc;
+
+use anyhow::{bail, Context};
+use chrono::Utc;
+use database::{
+    traits::{
+        item_pictures,
+        items::{self, Id, Payload, Response},
+        Precondition,
+    },
+    RepositoryTrait,
+};
+use fetcher::{Fetcher, Page, MAX_BODY_SIZE};
+use reqwest::Url;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::router::money::Currency;
+
+pub mod fetcher;
+mod opengraph;
+
+const MAX_NAME_LENGTH: usize = 100;
+
+pub struct Enricher {
+    items: Arc<dyn items::RepositoryTrait + Send + Sync>,
+    pictures: Arc<dyn item_pictures::RepositoryTrait + Send + Sync>,
+    fetcher: Arc<dyn Fetcher + Send + Sync>,
+}
+
+impl Enricher {
+    #[must_use]
+    pub fn new(
+        repository: Arc<dyn RepositoryTrait + Send + Sync>,
+        fetcher: Arc<dyn Fetcher + Send + Sync>,
+    ) -> Self {
+        Enricher {
+            items: repository.clone(),
+            pictures: repository,
+            fetcher,
+        }
+    }
+
+    // Fills the name, price and picture of an item from the OpenGraph metadata
+    // of its product page, keeping everything the owner has already set
+    pub async fn enrich_item(&self, id: Id) -> anyhow::Result<Option<Response>> {
+        let Some(item) = self.items.get_item(id).await? else {
+            return Ok(None);
+        };
+        let Some(url) = item.url.as_deref() else {
+            return Ok(Some(item));
+        };
+
+        let page = self.fetch(&Url::parse(url)?).await?;
+        let metadata = opengraph::parse(&String::from_utf8_lossy(&page.body));
+
+        let name = match metadata.title {
+            Some(title) if item.name.is_empty() => title.chars().take(MAX_NAME_LENGTH).collect(),
+            _ => item.name,
+        };
+
+        let (price, currency) = match (item.price, metadata.price_amount, metadata.price_currency) {
+            (None, Some(amount), Some(currency)) => Currency::try_from(currency)
+                .ok()
+                .and_then(|currency| {
+                    let amount = currency.parse_amount(&amount)?;
+                    Some((Some(amount.into()), Some(currency.into())))
+                })
+                .unwrap_or((None, None)),
+            _ => (item.price, item.currency),
+        };
+
+        let downloaded = match (item.picture_id, metadata.image) {
+            (None, Some(image)) => match self.download_picture(&page.url.join(&image)?).await {
+                Ok(key) => Some(key),
+                Err(err) => {
+                    warn!("Cannot download picture for item {id}: {err:#}");
+                    None
+                }
+            },
+            _ => None,
+        };
+        let picture_id = item.picture_id.or(downloaded);
+
+        let result = self
+            .items
+            .update_item(
+                id,
+                Payload {
+                    id,
+                    wishlist_id: item.wishlist_id,
+                    name,
+                    description: item.description,
+                    url: item.url,
+                    price,
+                    currency,
+                    quantity: item.quantity,
+                    priority: item.priority,
+                    is_hidden: item.is_hidden,
+                    is_funded: item.is_funded,
+                    picture_id,
+                    created_at: item.created_at,
+                    updated_at: Utc::now().naive_utc(),
+                },
+                // Edits made while the page was being fetched win
+                Some(Precondition::Version(item.version)),
+                // Filled in by the application rather than by a user
+                None,
+            )
+            .await;
+
+        match result {
+            Ok(response) => Ok(Some(response)),
+            Err(err) => {
+                if let Some(key) = downloaded {
+                    if let Err(err) = self.pictures.delete_item_picture(key).await {
+                        warn!("Cannot delete unused picture {key} of item {id}: {err:#}");
+                    }
+                }
+
+                Err(err.into())
+            }
+        }
+    }
+
+    // The fetcher is expected to stop reading at the limit already, this
+    // keeps other implementations to it as well
+    async fn fetch(&self, url: &Url) -> anyhow::Result<Page> {
+        let page = self.fetcher.fetch(url).await?;
+
+        if page.body.len() > MAX_BODY_SIZE {
+            bail!("Response from '{url}' is too large");
+        }
+
+        Ok(page)
+    }
+
+    async fn download_picture(&self, url: &Url) -> anyhow::Result<item_pictures::Key> {
+        let page = self.fetch(url).await?;
+
+        if !page
+            .content_type
+            .as_deref()
+            .is_some_and(|content_type| content_type.starts_with("image/"))
+        {
+            bail!("'{url}' is not an image");
+        }
+
+        let key = Uuid::new_v4();
+        self.pictures
+            .put_item_picture(key, item_pictures::Value::from(page.body))
+            .await
+            .with_context(|| format!("Cannot store picture from '{url}'"))?;
+
+        Ok(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use axum::{http::header, routing::get, Router};
+    use database::traits::{items::Patch, users, wishlists};
+
+    use super::*;
+    use crate::{enrichment::fetcher::tests::serve, testing};
+
+    const PRODUCT_PAGE: &str = r#"<meta property="og:title" content="Castle">
+        <meta property="og:image" content="/castle.png">
+        <meta property="product:price:amount" content="12.99">
+        <meta property="product:price:currency" content="EUR">"#;
+
+    // Blob storage isn't set up for tests, pictures are only kept track of
+    #[derive(Default)]
+    struct Pictures(Mutex<Vec<item_pictures::Key>>);
+
+    #[async_trait]
+    impl item_pictures::RepositoryTrait for Pictures {
+        async fn get_item_picture(
+            &self,
+            _key: item_pictures::Key,
+        ) -> Result<item_pictures::Value, item_pictures::Error> {
+            unimplemented!()
+        }
+
+        async fn put_item_picture(
+            &self,
+            key: item_pictures::Key,
+            _value: item_pictures::Value,
+        ) -> Result<(), item_pictures::Error> {
+            self.0.lock().unwrap().push(key);
+            Ok(())
+        }
+
+        async fn copy_item_picture(
+            &self,
+            _source: item_pictures::Key,
+            _destination: item_pictures::Key,
+        ) -> Result<(), item_pictures::Error> {
+            unimplemented!()
+        }
+
+        async fn delete_item_picture(
+            &self,
+            key: item_pictures::Key,
+        ) -> Result<(), item_pictures::Error> {
+            self.0.lock().unwrap().retain(|x| *x != key);
+            Ok(())
+        }
+    }
+
+    fn shop() -> Router {
+        Router::new()
+            .route(
+                "/castle",
+                get(|| async { ([(header::CONTENT_TYPE, "text/html")], PRODUCT_PAGE) }),
+            )
+            .route(
+                "/castle.png",
+                get(|| async {
+                    (
+                        [(header::CONTENT_TYPE, "image/png")],
+                        vec![0x89, b'P', b'N', b'G'],
+                    )
+                }),
+            )
+            .route("/large", get(|| async { vec![b' '; MAX_BODY_SIZE + 1] }))
+    }
+
+    struct Fixture {
+        repository: Arc<dyn RepositoryTrait + Send + Sync>,
+        pictures: Arc<Pictures>,
+        owner: users::Response,
+    }
+
+    impl Fixture {
+        async fn new() -> Self {
+            let repository: Arc<dyn RepositoryTrait + Send + Sync> =
+                Arc::new(testing::repository().await);
+            let now = Utc::now().naive_utc();
+
+            let owner = repository
+                .create_user(users::Payload {
+                    id: Uuid::new_v4(),
+                    name: "Enrichment".to_owned(),
+                    avatar_id: None,
+                    currency: "EUR".to_owned(),
+                    email: None,
+                    is_admin: false,
+                    is_private: false,
+                    username: None,
+                    bio: None,
+                    birthday: None,
+                    locale: None,
+                    created_at: now,
+                    updated_at: now,
+                })
+                .await
+                .unwrap();
+
+            Fixture {
+                repository,
+                pictures: Arc::default(),
+                owner,
+            }
+        }
+
+        async fn create_item(&self, id: Id, url: &Url) -> Response {
+            let now = Utc::now().naive_utc();
+            let wishlist = self
+                .repository
+                .create_wishlist(wishlists::Payload {
+                    id: Uuid::new_v4(),
+                    name: "Enrichment".to_owned(),
+                    user_id: self.owner.id,
+                    event_type: None,
+                    event_date: None,
+                    created_at: now,
+                    updated_at: now,
+                })
+                .await
+                .unwrap();
+
+            self.repository
+                .create_item(
+                    Payload {
+                        id,
+                        wishlist_id: wishlist.id,
+                        name: String::new(),
+                        description: None,
+                        url: Some(url.to_string()),
+                        price: None,
+                        currency: None,
+                        quantity: 1,
+                        priority: items::Priority::default(),
+                        is_hidden: false,
+                        is_funded: false,
+                        picture_id: None,
+                        created_at: now,
+                        updated_at: now,
+                    },
+                    Some(self.owner.id),
+                )
+                .await
+                .unwrap()
+        }
+
+        fn enricher(&self, fetcher: Arc<dyn Fetcher + Send + Sync>) -> Enricher {
+            Enricher {
+                items: self.repository.clone(),
+                pictures: self.pictures.clone(),
+                fetcher,
+            }
+        }
+
+        fn pictures(&self) -> Vec<item_pictures::Key> {
+            self.pictures.0.lock().unwrap().clone()
+        }
+
+        async fn get_item(&self, id: Id) -> Response {
+            self.repository.get_item(id).await.unwrap().unwrap()
+        }
+
+        async fn erase(self) {
+            self.repository
+                .erase_user(self.owner.id, self.owner.version)
+                .await
+                .unwrap();
+        }
+    }
+
+    fn fetcher(router: Router) -> (Arc<dyn Fetcher + Send + Sync>, Url) {
+        let (fetcher, url) = serve(router);
+        (Arc::new(fetcher), url)
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a database"]
+    async fn fills_item_from_opengraph_metadata() {
+        let fixture = Fixture::new().await;
+        let (fetcher, url) = fetcher(shop());
+        let item = fixture
+            .create_item(Uuid::new_v4(), &url.join("castle").unwrap())
+            .await;
+
+        let response = fixture
+            .enricher(fetcher)
+            .enrich_item(item.id)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(response.name, "Castle");
+        assert_eq!(response.price, Some(1299));
+        assert_eq!(response.currency.as_deref(), Some("EUR"));
+        assert!(response.picture_id.is_some());
+        assert_eq!(fixture.pictures(), vec![response.picture_id.unwrap()]);
+
+        fixture.erase().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a database"]
+    async fn keeps_values_set_by_the_owner() {
+        let fixture = Fixture::new().await;
+        let (fetcher, url) = fetcher(shop());
+        let item = fixture
+            .create_item(Uuid::new_v4(), &url.join("castle").unwrap())
+            .await;
+        fixture
+            .repository
+            .patch_item(
+                item.id,
+                Patch {
+                    name: Some("My castle".to_owned()),
+                    price: Some(Some(500)),
+                    currency: Some("USD".to_owned()),
+                    ..Patch::default()
+                },
+                None,
+                Some(fixture.owner.id),
+            )
+            .await
+            .unwrap();
+
+        let response = fixture
+            .enricher(fetcher)
+            .enrich_item(item.id)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(response.name, "My castle");
+        assert_eq!(response.price, Some(500));
+        assert_eq!(response.currency.as_deref(), Some("USD"));
+
+        fixture.erase().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a database"]
+    async fn rejects_oversized_pages() {
+        let fixture = Fixture::new().await;
+        let (fetcher, url) = fetcher(shop());
+        let item = fixture
+            .create_item(Uuid::new_v4(), &url.join("large").unwrap())
+            .await;
+
+        let result = fixture.enricher(fetcher).enrich_item(item.id).await;
+
+        assert!(result.is_err());
+        assert_eq!(fixture.get_item(item.id).await.version, item.version);
+
+        fixture.erase().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a database"]
+    async fn refuses_pages_on_internal_addresses() {
+        let fixture = Fixture::new().await;
+        let (fetcher, url) = fetcher(shop());
+        let mut internal = url.join("castle").unwrap();
+        internal.set_host(Some("127.0.0.1")).unwrap();
+        let item = fixture.create_item(Uuid::new_v4(), &internal).await;
+
+        let result = fixture.enricher(fetcher).enrich_item(item.id).await;
+
+        assert!(result.is_err());
+        assert_eq!(fixture.get_item(item.id).await.version, item.version);
+
+        fixture.erase().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a database"]
+    async fn discards_picture_when_item_changed_meanwhile() {
+        let fixture = Fixture::new().await;
+        let id = Uuid::new_v4();
+
+        // The owner renames the item while its page is being fetched
+        let repository = fixture.repository.clone();
+        let (fetcher, url) = fetcher(shop().route(
+            "/edited",
+            get(move || async move {
+                repository
+                    .patch_item(
+                        id,
+                        Patch {
+                            name: Some("My castle".to_owned()),
+                            ..Patch::default()
+                        },
+                        None,
+                        None,
+                    )
+                    .await
+                    .unwrap();
+
+                ([(header::CONTENT_TYPE, "text/html")], PRODUCT_PAGE)
+            }),
+        ));
+        fixture.create_item(id, &url.join("edited").unwrap()).await;
+
+        let result = fixture.enricher(fetcher).enrich_item(id).await;
+
+        assert!(result.is_err());
+        assert_eq!(fixture.get_item(id).await.name, "My castle");
+        assert!(fixture.pictures().is_empty());
+
+        fixture.erase().await;
+    }
+}
@@ -0,0 +1,206 @@
+#[derive(Default)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub image: Option<String>,
+    pub price_amount: Option<String>,
+    pub price_currency: Option<String>,
+}
+
+pub fn parse(html: &str) -> Metadata {
+    let mut metadata = Metadata::default();
+    let lowercase = html.to_ascii_lowercase();
+
+    for (start, end) in tags(&lowercase, "<meta") {
+        let attributes = attributes(&html[start..end]);
+        let attribute = |name: &str| {
+            attributes
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| decode_entities(value.trim()))
+        };
+
+        let (Some(property), Some(content)) = (
+            attribute("property").or_else(|| attribute("name")),
+            attribute("content"),
+        ) else {
+            continue;
+        };
+
+        if content.is_empty() {
+            continue;
+        }
+
+        let field = match property.to_ascii_lowercase().as_str() {
+            "og:title" => &mut metadata.title,
+            "og:image" | "og:image:url" | "og:image:secure_url" => &mut metadata.image,
+            "product:price:amount" | "og:price:amount" => &mut metadata.price_amount,
+            "product:price:currency" | "og:price:currency" => &mut metadata.price_currency,
+            _ => continue,
+        };
+
+        field.get_or_insert(content);
+    }
+
+    if metadata.title.is_none() {
+        metadata.title = title(html, &lowercase);
+    }
+
+    metadata
+}
+
+fn tags<'a>(lowercase: &'a str, name: &'a str) -> impl Iterator<Item = (usize, usize)> + 'a {
+    let mut position = 0;
+
+    std::iter::from_fn(move || loop {
+        let start = position + lowercase[position..].find(name)?;
+        let attributes_start = start + name.len();
+
+        match lowercase[attributes_start..].chars().next() {
+            Some(c) if c.is_ascii_whitespace() || c == '/' || c == '>' => {}
+            _ => {
+                position = attributes_start;
+                continue;
+            }
+        }
+
+        let mut quote = None;
+        let end = lowercase[attributes_start..]
+            .char_indices()
+            .find_map(|(i, c)| {
+                match (quote, c) {
+                    (None, '"' | '\'') => quote = Some(c),
+                    (Some(q), c) if q == c => quote = None,
+                    (None, '>') => return Some(attributes_start + i),
+                    _ => {}
+                }
+                None
+            })?;
+
+        position = end;
+        return Some((attributes_start, end));
+    })
+}
+
+fn attributes(tag: &str) -> Vec<(&str, &str)> {
+    let mut attributes = Vec::new();
+    let mut rest = tag;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        if rest.is_empty() {
+            break;
+        }
+
+        let name_end = rest
+            .find(|c: char| c.is_ascii_whitespace() || c == '=' || c == '/')
+            .unwrap_or(rest.len());
+        let name = &rest[..name_end];
+        rest = rest[name_end..].trim_start();
+
+        let Some(value_start) = rest.strip_prefix('=') else {
+            attributes.push((name, ""));
+            continue;
+        };
+        let value_start = value_start.trim_start();
+
+        let (value, remaining) = if let Some(quote @ ('"' | '\'')) = value_start.chars().next() {
+            let value_start = &value_start[1..];
+            let value_end = value_start.find(quote).unwrap_or(value_start.len());
+            (
+                &value_start[..value_end],
+                value_start.get(value_end + 1..).unwrap_or_default(),
+            )
+        } else {
+            let value_end = value_start
+                .find(|c: char| c.is_ascii_whitespace())
+                .unwrap_or(value_start.len());
+            (&value_start[..value_end], &value_start[value_end..])
+        };
+
+        attributes.push((name, value));
+        rest = remaining;
+    }
+
+    attributes
+}
+
+fn title(html: &str, lowercase: &str) -> Option<String> {
+    let (_, start) = tags(lowercase, "<title").next()?;
+    let start = start + 1;
+    let end = start + lowercase[start..].find("</title")?;
+    let title = decode_entities(html[start..end].trim());
+
+    (!title.is_empty()).then_some(title)
+}
+
+fn decode_entities(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+
+        if let Some((c, end)) = decoded {
+            result.push(c);
+            rest = &rest[end + 1..];
+        } else {
+            result.push('&');
+            rest = &rest[1..];
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_product_metadata() {
+        let metadata = parse(
+            r#"<html><head>
+            <meta property="og:title" content="Castle &amp; Knights">
+            <meta property='og:image' content='/castle.png'/>
+            <meta property="og:image" content="/other.png">
+            <META NAME="product:price:amount" CONTENT="1,299.99">
+            <meta property="product:price:currency" content="EUR">
+            </head></html>"#,
+        );
+
+        assert_eq!(metadata.title.as_deref(), Some("Castle & Knights"));
+        assert_eq!(metadata.image.as_deref(), Some("/castle.png"));
+        assert_eq!(metadata.price_amount.as_deref(), Some("1,299.99"));
+        assert_eq!(metadata.price_currency.as_deref(), Some("EUR"));
+    }
+
+    #[test]
+    fn falls_back_to_the_page_title() {
+        let metadata =
+            parse("<title> Castle &#x26; Knights </title><meta property=og:title content=\"\">");
+
+        assert_eq!(metadata.title.as_deref(), Some("Castle & Knights"));
+        assert!(metadata.image.is_none());
+    }
+}
@@ -0,0 +1,50 @@
+use std::{sync::Arc, time::Duration};
+
+use database::{traits::events::Event, RepositoryTrait};
+use futures::StreamExt;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use tracing::warn;
+
+const CAPACITY: usize = 1024;
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+// Fans out item events from the database to every connected client, so that
+// changes made through any instance of the server reach all viewers
+pub struct Bus {
+    repository: Arc<dyn RepositoryTrait + Send + Sync>,
+    sender: Sender<Event>,
+}
+
+impl Bus {
+    #[must_use]
+    pub fn new(repository: Arc<dyn RepositoryTrait + Send + Sync>) -> Self {
+        let (sender, _) = broadcast::channel(CAPACITY);
+
+        Bus { repository, sender }
+    }
+
+    pub fn subscribe(&self) -> Receiver<Event> {
+        self.sender.subscribe()
+    }
+
+    pub async fn run(self: Arc<Self>) {
+        loop {
+            if let Err(err) = self.listen().await {
+                warn!("Cannot listen to item events: {err:#}");
+            }
+
+            tokio::time::sleep(RETRY_DELAY).await;
+        }
+    }
+
+    async fn listen(&self) -> anyhow::Result<()> {
+        let mut events = self.repository.listen_item_events().await?;
+
+        while let Some(event) = events.next().await {
+            // Sending only fails when nobody is subscribed at the moment
+            let _ = self.sender.send(event?);
+        }
+
+        anyhow::bail!("Item event stream ended")
+    }
+}
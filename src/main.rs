@@ -1,21 +1,44 @@
 #![forbid(unsafe_code)]
 #![warn(clippy::pedantic)]
+use std::{
+    io::{Read, Write},
+    path::Path,
+    sync::Arc,
+    time::Duration as StdDuration,
+};
+
 use axum::{Router as AxumRouter, Server};
+use chrono::{Duration, Utc};
 use clap::Parser;
-use config::{Commands, Config, LogFormat};
+use config::{Commands, Config, ExportArgs, ImportArgs, LogFormat, PurgeArgs};
 use database::{
+    traits::{
+        idempotency_keys::RepositoryTrait as _,
+        purge::RepositoryTrait as _,
+        wishlists::RepositoryTrait as _,
+    },
     BlobStorageClient,
     BlobStorageConfig,
     Database,
     DatabaseConnectOptions,
     Repository,
 };
+use enrichment::fetcher::HttpFetcher;
 use migrations::{Migrator, MigratorTrait};
+use notifications::{delivery::InApp, email::Email, webhook::Webhook, Dispatcher};
 use router::{state::State, Router};
-use tracing::error;
+use tracing::{error, info};
 
 mod config;
+mod enrichment;
+mod events;
+mod notifications;
+mod outbound;
 mod router;
+#[cfg(test)]
+mod testing;
+mod transfer;
+mod webhooks;
 
 #[tokio::main]
 async fn main() {
@@ -37,8 +60,14 @@ async fn main() {
             panic!()
         });
 
+    let blob_storage_bucket = config.blob_storage.bucket.clone();
     let blob_storage_config: BlobStorageConfig = config.blob_storage.into();
     let blob_storage_client = BlobStorageClient::from_conf(blob_storage_config);
+    let repository = Repository::new(
+        database_connection.clone(),
+        blob_storage_client,
+        blob_storage_bucket,
+    );
 
     match config.command {
         Commands::Migrate => Migrator::up(&database_connection, None)
@@ -47,8 +76,55 @@ async fn main() {
                 error!("Migration not successful");
                 panic!()
             }),
+        Commands::Purge(purge_args) => purge(&repository, purge_args).await,
+        Commands::Import(import_args) => import(&repository, import_args).await,
+        Commands::Export(export_args) => export(&repository, export_args).await,
         Commands::Run(run_args) => {
-            let state = State::new(Repository::new(database_connection, blob_storage_client));
+            let fetcher = HttpFetcher::new().unwrap_or_else(|_| {
+                error!("Cannot create HTTP client");
+                panic!()
+            });
+            let state = State::new(repository, Arc::new(fetcher));
+
+            let mut dispatcher = Dispatcher::new(
+                state.repository.clone(),
+                StdDuration::from_secs(run_args.notifications.interval),
+            )
+            .with_delivery(Arc::new(InApp));
+
+            if let Some(address) = run_args.notifications.smtp_address {
+                dispatcher = dispatcher.with_delivery(Arc::new(Email::new(
+                    address,
+                    run_args.notifications.smtp_from,
+                )));
+            }
+
+            if let Some(url) = run_args.notifications.webhook_url {
+                let webhook = url
+                    .parse()
+                    .map_err(anyhow::Error::from)
+                    .and_then(Webhook::new)
+                    .unwrap_or_else(|_| {
+                        error!("Cannot create notification webhook ({url})");
+                        panic!()
+                    });
+                dispatcher = dispatcher.with_delivery(Arc::new(webhook));
+            }
+
+            tokio::spawn(dispatcher.run());
+            tokio::spawn(state.events.clone().run());
+
+            let webhooks = webhooks::Dispatcher::new(
+                state.repository.clone(),
+                StdDuration::from_secs(run_args.webhooks.interval),
+                run_args.webhooks.max_attempts,
+            )
+            .unwrap_or_else(|_| {
+                error!("Cannot create webhook HTTP client");
+                panic!()
+            });
+            tokio::spawn(webhooks.run());
+
             let router: AxumRouter = Router::new(run_args.root_path.into(), state).into();
 
             Server::bind(&run_args.bind_address)
@@ -61,3 +137,85 @@ async fn main() {
         }
     }
 }
+
+async fn purge(repository: &Repository, args: PurgeArgs) {
+    let before = Utc::now().naive_utc() - Duration::days(i64::from(args.retention_days));
+
+    let response = repository.purge_deleted(before).await.unwrap_or_else(|_| {
+        error!("Purge not successful");
+        panic!()
+    });
+
+    info!(
+        "Purged {} users, {} wishlists, {} items and {} blobs deleted before {before}",
+        response.users, response.wishlists, response.items, response.blobs
+    );
+
+    let keys = repository
+        .purge_idempotency_keys(Utc::now().naive_utc())
+        .await
+        .unwrap_or_else(|_| {
+            error!("Purge of idempotency keys not successful");
+            panic!()
+        });
+
+    info!("Purged {keys} expired idempotency keys");
+}
+
+async fn import(repository: &Repository, args: ImportArgs) {
+    let content = read_input(&args.path).unwrap_or_else(|_| {
+        error!("Cannot read {}", args.path.display());
+        panic!()
+    });
+
+    let response = transfer::import(repository, args.user_id, args.format, args.name, &content)
+        .await
+        .unwrap_or_else(|err| {
+            error!("Import not successful: {err}");
+            panic!()
+        });
+
+    info!("Imported wishlist {} ({})", response.id, response.name);
+}
+
+async fn export(repository: &Repository, args: ExportArgs) {
+    let wishlist = repository
+        .get_wishlist(args.wishlist_id)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| {
+            error!("Wishlist {} not found", args.wishlist_id);
+            panic!()
+        });
+
+    let content = transfer::export(repository, wishlist, args.format)
+        .await
+        .unwrap_or_else(|err| {
+            error!("Export not successful: {err}");
+            panic!()
+        });
+
+    write_output(&args.path, &content).unwrap_or_else(|_| {
+        error!("Cannot write {}", args.path.display());
+        panic!()
+    });
+}
+
+fn read_input(path: &Path) -> std::io::Result<Vec<u8>> {
+    if path == Path::new("-") {
+        let mut content = Vec::new();
+        std::io::stdin().read_to_end(&mut content)?;
+        Ok(content)
+    } else {
+        std::fs::read(path)
+    }
+}
+
+fn write_output(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    if path == Path::new("-") {
+        std::io::stdout().write_all(content)
+    } else {
+        std::fs::write(path, content)
+    }
+}
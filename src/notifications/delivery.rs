@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+use database::traits::{notifications, users};
+
+pub struct Message {
+    pub notification: notifications::Response,
+    pub recipient: users::Response,
+    pub subject: String,
+    pub text: String,
+}
+
+#[async_trait]
+pub trait Delivery {
+    fn name(&self) -> &'static str;
+    async fn deliver(&self, message: &Message) -> anyhow::Result<()>;
+}
+
+// Stored notifications are already served by the API, nothing to send
+pub struct InApp;
+
+#[async_trait]
+impl Delivery for InApp {
+    fn name(&self) -> &'static str {
+        "in-app"
+    }
+
+    async fn deliver(&self, _message: &Message) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
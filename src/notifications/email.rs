@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+        TcpStream,
+    },
+};
+
+use super::delivery::{Delivery, Message};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// Plain SMTP without TLS or authentication, meant for a relay on the local network
+pub struct Email {
+    address: String,
+    from: String,
+}
+
+impl Email {
+    #[must_use]
+    pub fn new(address: String, from: String) -> Self {
+        Email { address, from }
+    }
+
+    async fn send(&self, to: &str, message: &Message) -> anyhow::Result<()> {
+        let mut session = Session::connect(&self.address).await?;
+
+        session.expect(220).await?;
+        session.command("HELO wishlists", 250).await?;
+        session
+            .command(&format!("MAIL FROM:<{}>", self.from), 250)
+            .await?;
+        session.command(&format!("RCPT TO:<{to}>"), 250).await?;
+        session.command("DATA", 354).await?;
+
+        let mut data = format!(
+            "From: <{}>\r\nTo: <{to}>\r\nSubject: {}\r\nContent-Type: text/plain; \
+             charset=utf-8\r\n\r\n",
+            self.from,
+            header_value(&message.subject),
+        );
+        for line in message.text.lines() {
+            // Lines starting with a dot are escaped so they don't end the message
+            if line.starts_with('.') {
+                data.push('.');
+            }
+            data.push_str(line);
+            data.push_str("\r\n");
+        }
+        data.push('.');
+
+        session.command(&data, 250).await?;
+        session.command("QUIT", 221).await
+    }
+}
+
+fn header_value(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+fn is_valid_address(address: &str) -> bool {
+    address.contains('@') && !address.contains(['\r', '\n', '<', '>'])
+}
+
+struct Session {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
+
+impl Session {
+    async fn connect(address: &str) -> anyhow::Result<Self> {
+        let (reader, writer) = TcpStream::connect(address).await?.into_split();
+
+        Ok(Session {
+            reader: BufReader::new(reader),
+            writer,
+        })
+    }
+
+    async fn reply(&mut self) -> anyhow::Result<u16> {
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line).await? == 0 {
+                bail!("SMTP server closed the connection");
+            }
+
+            // Multiline replies continue with a dash after the code
+            if line.as_bytes().get(3) != Some(&b'-') {
+                return Ok(line.get(..3).unwrap_or_default().parse()?);
+            }
+        }
+    }
+
+    async fn expect(&mut self, code: u16) -> anyhow::Result<()> {
+        let reply = self.reply().await?;
+        if reply == code {
+            Ok(())
+        } else {
+            bail!("Unexpected SMTP reply {reply}, expected {code}")
+        }
+    }
+
+    async fn command(&mut self, command: &str, code: u16) -> anyhow::Result<()> {
+        self.writer.write_all(command.as_bytes()).await?;
+        self.writer.write_all(b"\r\n").await?;
+        self.expect(code).await
+    }
+}
+
+#[async_trait]
+impl Delivery for Email {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    async fn deliver(&self, message: &Message) -> anyhow::Result<()> {
+        let Some(to) = message.recipient.email.as_deref() else {
+            return Ok(());
+        };
+
+        if !is_valid_address(to) {
+            bail!("Invalid email address of user {}", message.recipient.id);
+        }
+
+        tokio::time::timeout(TIMEOUT, self.send(to, message)).await?
+    }
+}
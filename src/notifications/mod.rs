@@ -0,0 +1,147 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Duration as ChronoDuration;
+use database::{
+    traits::notifications::{Kind, Response},
+    RepositoryTrait,
+};
+use delivery::{Delivery, Message};
+use tracing::warn;
+
+pub mod delivery;
+pub mod email;
+pub mod webhook;
+
+const BATCH_SIZE: u64 = 100;
+const CLAIM_MINUTES: i64 = 10;
+
+pub struct Dispatcher {
+    repository: Arc<dyn RepositoryTrait + Send + Sync>,
+    deliveries: Vec<Arc<dyn Delivery + Send + Sync>>,
+    interval: Duration,
+}
+
+impl Dispatcher {
+    #[must_use]
+    pub fn new(repository: Arc<dyn RepositoryTrait + Send + Sync>, interval: Duration) -> Self {
+        Dispatcher {
+            repository,
+            deliveries: Vec::new(),
+            interval,
+        }
+    }
+
+    #[must_use]
+    pub fn with_delivery(mut self, delivery: Arc<dyn Delivery + Send + Sync>) -> Self {
+        self.deliveries.push(delivery);
+        self
+    }
+
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = self.dispatch().await {
+                warn!("Cannot dispatch notifications: {err:#}");
+            }
+        }
+    }
+
+    // Every notification is attempted once, failed deliveries are logged and
+    // dropped. Ones that couldn't be composed are tried again once their claim
+    // runs out, and instances running side by side never claim the same ones.
+    async fn dispatch(&self) -> anyhow::Result<()> {
+        loop {
+            let notifications = self
+                .repository
+                .claim_undelivered_notifications(BATCH_SIZE, ChronoDuration::minutes(CLAIM_MINUTES))
+                .await?;
+
+            if notifications.is_empty() {
+                return Ok(());
+            }
+
+            let mut ids = Vec::with_capacity(notifications.len());
+
+            for notification in notifications {
+                let id = notification.id;
+
+                let message = match self.compose(notification).await {
+                    Ok(Some(message)) => message,
+                    Ok(None) => {
+                        ids.push(id);
+                        continue;
+                    }
+                    Err(err) => {
+                        warn!("Cannot compose notification {id}: {err:#}");
+                        continue;
+                    }
+                };
+
+                for delivery in &self.deliveries {
+                    if let Err(err) = delivery.deliver(&message).await {
+                        warn!(
+                            "Cannot deliver notification {id} via {}: {err:#}",
+                            delivery.name()
+                        );
+                    }
+                }
+
+                ids.push(id);
+            }
+
+            self.repository.mark_notifications_delivered(ids).await?;
+        }
+    }
+
+    // Notifications about objects removed in the meantime are skipped
+    async fn compose(&self, notification: Response) -> anyhow::Result<Option<Message>> {
+        let Some(recipient) = self.repository.get_user(notification.user_id).await? else {
+            return Ok(None);
+        };
+
+        let actor = match notification.actor_id {
+            Some(id) => self.repository.get_user(id).await?,
+            None => None,
+        }
+        .map_or_else(|| "Someone".to_owned(), |x| x.name);
+
+        let wishlist = match notification.wishlist_id {
+            Some(id) => self.repository.get_wishlist(id).await?,
+            None => None,
+        };
+        let Some(wishlist) = wishlist.map(|x| x.name) else {
+            return Ok(None);
+        };
+
+        let item = match notification.item_id {
+            Some(id) => self.repository.get_item(id).await?.map(|x| x.name),
+            None => None,
+        };
+
+        let (subject, text) = match (notification.kind, item) {
+            (Kind::WishlistCreated, _) => (
+                format!("New wishlist from {actor}"),
+                format!("{actor} created a new wishlist \"{wishlist}\""),
+            ),
+            (Kind::ItemAdded, Some(item)) => (
+                format!("New item from {actor}"),
+                format!("{actor} added \"{item}\" to \"{wishlist}\""),
+            ),
+            (Kind::ItemReserved, Some(item)) => (
+                format!("Gift reserved in \"{wishlist}\""),
+                format!("{actor} reserved \"{item}\" from \"{wishlist}\""),
+            ),
+            (Kind::ItemAdded | Kind::ItemReserved, None) => return Ok(None),
+        };
+
+        Ok(Some(Message {
+            notification,
+            recipient,
+            subject,
+            text,
+        }))
+    }
+}
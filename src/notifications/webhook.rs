@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use reqwest::{Client, Url};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::delivery::{Delivery, Message};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct Webhook {
+    client: Client,
+    url: Url,
+}
+
+impl Webhook {
+    pub fn new(url: Url) -> anyhow::Result<Self> {
+        let client = Client::builder()
+            .timeout(TIMEOUT)
+            .user_agent(concat!("wishlists/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        Ok(Webhook { client, url })
+    }
+}
+
+#[derive(Serialize)]
+struct Body<'a> {
+    id: Uuid,
+    user_id: Uuid,
+    kind: String,
+    actor_id: Option<Uuid>,
+    wishlist_id: Option<Uuid>,
+    item_id: Option<Uuid>,
+    subject: &'a str,
+    text: &'a str,
+    created_at: NaiveDateTime,
+}
+
+impl<'a> From<&'a Message> for Body<'a> {
+    fn from(value: &'a Message) -> Self {
+        Body {
+            id: value.notification.id,
+            user_id: value.notification.user_id,
+            kind: value.notification.kind.into(),
+            actor_id: value.notification.actor_id,
+            wishlist_id: value.notification.wishlist_id,
+            item_id: value.notification.item_id,
+            subject: &value.subject,
+            text: &value.text,
+            created_at: value.notification.created_at,
+        }
+    }
+}
+
+#[async_trait]
+impl Delivery for Webhook {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn deliver(&self, message: &Message) -> anyhow::Result<()> {
+        self.client
+            .post(self.url.clone())
+            .json(&Body::from(message))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
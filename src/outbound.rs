@@ -0,0 +1,186 @@
+//! Guards for HTTP requests to URLs supplied by users. They may only reach
+//! public addresses, never the loopback interface, private networks or the
+//! cloud metadata endpoint, whatever the host resolves or redirects to.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, bail};
+use hyper::client::connect::dns::Name;
+use reqwest::{
+    dns::{Addrs, Resolve, Resolving},
+    redirect::Policy,
+    ClientBuilder,
+    Url,
+};
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // "This network" and the carrier-grade NAT range
+        || first == 0
+        || (first == 100 && second & 0b1100_0000 == 64))
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => {
+                !(ip.is_unspecified()
+                    || ip.is_loopback()
+                    || ip.is_multicast()
+                    // Unique local fc00::/7 and link local fe80::/10
+                    || ip.segments()[0] & 0xfe00 == 0xfc00
+                    || ip.segments()[0] & 0xffc0 == 0xfe80)
+            }
+        },
+    }
+}
+
+// Checks what can be told from the URL alone. Host names are checked once
+// resolved, by the resolver of the clients built here.
+pub(crate) fn check_url(url: &Url) -> anyhow::Result<()> {
+    if !matches!(url.scheme(), "http" | "https") {
+        bail!("Unsupported URL scheme '{}'", url.scheme());
+    }
+
+    let ip = match url.host() {
+        Some(url::Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(url::Host::Ipv6(ip)) => IpAddr::V6(ip),
+        Some(url::Host::Domain(_)) => return Ok(()),
+        None => bail!("URL '{url}' has no host"),
+    };
+
+    if is_public(ip) {
+        Ok(())
+    } else {
+        Err(anyhow!("Address {ip} of '{url}' is not public"))
+    }
+}
+
+async fn resolve(host: &str, port: u16) -> anyhow::Result<Vec<SocketAddr>> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port)).await?.collect();
+
+    // A single private address is enough to refuse the host, rather than
+    // leaving it to chance which one gets connected to
+    if let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+        bail!("Address {} of '{host}' is not public", addr.ip());
+    }
+
+    if addrs.is_empty() {
+        bail!("Cannot resolve '{host}'");
+    }
+
+    Ok(addrs)
+}
+
+// Checks the URL up front, including what its host resolves to
+pub(crate) async fn check_public(url: &Url) -> anyhow::Result<()> {
+    check_url(url)?;
+
+    if let Some(url::Host::Domain(host)) = url.host() {
+        resolve(host, url.port_or_known_default().unwrap_or_default()).await?;
+    }
+
+    Ok(())
+}
+
+// Connections only ever go to the addresses checked here, so a host can't
+// resolve to a public address for the check and a private one afterwards
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = resolve(name.as_str(), 0).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+// Proxies are skipped, they would resolve the host on their own
+pub(crate) fn client_builder() -> ClientBuilder {
+    reqwest::Client::builder()
+        .no_proxy()
+        .dns_resolver(Arc::new(PublicResolver))
+}
+
+// Follows redirects only to URLs that pass the same checks
+pub(crate) fn redirect_policy(max_redirects: usize) -> Policy {
+    Policy::custom(move |attempt| {
+        if attempt.previous().len() > max_redirects {
+            attempt.error(anyhow!("Too many redirects"))
+        } else if let Err(err) = check_url(attempt.url()) {
+            attempt.error(err)
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_internal_addresses() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fc00::1",
+            "fdff::1",
+            "fe80::1",
+            "febf::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[test]
+    fn accepts_public_addresses() {
+        for ip in ["93.184.216.34", "1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[test]
+    fn checks_literal_hosts() {
+        for url in [
+            "http://127.0.0.1/",
+            "http://[::1]:8080/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://2130706433/",
+            "ftp://example.com/",
+        ] {
+            assert!(check_url(&url.parse().unwrap()).is_err(), "{url}");
+        }
+
+        assert!(check_url(&"https://example.com/product".parse().unwrap()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn resolves_hosts_before_accepting_them() {
+        assert!(check_public(&"http://localhost:5432/".parse().unwrap())
+            .await
+            .is_err());
+    }
+}
@@ -3,15 +3,30 @@ use axum::{
     response::{IntoResponse, Response},
 };
 
-pub(crate) struct AppError(anyhow::Error);
+pub(crate) struct AppError {
+    status: StatusCode,
+    error: anyhow::Error,
+}
+
+impl AppError {
+    pub(crate) fn new<E>(status: StatusCode, err: E) -> Self
+    where
+        E: Into<anyhow::Error>,
+    {
+        Self {
+            status,
+            error: err.into(),
+        }
+    }
+}
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Something went wrong: {}", self.0),
-        )
-            .into_response()
+        if self.status.is_server_error() {
+            (self.status, format!("Something went wrong: {}", self.error)).into_response()
+        } else {
+            (self.status, self.error.to_string()).into_response()
+        }
     }
 }
 
@@ -20,6 +35,6 @@ where
     E: Into<anyhow::Error>,
 {
     fn from(err: E) -> Self {
-        Self(err.into())
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, err)
     }
 }
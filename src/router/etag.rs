@@ -0,0 +1,113 @@
+//! Entity tags carrying the version of users, wishlists and items. Reads are
+//! revalidated with `If-None-Match` and writes guarded with `If-Match`, which
+//! fails with 412 once someone else changed the resource in between.
+
+use anyhow::anyhow;
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use database::traits::Precondition;
+use serde::Serialize;
+
+use super::errors::AppError;
+
+pub(crate) type Version = i32;
+
+fn tag(version: Version) -> String {
+    format!("\"{version}\"")
+}
+
+// Precondition of a write, none without the header. `*` only requires the
+// resource to exist, otherwise a single strong tag can be matched against the
+// stored version.
+pub(crate) struct IfMatch(pub(crate) Option<Precondition>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for IfMatch
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(header::IF_MATCH) else {
+            return Ok(IfMatch(None));
+        };
+
+        let value = value.to_str().unwrap_or_default().trim();
+        if value == "*" {
+            return Ok(IfMatch(Some(Precondition::Exists)));
+        }
+
+        value
+            .strip_prefix('"')
+            .and_then(|x| x.strip_suffix('"'))
+            .and_then(|x| x.parse().ok())
+            .map(|version| IfMatch(Some(Precondition::Version(version))))
+            .ok_or_else(|| {
+                AppError::new(
+                    StatusCode::PRECONDITION_FAILED,
+                    anyhow!("If-Match must be '*' or a single entity tag"),
+                )
+            })
+    }
+}
+
+// Missing resources fail `*` rather than being reported as not found
+pub(crate) fn not_found(precondition: Option<Precondition>, message: &'static str) -> AppError {
+    match precondition {
+        Some(Precondition::Exists) => AppError::new(
+            StatusCode::PRECONDITION_FAILED,
+            anyhow!("If-Match '*' requires an existing resource"),
+        ),
+        _ => AppError::new(StatusCode::NOT_FOUND, anyhow!(message)),
+    }
+}
+
+// A write built from what was just read expects the version read, unless the
+// client named one, so a change made in between isn't overwritten
+pub(crate) fn read_version(
+    precondition: Option<Precondition>,
+    version: Version,
+) -> Option<Precondition> {
+    match precondition {
+        Some(Precondition::Version(_)) => precondition,
+        _ => Some(Precondition::Version(version)),
+    }
+}
+
+pub(crate) fn tagged<T>(status: StatusCode, version: Version, body: T) -> Response
+where
+    T: Serialize,
+{
+    (status, [(header::ETAG, tag(version))], Json(body)).into_response()
+}
+
+// Tags are compared weakly here, the client only asks whether its copy is
+// still good
+pub(crate) fn read<T>(headers: &HeaderMap, version: Version, body: T) -> Response
+where
+    T: Serialize,
+{
+    let etag = tag(version);
+
+    let is_fresh = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|x| x.to_str().ok())
+        .is_some_and(|value| {
+            value.trim() == "*"
+                || value
+                    .split(',')
+                    .any(|x| x.trim().trim_start_matches("W/") == etag)
+        });
+
+    if is_fresh {
+        (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response()
+    } else {
+        tagged(StatusCode::OK, version, body)
+    }
+}
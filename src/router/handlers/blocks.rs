@@ -0,0 +1,153 @@
+use axum::{
+    extract::{Path, Query, State as AxumState},
+    http::StatusCode,
+    Json,
+    Router,
+};
+use chrono::{NaiveDateTime, Utc};
+use database::traits::blocks::{
+    Error as DatabaseError,
+    Kind as DatabaseKind,
+    Payload as DatabasePayload,
+    Response as DatabaseResponse,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::users;
+use crate::router::{errors::AppError, state::State};
+
+#[derive(Deserialize)]
+struct CreatePayload {
+    user_id: users::Id,
+    target_id: users::Id,
+}
+
+impl CreatePayload {
+    fn into_database_payload(self, kind: DatabaseKind) -> DatabasePayload {
+        DatabasePayload {
+            id: Uuid::new_v4(),
+            user_id: self.user_id,
+            target_id: self.target_id,
+            kind,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct OwnerParams {
+    user_id: users::Id,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Response {
+    user_id: users::Id,
+    target_id: users::Id,
+    created_at: NaiveDateTime,
+}
+
+impl From<DatabaseResponse> for Response {
+    fn from(val: DatabaseResponse) -> Self {
+        Response {
+            user_id: val.user_id,
+            target_id: val.target_id,
+            created_at: val.created_at,
+        }
+    }
+}
+
+pub(crate) fn into_app_error(err: DatabaseError) -> AppError {
+    match err {
+        DatabaseError::UserNotFound | DatabaseError::NotFound => {
+            AppError::new(StatusCode::NOT_FOUND, err)
+        }
+        DatabaseError::SelfBlock => AppError::new(StatusCode::UNPROCESSABLE_ENTITY, err),
+        DatabaseError::AlreadyExists => AppError::new(StatusCode::CONFLICT, err),
+        DatabaseError::Unknown => err.into(),
+    }
+}
+
+async fn create(
+    state: &State,
+    payload: CreatePayload,
+    kind: DatabaseKind,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let response = state
+        .repository
+        .create_block(payload.into_database_payload(kind))
+        .await
+        .map_err(into_app_error)?
+        .into();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+async fn delete(
+    state: &State,
+    target_id: users::Id,
+    params: OwnerParams,
+    kind: DatabaseKind,
+) -> Result<(StatusCode, String), AppError> {
+    state
+        .repository
+        .delete_block(params.user_id, target_id, kind)
+        .await
+        .map_err(into_app_error)?;
+
+    Ok((StatusCode::NO_CONTENT, "Object removed".to_owned()))
+}
+
+async fn block(
+    AxumState(state): AxumState<State>,
+    Json(payload): Json<CreatePayload>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    create(&state, payload, DatabaseKind::Block).await
+}
+
+async fn unblock(
+    AxumState(state): AxumState<State>,
+    Path(target_id): Path<users::Id>,
+    Query(params): Query<OwnerParams>,
+) -> Result<(StatusCode, String), AppError> {
+    delete(&state, target_id, params, DatabaseKind::Block).await
+}
+
+async fn mute(
+    AxumState(state): AxumState<State>,
+    Json(payload): Json<CreatePayload>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    create(&state, payload, DatabaseKind::Mute).await
+}
+
+async fn unmute(
+    AxumState(state): AxumState<State>,
+    Path(target_id): Path<users::Id>,
+    Query(params): Query<OwnerParams>,
+) -> Result<(StatusCode, String), AppError> {
+    delete(&state, target_id, params, DatabaseKind::Mute).await
+}
+
+static BLOCKS_SUBPATH: &str = "/blocks";
+static MUTES_SUBPATH: &str = "/mutes";
+
+pub(crate) fn get_router(root_path: &str, state: State) -> Router {
+    Router::new()
+        .route(
+            &format!("{root_path}{BLOCKS_SUBPATH}"),
+            axum::routing::post(block),
+        )
+        .route(
+            &format!("{root_path}{BLOCKS_SUBPATH}/:target_id"),
+            axum::routing::delete(unblock),
+        )
+        .route(
+            &format!("{root_path}{MUTES_SUBPATH}"),
+            axum::routing::post(mute),
+        )
+        .route(
+            &format!("{root_path}{MUTES_SUBPATH}/:target_id"),
+            axum::routing::delete(unmute),
+        )
+        .with_state(state)
+}
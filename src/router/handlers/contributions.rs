@@ -0,0 +1,137 @@
+use axum::{
+    extract::{Path, Query, State as AxumState},
+    http::StatusCode,
+    Json,
+    Router,
+};
+use chrono::{NaiveDateTime, Utc};
+use database::traits::contributions::{
+    Error as DatabaseError,
+    Payload as DatabasePayload,
+    Response as DatabaseResponse,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{items, users};
+use crate::router::{
+    errors::AppError,
+    money::{Amount, Currency},
+    state::State,
+};
+
+type Id = Uuid;
+
+#[derive(Deserialize)]
+struct CreatePayload {
+    item_id: items::Id,
+    user_id: users::Id,
+    amount: Amount,
+    currency: Option<Currency>,
+    note: Option<String>,
+}
+
+impl From<CreatePayload> for DatabasePayload {
+    fn from(val: CreatePayload) -> Self {
+        DatabasePayload {
+            id: Uuid::new_v4(),
+            item_id: val.item_id,
+            user_id: val.user_id,
+            amount: val.amount.into(),
+            currency: val.currency.map(Into::into),
+            note: val.note,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct Response {
+    id: Id,
+    item_id: items::Id,
+    user_id: Option<users::Id>,
+    amount: i64,
+    currency: String,
+    note: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl From<DatabaseResponse> for Response {
+    fn from(val: DatabaseResponse) -> Self {
+        Response {
+            id: val.id,
+            item_id: val.item_id,
+            user_id: val.user_id,
+            amount: val.amount,
+            currency: val.currency,
+            note: val.note,
+            created_at: val.created_at,
+        }
+    }
+}
+
+fn into_app_error(err: DatabaseError) -> AppError {
+    match err {
+        DatabaseError::ItemNotFound => AppError::new(StatusCode::NOT_FOUND, err),
+        DatabaseError::InvalidAmount | DatabaseError::CurrencyMismatch => {
+            AppError::new(StatusCode::UNPROCESSABLE_ENTITY, err)
+        }
+        DatabaseError::Blocked => AppError::new(StatusCode::FORBIDDEN, err),
+        DatabaseError::ItemFunded | DatabaseError::WishlistArchived => {
+            AppError::new(StatusCode::CONFLICT, err)
+        }
+        DatabaseError::Unknown => err.into(),
+    }
+}
+
+async fn create(
+    AxumState(state): AxumState<State>,
+    Json(payload): Json<CreatePayload>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let response = state
+        .repository
+        .create_contribution(payload.into())
+        .await
+        .map_err(into_app_error)?
+        .into();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+async fn get(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+) -> Result<(StatusCode, Json<Option<Response>>), AppError> {
+    let response = state.repository.get_contribution(id).await?.map(Into::into);
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn delete(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(actor): Query<items::ActorParams>,
+) -> Result<(StatusCode, String), AppError> {
+    state
+        .repository
+        .delete_contribution(id, actor.user_id)
+        .await
+        .map_err(into_app_error)?;
+
+    Ok((StatusCode::NO_CONTENT, "Object removed".to_owned()))
+}
+
+static SUBPATH: &str = "/contributions";
+
+pub(crate) fn get_router(root_path: &str, state: State) -> Router {
+    Router::new()
+        .route(
+            &format!("{root_path}{SUBPATH}"),
+            axum::routing::post(create),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id"),
+            axum::routing::get(get).delete(delete),
+        )
+        .with_state(state)
+}
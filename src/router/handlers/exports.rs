@@ -0,0 +1,129 @@
+use std::io::{Cursor, Write};
+
+use axum::{
+    extract::{Path, State as AxumState},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Router,
+};
+use database::traits::exports::{Error as DatabaseError, Export as DatabaseExport};
+use serde::Serialize;
+use zip::{write::FileOptions, ZipWriter};
+
+use super::{contributions, items, reservations, subscriptions, users, wishlists};
+use crate::router::{errors::AppError, state::State};
+
+// The account as its owner sees it, with the email address kept from others
+#[derive(Serialize)]
+struct Account {
+    #[serde(flatten)]
+    user: users::Response,
+    email: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Export {
+    user: Account,
+    wishlists: Vec<wishlists::Response>,
+    items: Vec<items::Response>,
+    subscriptions: Vec<subscriptions::Response>,
+    reservations: Vec<reservations::Response>,
+    contributions: Vec<contributions::Response>,
+}
+
+impl From<DatabaseExport> for Export {
+    fn from(val: DatabaseExport) -> Self {
+        Export {
+            user: Account {
+                email: val.user.email.clone(),
+                user: val.user.into(),
+            },
+            wishlists: val.wishlists.into_iter().map(Into::into).collect(),
+            items: val.items.into_iter().map(Into::into).collect(),
+            subscriptions: val.subscriptions.into_iter().map(Into::into).collect(),
+            reservations: val.reservations.into_iter().map(Into::into).collect(),
+            contributions: val.contributions.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+fn into_app_error(err: DatabaseError) -> AppError {
+    match err {
+        DatabaseError::UserNotFound => AppError::new(StatusCode::NOT_FOUND, err),
+        DatabaseError::Unknown => err.into(),
+    }
+}
+
+// The archive holds the data as JSON next to the avatar and the item pictures,
+// blobs are named after the keys the JSON refers to them by
+fn archive(export: &Export, blobs: Vec<(String, Vec<u8>)>) -> anyhow::Result<Vec<u8>> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+    writer.start_file("data.json", FileOptions::default())?;
+    writer.write_all(&serde_json::to_vec_pretty(export)?)?;
+
+    for (path, content) in blobs {
+        writer.start_file(path, FileOptions::default())?;
+        writer.write_all(&content)?;
+    }
+
+    Ok(writer.finish()?.into_inner())
+}
+
+async fn export(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<users::Id>,
+) -> Result<impl IntoResponse, AppError> {
+    let export = state
+        .repository
+        .export_user(id)
+        .await
+        .map_err(into_app_error)?;
+
+    let mut blobs = Vec::new();
+
+    if let Some(key) = export.user.avatar_id {
+        let value = state
+            .repository
+            .get_user_avatar(key)
+            .await?
+            .collect()
+            .await?;
+        blobs.push((format!("avatars/{key}"), value.to_vec()));
+    }
+
+    for key in export.items.iter().filter_map(|x| x.picture_id) {
+        let value = state
+            .repository
+            .get_item_picture(key)
+            .await?
+            .collect()
+            .await?;
+        blobs.push((format!("pictures/{key}"), value.to_vec()));
+    }
+
+    let content = archive(&export.into(), blobs)?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/zip".to_owned()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"wishlists-export-{id}.zip\""),
+            ),
+        ],
+        content,
+    ))
+}
+
+static SUBPATH: &str = "/users";
+
+pub(crate) fn get_router(root_path: &str, state: State) -> Router {
+    Router::new()
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/export"),
+            axum::routing::get(export),
+        )
+        .with_state(state)
+}
@@ -0,0 +1,155 @@
+use axum::{
+    extract::{Query, State as AxumState},
+    http::StatusCode,
+    Json,
+    Router,
+};
+use chrono::NaiveDateTime;
+use database::traits::feed::{
+    Cursor as DatabaseCursor,
+    Entry as DatabaseEntry,
+    Error as DatabaseError,
+    Kind as DatabaseKind,
+    Page as DatabasePage,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{items, users, wishlists};
+use crate::router::{errors::AppError, state::State};
+
+const DEFAULT_LIMIT: u64 = 20;
+const MAX_LIMIT: u64 = 100;
+const CURSOR_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.6f";
+
+#[derive(Deserialize)]
+#[serde(try_from = "String")]
+struct Cursor(DatabaseCursor);
+
+impl TryFrom<String> for Cursor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value
+            .split_once('_')
+            .and_then(|(created_at, id)| {
+                Some(DatabaseCursor {
+                    created_at: NaiveDateTime::parse_from_str(created_at, CURSOR_TIME_FORMAT)
+                        .ok()?,
+                    id: id.parse().ok()?,
+                })
+            })
+            .map(Cursor)
+            .ok_or_else(|| format!("'{value}' is not a valid cursor"))
+    }
+}
+
+impl From<Cursor> for String {
+    fn from(val: Cursor) -> Self {
+        format!(
+            "{}_{}",
+            val.0.created_at.format(CURSOR_TIME_FORMAT),
+            val.0.id
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct ListParams {
+    user_id: users::Id,
+    before: Option<Cursor>,
+    limit: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Kind {
+    WishlistCreated,
+    ItemAdded,
+}
+
+impl From<DatabaseKind> for Kind {
+    fn from(val: DatabaseKind) -> Self {
+        match val {
+            DatabaseKind::WishlistCreated => Kind::WishlistCreated,
+            DatabaseKind::ItemAdded => Kind::ItemAdded,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Entry {
+    kind: Kind,
+    user_id: users::Id,
+    wishlist_id: wishlists::Id,
+    wishlist_name: String,
+    item_id: Option<items::Id>,
+    item_name: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl From<DatabaseEntry> for Entry {
+    fn from(val: DatabaseEntry) -> Self {
+        Entry {
+            kind: val.kind.into(),
+            user_id: val.user_id,
+            wishlist_id: val.wishlist_id,
+            wishlist_name: val.wishlist_name,
+            item_id: val.item_id,
+            item_name: val.item_name,
+            created_at: val.created_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Response {
+    entries: Vec<Entry>,
+    next_cursor: Option<String>,
+}
+
+fn into_app_error(err: DatabaseError) -> AppError {
+    match err {
+        DatabaseError::UserNotFound => AppError::new(StatusCode::NOT_FOUND, err),
+        DatabaseError::Unknown => err.into(),
+    }
+}
+
+async fn list(
+    AxumState(state): AxumState<State>,
+    Query(params): Query<ListParams>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let entries = state
+        .repository
+        .list_user_feed(
+            params.user_id,
+            DatabasePage {
+                before: params.before.map(|x| x.0),
+                limit,
+            },
+        )
+        .await
+        .map_err(into_app_error)?;
+
+    // A short page means the feed is exhausted
+    let next_cursor = match entries.last() {
+        Some(entry) if entries.len() as u64 == limit => Some(Cursor(entry.cursor()).into()),
+        _ => None,
+    };
+
+    let response = Response {
+        entries: entries.into_iter().map(Into::into).collect(),
+        next_cursor,
+    };
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+static SUBPATH: &str = "/feed";
+
+pub(crate) fn get_router(root_path: &str, state: State) -> Router {
+    Router::new()
+        .route(&format!("{root_path}{SUBPATH}"), axum::routing::get(list))
+        .with_state(state)
+}
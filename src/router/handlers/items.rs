@@ -1,40 +1,181 @@
+use std::collections::HashMap;
+
+use anyhow::anyhow;
 use axum::{
     extract::{Path, Query, State as AxumState},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response as AxumResponse},
     Json,
     Router,
 };
 use chrono::{NaiveDateTime, Utc};
-use database::traits::items::{Payload as DatabasePayload, Response as DatabaseResponse};
+use database::{
+    traits::{
+        items::{
+            Error as DatabaseError,
+            Filter as DatabaseFilter,
+            Operation as DatabaseOperation,
+            Outcome as DatabaseOutcome,
+            Patch as DatabasePatch,
+            Payload as DatabasePayload,
+            Priority as DatabasePriority,
+            Response as DatabaseResponse,
+            Sort as DatabaseSort,
+            Update as DatabaseUpdate,
+        },
+        wishlists::Response as WishlistResponse,
+    },
+    RepositoryTrait,
+};
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 use uuid::Uuid;
 
-use super::{users, wishlists};
-use crate::router::{errors::AppError, state::State};
+use super::{contributions, reservations, users, wishlists};
+use crate::router::{
+    errors::AppError,
+    etag::{self, IfMatch},
+    idempotency::{self, Idempotent, Outcome},
+    money::{Amount, Currency, Money, Price},
+    patch::{nullable, present},
+    state::State,
+};
 
-type Id = Uuid;
+const MAX_BATCH_OPERATIONS: usize = 100;
+
+pub(crate) type Id = Uuid;
 type PictureId = Uuid;
 pub type Predicate = String;
 
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Priority {
+    NiceToHave,
+    Normal,
+    MustHave,
+}
+
+impl From<Priority> for DatabasePriority {
+    fn from(val: Priority) -> Self {
+        match val {
+            Priority::NiceToHave => DatabasePriority::NiceToHave,
+            Priority::Normal => DatabasePriority::Normal,
+            Priority::MustHave => DatabasePriority::MustHave,
+        }
+    }
+}
+
+impl From<DatabasePriority> for Priority {
+    fn from(val: DatabasePriority) -> Self {
+        match val {
+            DatabasePriority::NiceToHave => Priority::NiceToHave,
+            DatabasePriority::Normal => Priority::Normal,
+            DatabasePriority::MustHave => Priority::MustHave,
+        }
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Sort {
+    #[default]
+    Default,
+    PriceAsc,
+    PriceDesc,
+    Priority,
+}
+
+impl From<Sort> for DatabaseSort {
+    fn from(val: Sort) -> Self {
+        match val {
+            Sort::Default => DatabaseSort::Default,
+            Sort::PriceAsc => DatabaseSort::PriceAsc,
+            Sort::PriceDesc => DatabaseSort::PriceDesc,
+            Sort::Priority => DatabaseSort::Priority,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ListParams {
+    predicate: Option<Predicate>,
+    currency: Option<Currency>,
+    min_price: Option<Amount>,
+    max_price: Option<Amount>,
+    #[serde(default)]
+    sort: Sort,
+}
+
+impl TryFrom<ListParams> for DatabaseFilter {
+    type Error = AppError;
+
+    fn try_from(val: ListParams) -> Result<Self, Self::Error> {
+        if val.currency.is_none() && (val.min_price.is_some() || val.max_price.is_some()) {
+            return Err(AppError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                anyhow!("Price filters require a currency"),
+            ));
+        }
+
+        Ok(DatabaseFilter {
+            predicate: val.predicate,
+            currency: val.currency.map(Into::into),
+            min_price: val.min_price.map(Into::into),
+            max_price: val.max_price.map(Into::into),
+            sort: val.sort.into(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(try_from = "String")]
+pub(crate) struct ProductUrl(pub(crate) String);
+
+impl TryFrom<String> for ProductUrl {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match Url::parse(&value) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") && value.len() <= 2048 => {
+                Ok(ProductUrl(value))
+            }
+            _ => Err(format!("'{value}' is not a valid product URL")),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct CreatePayload {
     wishlist_id: wishlists::Id,
+    #[serde(default)]
     name: String,
     description: Option<String>,
-    price: Option<i32>,
+    url: Option<ProductUrl>,
+    price: Option<Price>,
+    quantity: Option<i32>,
+    priority: Option<Priority>,
     is_hidden: bool,
 }
 
 impl From<CreatePayload> for DatabasePayload {
     fn from(val: CreatePayload) -> Self {
+        let (price, currency) = val.price.map_or((None, None), |price| {
+            (Some(price.amount.into()), price.currency.map(Into::into))
+        });
+
         DatabasePayload {
             id: Uuid::new_v4(),
             wishlist_id: val.wishlist_id,
-            selected_by_id: None,
             name: val.name,
             description: val.description,
-            price: val.price,
+            url: val.url.map(|x| x.0),
+            price,
+            currency,
+            quantity: val.quantity.unwrap_or(1),
+            priority: val.priority.map(Into::into).unwrap_or_default(),
             is_hidden: val.is_hidden,
+            is_funded: false,
             picture_id: None,
             created_at: Utc::now().naive_utc(),
             updated_at: Utc::now().naive_utc(),
@@ -44,38 +185,191 @@ impl From<CreatePayload> for DatabasePayload {
 
 #[derive(Deserialize)]
 struct UpdatePayload {
-    wishlist_id: wishlists::Id,
-    selected_by_id: Option<users::Id>,
     name: String,
     description: Option<String>,
-    price: Option<i32>,
+    url: Option<ProductUrl>,
+    price: Option<Price>,
+    quantity: i32,
+    priority: Option<Priority>,
     is_hidden: bool,
+    is_funded: bool,
+}
+
+impl From<UpdatePayload> for DatabaseUpdate {
+    fn from(val: UpdatePayload) -> Self {
+        let (price, currency) = val.price.map_or((None, None), |price| {
+            (Some(price.amount.into()), price.currency.map(Into::into))
+        });
+
+        DatabaseUpdate {
+            name: val.name,
+            description: val.description,
+            url: val.url.map(|x| x.0),
+            price,
+            currency,
+            quantity: val.quantity,
+            priority: val.priority.map(Into::into),
+            is_hidden: val.is_hidden,
+            is_funded: val.is_funded,
+        }
+    }
+}
+
+#[allow(clippy::option_option)]
+#[derive(Deserialize)]
+struct PatchPayload {
+    #[serde(default, deserialize_with = "present")]
+    name: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    url: Option<Option<ProductUrl>>,
+    #[serde(default, deserialize_with = "nullable")]
+    price: Option<Option<Price>>,
+    #[serde(default, deserialize_with = "present")]
+    quantity: Option<i32>,
+    #[serde(default, deserialize_with = "present")]
+    priority: Option<Priority>,
+    #[serde(default, deserialize_with = "present")]
+    is_hidden: Option<bool>,
+    #[serde(default, deserialize_with = "present")]
+    is_funded: Option<bool>,
+}
+
+// A price is replaced as a whole rather than merged member by member
+impl From<PatchPayload> for DatabasePatch {
+    fn from(val: PatchPayload) -> Self {
+        let (price, currency) = match val.price {
+            Some(Some(price)) => (Some(Some(price.amount.into())), price.currency),
+            Some(None) => (Some(None), None),
+            None => (None, None),
+        };
+
+        DatabasePatch {
+            name: val.name,
+            description: val.description,
+            url: val.url.map(|x| x.map(|x| x.0)),
+            price,
+            currency: currency.map(Into::into),
+            quantity: val.quantity,
+            priority: val.priority.map(Into::into),
+            is_hidden: val.is_hidden,
+            is_funded: val.is_funded,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum BatchOperation {
+    Create { item: CreatePayload },
+    Update { id: Id, item: UpdatePayload },
+    Delete { id: Id },
+}
+
+impl From<BatchOperation> for DatabaseOperation {
+    fn from(val: BatchOperation) -> Self {
+        match val {
+            BatchOperation::Create { item } => DatabaseOperation::Create(item.into()),
+            BatchOperation::Update { id, item } => DatabaseOperation::Update(id, item.into()),
+            BatchOperation::Delete { id } => DatabaseOperation::Delete(id),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct BatchPayload {
+    operations: Vec<BatchOperation>,
+}
+
+#[derive(Serialize)]
+struct BatchResult {
+    status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    item: Option<Response>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl BatchResult {
+    fn new(status: StatusCode, item: Option<Response>, error: Option<String>) -> Self {
+        BatchResult {
+            status: status.as_u16(),
+            item,
+            error,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BatchResponse {
+    results: Vec<BatchResult>,
+}
+
+#[derive(Deserialize)]
+struct ReorderPayload {
+    position: u32,
+}
+
+#[derive(Deserialize)]
+struct TransferPayload {
+    user_id: users::Id,
+    wishlist_id: wishlists::Id,
 }
 
 #[derive(Serialize)]
 pub(crate) struct Response {
     id: Id,
     wishlist_id: wishlists::Id,
-    selected_by_id: Option<users::Id>,
     name: String,
     description: Option<String>,
-    price: Option<i32>,
+    url: Option<String>,
+    price: Option<Money>,
+    quantity: i32,
+    reserved_quantity: Option<i32>,
+    remaining_quantity: Option<i32>,
+    contributed_amount: i64,
+    funded_percentage: Option<i32>,
+    priority: Priority,
+    position: i32,
     is_hidden: bool,
+    is_funded: bool,
     picture_id: Option<PictureId>,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
 }
 
+fn funded_percentage(val: &DatabaseResponse) -> Option<i32> {
+    if val.is_funded {
+        return Some(100);
+    }
+
+    val.price.filter(|price| *price > 0).map(|price| {
+        let percentage = val.contributed_amount.saturating_mul(100) / price;
+        i32::try_from(percentage.min(100)).unwrap_or(100)
+    })
+}
+
 impl From<DatabaseResponse> for Response {
     fn from(val: DatabaseResponse) -> Self {
+        let funded_percentage = funded_percentage(&val);
+
         Response {
             id: val.id,
             wishlist_id: val.wishlist_id,
-            selected_by_id: val.selected_by_id,
             name: val.name,
             description: val.description,
-            price: val.price,
+            url: val.url,
+            price: Money::from_parts(val.price, val.currency),
+            quantity: val.quantity,
+            reserved_quantity: Some(val.reserved_quantity),
+            remaining_quantity: Some(val.quantity - val.reserved_quantity),
+            contributed_amount: val.contributed_amount,
+            funded_percentage,
+            priority: val.priority.into(),
+            position: val.position,
             is_hidden: val.is_hidden,
+            is_funded: val.is_funded,
             picture_id: val.picture_id,
             created_at: val.created_at,
             updated_at: val.updated_at,
@@ -83,81 +377,534 @@ impl From<DatabaseResponse> for Response {
     }
 }
 
+impl Response {
+    pub(crate) fn conceal_reservations(self) -> Self {
+        Response {
+            reserved_quantity: None,
+            remaining_quantity: None,
+            ..self
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ViewerParams {
+    pub(crate) user_id: users::Id,
+}
+
+impl ViewerParams {
+    // Owners don't see who reserved what until the event is over
+    pub(crate) fn conceals_reservations(&self, wishlist: &WishlistResponse) -> bool {
+        !wishlist.is_archived && self.user_id == wishlist.user_id
+    }
+
+    async fn conceals_reservations_of(
+        &self,
+        state: &State,
+        wishlist_id: wishlists::Id,
+    ) -> Result<bool, AppError> {
+        Ok(state
+            .repository
+            .get_wishlist(wishlist_id)
+            .await?
+            .map_or(true, |wishlist| self.conceals_reservations(&wishlist)))
+    }
+}
+
+// The user making a change, it is recorded in the history of the wishlist
+#[derive(Deserialize)]
+pub(crate) struct ActorParams {
+    pub(crate) user_id: users::Id,
+}
+
+async fn default_currency(
+    repository: &(dyn RepositoryTrait + Send + Sync),
+    wishlist_id: wishlists::Id,
+) -> Result<String, AppError> {
+    let wishlist = repository
+        .get_wishlist(wishlist_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("Wishlist not found")))?;
+
+    let user = repository
+        .get_user(wishlist.user_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("User not found")))?;
+
+    Ok(user.currency)
+}
+
 async fn list(
     AxumState(state): AxumState<State>,
-    Query(predicate): Query<Option<Predicate>>,
+    Query(params): Query<ListParams>,
+    Query(viewer): Query<ViewerParams>,
 ) -> Result<(StatusCode, Json<Vec<Response>>), AppError> {
-    let response = state
+    let items = state
         .repository
-        .list_items(predicate)
-        .await?
-        .into_iter()
-        .map(Into::into)
-        .collect();
+        .list_items(viewer.user_id, params.try_into()?)
+        .await?;
+
+    let mut concealed = HashMap::new();
+    let mut response = Vec::with_capacity(items.len());
+    for item in items {
+        let conceal = if let Some(conceal) = concealed.get(&item.wishlist_id) {
+            *conceal
+        } else {
+            let conceal = viewer
+                .conceals_reservations_of(&state, item.wishlist_id)
+                .await?;
+            concealed.insert(item.wishlist_id, conceal);
+            conceal
+        };
+
+        let item = Response::from(item);
+        response.push(if conceal {
+            item.conceal_reservations()
+        } else {
+            item
+        });
+    }
 
     Ok((StatusCode::OK, Json(response)))
 }
 
+fn error_status(err: &DatabaseError) -> StatusCode {
+    match err {
+        DatabaseError::InvalidQuantity => StatusCode::UNPROCESSABLE_ENTITY,
+        DatabaseError::QuantityBelowReserved | DatabaseError::WishlistArchived => {
+            StatusCode::CONFLICT
+        }
+        DatabaseError::NotFound | DatabaseError::WishlistNotFound => StatusCode::NOT_FOUND,
+        DatabaseError::NotOwner => StatusCode::FORBIDDEN,
+        DatabaseError::VersionMismatch => StatusCode::PRECONDITION_FAILED,
+        DatabaseError::Unknown => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn into_app_error(err: DatabaseError) -> AppError {
+    AppError::new(error_status(&err), err)
+}
+
+fn spawn_enrichment(state: &State, id: Id) {
+    let enricher = state.enricher.clone();
+
+    tokio::spawn(async move {
+        if let Err(err) = enricher.enrich_item(id).await {
+            warn!("Cannot enrich item {id}: {err:#}");
+        }
+    });
+}
+
 async fn create(
     AxumState(state): AxumState<State>,
-    Json(payload): Json<CreatePayload>,
-) -> Result<(StatusCode, Json<Response>), AppError> {
-    let response = state.repository.create_item(payload.into()).await?.into();
+    Query(actor): Query<ActorParams>,
+    Idempotent { key, payload }: Idempotent<CreatePayload>,
+) -> Result<AxumResponse, AppError> {
+    if payload.name.is_empty() && payload.url.is_none() {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            anyhow!("Either a name or a product URL is required"),
+        ));
+    }
 
-    Ok((StatusCode::CREATED, Json(response)))
+    let outcome = idempotency::run(&state, SUBPATH, key, move |repository| {
+        Box::pin(async move {
+            let mut payload: DatabasePayload = payload.into();
+            if payload.price.is_some() && payload.currency.is_none() {
+                payload.currency = Some(default_currency(repository, payload.wishlist_id).await?);
+            }
+
+            let response = repository
+                .create_item(payload, Some(actor.user_id))
+                .await
+                .map_err(into_app_error)?;
+
+            Ok((StatusCode::CREATED, Response::from(response)))
+        })
+    })
+    .await?;
+
+    match outcome {
+        Outcome::Done(status, response) => {
+            if response.url.is_some() {
+                spawn_enrichment(&state, response.id);
+            }
+
+            Ok((status, Json(response)).into_response())
+        }
+        Outcome::Replayed(response) => Ok(response),
+    }
 }
 
 async fn get(
     AxumState(state): AxumState<State>,
     Path(id): Path<Id>,
-) -> Result<(StatusCode, Json<Option<Response>>), AppError> {
-    let response = state.repository.get_item(id).await?.map(Into::into);
+    Query(viewer): Query<ViewerParams>,
+    headers: HeaderMap,
+) -> Result<AxumResponse, AppError> {
+    let Some(item) = state
+        .repository
+        .get_visible_item(id, viewer.user_id)
+        .await?
+    else {
+        return Ok((StatusCode::OK, Json(None::<Response>)).into_response());
+    };
 
-    Ok((StatusCode::OK, Json(response)))
+    let version = item.version;
+    let mut response = Response::from(item);
+    if viewer
+        .conceals_reservations_of(&state, response.wishlist_id)
+        .await?
+    {
+        response = response.conceal_reservations();
+    }
+
+    Ok(etag::read(&headers, version, response))
 }
 
 async fn update(
     AxumState(state): AxumState<State>,
     Path(id): Path<Id>,
+    Query(actor): Query<ActorParams>,
+    IfMatch(version): IfMatch,
     Json(payload): Json<UpdatePayload>,
-) -> Result<(StatusCode, Json<Response>), AppError> {
-    match state.repository.get_item(id).await? {
-        Some(object) => {
-            let response = state
-                .repository
-                .update_item(
-                    id,
-                    DatabasePayload {
+) -> Result<AxumResponse, AppError> {
+    let (response, url_changed) = state
+        .repository
+        .unit_of_work(move |repository| {
+            Box::pin(async move {
+                let object = repository
+                    .get_item(id)
+                    .await?
+                    .ok_or_else(|| etag::not_found(version, "Item not found"))?;
+
+                let (price, currency) = match payload.price {
+                    Some(Price {
+                        amount,
+                        currency: Some(currency),
+                    }) => (Some(amount.into()), Some(currency.into())),
+                    Some(Price {
+                        amount,
+                        currency: None,
+                    }) => (
+                        Some(amount.into()),
+                        Some(default_currency(repository, object.wishlist_id).await?),
+                    ),
+                    None => (None, None),
+                };
+
+                let url = payload.url.map(|x| x.0);
+                let url_changed = url.is_some() && url != object.url;
+
+                let response = repository
+                    .update_item(
                         id,
-                        wishlist_id: payload.wishlist_id,
-                        selected_by_id: payload.selected_by_id,
-                        name: payload.name,
-                        description: payload.description,
-                        price: payload.price,
-                        is_hidden: payload.is_hidden,
-                        picture_id: object.picture_id,
-                        created_at: object.created_at,
-                        updated_at: Utc::now().naive_local(),
-                    },
-                )
-                .await?
-                .into();
+                        DatabasePayload {
+                            id,
+                            wishlist_id: object.wishlist_id,
+                            name: payload.name,
+                            description: payload.description,
+                            url,
+                            price,
+                            currency,
+                            quantity: payload.quantity,
+                            priority: payload.priority.map_or(object.priority, Into::into),
+                            is_hidden: payload.is_hidden,
+                            is_funded: payload.is_funded,
+                            picture_id: object.picture_id,
+                            created_at: object.created_at,
+                            updated_at: Utc::now().naive_local(),
+                        },
+                        etag::read_version(version, object.version),
+                        Some(actor.user_id),
+                    )
+                    .await
+                    .map_err(into_app_error)?;
 
-            Ok((StatusCode::OK, Json(response)))
-        }
-        None => todo!(),
+                Ok::<_, AppError>((response, url_changed))
+            })
+        })
+        .await?;
+
+    if url_changed {
+        spawn_enrichment(&state, id);
     }
+
+    Ok(etag::tagged(
+        StatusCode::OK,
+        response.version,
+        Response::from(response),
+    ))
+}
+
+async fn patch(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(actor): Query<ActorParams>,
+    IfMatch(version): IfMatch,
+    Json(payload): Json<PatchPayload>,
+) -> Result<AxumResponse, AppError> {
+    let url_given = matches!(payload.url, Some(Some(_)));
+
+    let response = state
+        .repository
+        .patch_item(id, payload.into(), version, Some(actor.user_id))
+        .await
+        .map_err(into_app_error)?;
+
+    if url_given {
+        spawn_enrichment(&state, id);
+    }
+
+    Ok(etag::tagged(
+        StatusCode::OK,
+        response.version,
+        Response::from(response),
+    ))
 }
 
 async fn delete(
     AxumState(state): AxumState<State>,
     Path(id): Path<Id>,
+    Query(actor): Query<ActorParams>,
+    IfMatch(version): IfMatch,
 ) -> Result<(StatusCode, String), AppError> {
-    state.repository.delete_item(id).await?;
+    state
+        .repository
+        .delete_item(id, version, Some(actor.user_id))
+        .await
+        .map_err(into_app_error)?;
 
     Ok((StatusCode::NO_CONTENT, "Object removed".to_owned()))
 }
 
+// Operations run in order in a single transaction, so either all of them are
+// applied or none is. On failure the failing operation carries its error and
+// the others are reported as not applied.
+async fn batch(
+    AxumState(state): AxumState<State>,
+    Query(actor): Query<ActorParams>,
+    Json(payload): Json<BatchPayload>,
+) -> Result<(StatusCode, Json<BatchResponse>), AppError> {
+    if payload.operations.is_empty() || payload.operations.len() > MAX_BATCH_OPERATIONS {
+        return Err(AppError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            anyhow!("A batch must contain between 1 and {MAX_BATCH_OPERATIONS} operations"),
+        ));
+    }
+
+    for (index, operation) in payload.operations.iter().enumerate() {
+        if let BatchOperation::Create { item } = operation {
+            if item.name.is_empty() && item.url.is_none() {
+                return Err(AppError::new(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    anyhow!("Operation {index}: either a name or a product URL is required"),
+                ));
+            }
+        }
+    }
+
+    let count = payload.operations.len();
+    let operations = payload.operations.into_iter().map(Into::into).collect();
+
+    let outcomes = match state
+        .repository
+        .batch_items(operations, Some(actor.user_id))
+        .await
+    {
+        Ok(outcomes) => outcomes,
+        Err(err) => {
+            let status = error_status(&err.error);
+            let Some(failed) = err.index else {
+                return Err(AppError::new(status, err));
+            };
+
+            let results = (0..count)
+                .map(|index| {
+                    if index == failed {
+                        BatchResult::new(status, None, Some(err.error.to_string()))
+                    } else {
+                        BatchResult::new(
+                            StatusCode::FAILED_DEPENDENCY,
+                            None,
+                            Some(format!("Operation {failed} failed")),
+                        )
+                    }
+                })
+                .collect();
+
+            return Ok((status, Json(BatchResponse { results })));
+        }
+    };
+
+    let results = outcomes
+        .into_iter()
+        .map(|outcome| match outcome {
+            DatabaseOutcome::Created(item) => {
+                if item.url.is_some() {
+                    spawn_enrichment(&state, item.id);
+                }
+
+                BatchResult::new(StatusCode::CREATED, Some(item.into()), None)
+            }
+            DatabaseOutcome::Updated { item, url_changed } => {
+                if url_changed {
+                    spawn_enrichment(&state, item.id);
+                }
+
+                BatchResult::new(StatusCode::OK, Some(item.into()), None)
+            }
+            DatabaseOutcome::Deleted(_) => BatchResult::new(StatusCode::NO_CONTENT, None, None),
+        })
+        .collect();
+
+    Ok((StatusCode::OK, Json(BatchResponse { results })))
+}
+
+async fn restore(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(actor): Query<ActorParams>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let response = state
+        .repository
+        .restore_item(id, actor.user_id)
+        .await
+        .map_err(into_app_error)?
+        .into();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn reorder(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(actor): Query<ActorParams>,
+    Json(payload): Json<ReorderPayload>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let position = i32::try_from(payload.position).unwrap_or(i32::MAX);
+
+    let response = state
+        .repository
+        .reorder_item(id, position, actor.user_id)
+        .await
+        .map_err(into_app_error)?
+        .into();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn move_to(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Json(payload): Json<TransferPayload>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let response = state
+        .repository
+        .move_item(id, payload.user_id, payload.wishlist_id)
+        .await
+        .map_err(into_app_error)?
+        .into();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn copy_to(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Json(payload): Json<TransferPayload>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let response = state
+        .repository
+        .copy_item(id, payload.user_id, payload.wishlist_id)
+        .await
+        .map_err(into_app_error)?
+        .into();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+async fn enrich(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(actor): Query<ActorParams>,
+) -> Result<(StatusCode, Json<Option<Response>>), AppError> {
+    let Some(item) = state.repository.get_item(id).await? else {
+        return Ok((StatusCode::NOT_FOUND, Json(None)));
+    };
+    let wishlist = state
+        .repository
+        .get_wishlist(item.wishlist_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("Wishlist not found")))?;
+
+    if wishlist.user_id != actor.user_id {
+        return Err(AppError::new(
+            StatusCode::FORBIDDEN,
+            anyhow!("Only the owner can enrich an item"),
+        ));
+    }
+
+    let response = state
+        .enricher
+        .enrich_item(id)
+        .await
+        .map_err(|err| AppError::new(StatusCode::BAD_GATEWAY, err))?;
+
+    match response {
+        Some(response) => Ok((StatusCode::OK, Json(Some(response.into())))),
+        None => Ok((StatusCode::NOT_FOUND, Json(None))),
+    }
+}
+
+async fn list_reservations(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(viewer): Query<ViewerParams>,
+) -> Result<(StatusCode, Json<Vec<reservations::Response>>), AppError> {
+    let item = state
+        .repository
+        .get_visible_item(id, viewer.user_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("Item not found")))?;
+
+    if viewer
+        .conceals_reservations_of(&state, item.wishlist_id)
+        .await?
+    {
+        return Err(AppError::new(
+            StatusCode::FORBIDDEN,
+            anyhow!("Reservations are revealed to the owner after the event"),
+        ));
+    }
+
+    let response = state
+        .repository
+        .list_item_reservations(id, viewer.user_id)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn list_contributions(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(viewer): Query<ViewerParams>,
+) -> Result<(StatusCode, Json<Vec<contributions::Response>>), AppError> {
+    let response = state
+        .repository
+        .list_item_contributions(id, viewer.user_id)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
 static SUBPATH: &str = "/items";
 
 pub fn get_router(root_path: &str, state: State) -> Router {
@@ -166,9 +913,44 @@ pub fn get_router(root_path: &str, state: State) -> Router {
             &format!("{root_path}{SUBPATH}"),
             axum::routing::get(list).post(create),
         )
+        .route(
+            &format!("{root_path}{SUBPATH}/batch"),
+            axum::routing::post(batch),
+        )
         .route(
             &format!("{root_path}{SUBPATH}/:id"),
-            axum::routing::get(get).put(update).delete(delete),
+            axum::routing::get(get)
+                .put(update)
+                .patch(patch)
+                .delete(delete),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/restore"),
+            axum::routing::post(restore),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/position"),
+            axum::routing::put(reorder),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/move"),
+            axum::routing::post(move_to),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/copy"),
+            axum::routing::post(copy_to),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/enrich"),
+            axum::routing::post(enrich),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/reservations"),
+            axum::routing::get(list_reservations),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/contributions"),
+            axum::routing::get(list_contributions),
         )
         .with_state(state)
 }
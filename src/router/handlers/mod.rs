@@ -1,4 +1,14 @@
+pub mod blocks;
+pub mod contributions;
+pub mod exports;
+pub mod feed;
 pub mod health;
 pub mod items;
+pub mod notifications;
+pub mod reservations;
+pub mod subscriptions;
+pub mod suggestions;
+pub mod templates;
 pub mod users;
+pub mod webhooks;
 pub mod wishlists;
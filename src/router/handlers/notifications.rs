@@ -0,0 +1,153 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, Query, State as AxumState},
+    http::StatusCode,
+    Json,
+    Router,
+};
+use chrono::NaiveDateTime;
+use database::traits::notifications::{
+    Error as DatabaseError,
+    Filter as DatabaseFilter,
+    Kind as DatabaseKind,
+    Response as DatabaseResponse,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{items, users, wishlists};
+use crate::router::{errors::AppError, state::State};
+
+type Id = Uuid;
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Kind {
+    WishlistCreated,
+    ItemAdded,
+    ItemReserved,
+}
+
+impl From<DatabaseKind> for Kind {
+    fn from(val: DatabaseKind) -> Self {
+        match val {
+            DatabaseKind::WishlistCreated => Kind::WishlistCreated,
+            DatabaseKind::ItemAdded => Kind::ItemAdded,
+            DatabaseKind::ItemReserved => Kind::ItemReserved,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ListParams {
+    #[serde(default)]
+    unread: bool,
+}
+
+impl From<ListParams> for DatabaseFilter {
+    fn from(val: ListParams) -> Self {
+        DatabaseFilter { unread: val.unread }
+    }
+}
+
+#[derive(Deserialize)]
+struct RecipientParams {
+    user_id: users::Id,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Response {
+    id: Id,
+    user_id: users::Id,
+    kind: Kind,
+    actor_id: Option<users::Id>,
+    wishlist_id: Option<wishlists::Id>,
+    item_id: Option<items::Id>,
+    is_read: bool,
+    created_at: NaiveDateTime,
+}
+
+impl From<DatabaseResponse> for Response {
+    fn from(val: DatabaseResponse) -> Self {
+        Response {
+            id: val.id,
+            user_id: val.user_id,
+            kind: val.kind.into(),
+            actor_id: val.actor_id,
+            wishlist_id: val.wishlist_id,
+            item_id: val.item_id,
+            is_read: val.is_read,
+            created_at: val.created_at,
+        }
+    }
+}
+
+fn into_app_error(err: DatabaseError) -> AppError {
+    match err {
+        DatabaseError::NotFound => AppError::new(StatusCode::NOT_FOUND, err),
+        DatabaseError::Unknown => err.into(),
+    }
+}
+
+async fn mark(
+    state: &State,
+    id: Id,
+    params: &RecipientParams,
+    is_read: bool,
+) -> Result<Response, AppError> {
+    let notification = state
+        .repository
+        .get_notification(id)
+        .await
+        .map_err(into_app_error)?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("Notification not found")))?;
+
+    if notification.user_id != params.user_id {
+        return Err(AppError::new(
+            StatusCode::FORBIDDEN,
+            anyhow!("Only the recipient can change a notification"),
+        ));
+    }
+
+    state
+        .repository
+        .mark_notification(id, is_read)
+        .await
+        .map(Into::into)
+        .map_err(into_app_error)
+}
+
+async fn read(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(params): Query<RecipientParams>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let response = mark(&state, id, &params, true).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn unread(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(params): Query<RecipientParams>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let response = mark(&state, id, &params, false).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+static SUBPATH: &str = "/notifications";
+
+pub(crate) fn get_router(root_path: &str, state: State) -> Router {
+    Router::new()
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/read"),
+            axum::routing::post(read),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/unread"),
+            axum::routing::post(unread),
+        )
+        .with_state(state)
+}
@@ -0,0 +1,196 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, Query, State as AxumState},
+    http::StatusCode,
+    Json,
+    Router,
+};
+use chrono::{NaiveDateTime, Utc};
+use database::traits::subscriptions::{
+    Error as DatabaseError,
+    Payload as DatabasePayload,
+    Response as DatabaseResponse,
+    Status as DatabaseStatus,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::users;
+use crate::router::{errors::AppError, state::State};
+
+type Id = Uuid;
+
+#[derive(Deserialize)]
+struct CreatePayload {
+    user_id: users::Id,
+    subscriber_id: users::Id,
+}
+
+impl From<CreatePayload> for DatabasePayload {
+    fn from(val: CreatePayload) -> Self {
+        DatabasePayload {
+            id: Uuid::new_v4(),
+            user_id: val.user_id,
+            subscriber_id: val.subscriber_id,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RecipientParams {
+    user_id: users::Id,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Pending,
+    Accepted,
+}
+
+impl From<DatabaseStatus> for Status {
+    fn from(val: DatabaseStatus) -> Self {
+        match val {
+            DatabaseStatus::Pending => Status::Pending,
+            DatabaseStatus::Accepted => Status::Accepted,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct Response {
+    id: Id,
+    user_id: users::Id,
+    subscriber_id: users::Id,
+    status: Status,
+    created_at: NaiveDateTime,
+}
+
+impl From<DatabaseResponse> for Response {
+    fn from(val: DatabaseResponse) -> Self {
+        Response {
+            id: val.id,
+            user_id: val.user_id,
+            subscriber_id: val.subscriber_id,
+            status: val.status.into(),
+            created_at: val.created_at,
+        }
+    }
+}
+
+pub(crate) fn into_app_error(err: DatabaseError) -> AppError {
+    match err {
+        DatabaseError::NotFound | DatabaseError::UserNotFound => {
+            AppError::new(StatusCode::NOT_FOUND, err)
+        }
+        DatabaseError::SelfSubscription => AppError::new(StatusCode::UNPROCESSABLE_ENTITY, err),
+        DatabaseError::Blocked => AppError::new(StatusCode::FORBIDDEN, err),
+        DatabaseError::AlreadyExists | DatabaseError::NotPending => {
+            AppError::new(StatusCode::CONFLICT, err)
+        }
+        DatabaseError::Unknown => err.into(),
+    }
+}
+
+// Only the followed user decides on requests to follow them
+async fn check_recipient(state: &State, id: Id, params: &RecipientParams) -> Result<(), AppError> {
+    let subscription = state
+        .repository
+        .get_subscription(id)
+        .await
+        .map_err(into_app_error)?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("Subscription not found")))?;
+
+    if subscription.user_id != params.user_id {
+        return Err(AppError::new(
+            StatusCode::FORBIDDEN,
+            anyhow!("Only the followed user can answer a subscription request"),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn create(
+    AxumState(state): AxumState<State>,
+    Json(payload): Json<CreatePayload>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let response = state
+        .repository
+        .create_subscription(payload.into())
+        .await
+        .map_err(into_app_error)?
+        .into();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+async fn delete(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+) -> Result<(StatusCode, String), AppError> {
+    state
+        .repository
+        .delete_subscription(id)
+        .await
+        .map_err(into_app_error)?;
+
+    Ok((StatusCode::NO_CONTENT, "Object removed".to_owned()))
+}
+
+async fn accept(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(params): Query<RecipientParams>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    check_recipient(&state, id, &params).await?;
+
+    let response = state
+        .repository
+        .accept_subscription(id)
+        .await
+        .map_err(into_app_error)?
+        .into();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn reject(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(params): Query<RecipientParams>,
+) -> Result<(StatusCode, String), AppError> {
+    check_recipient(&state, id, &params).await?;
+
+    state
+        .repository
+        .reject_subscription(id)
+        .await
+        .map_err(into_app_error)?;
+
+    Ok((StatusCode::NO_CONTENT, "Request rejected".to_owned()))
+}
+
+static SUBPATH: &str = "/subscriptions";
+
+pub(crate) fn get_router(root_path: &str, state: State) -> Router {
+    Router::new()
+        .route(
+            &format!("{root_path}{SUBPATH}"),
+            axum::routing::post(create),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id"),
+            axum::routing::delete(delete),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/accept"),
+            axum::routing::post(accept),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/reject"),
+            axum::routing::post(reject),
+        )
+        .with_state(state)
+}
@@ -0,0 +1,80 @@
+use axum::{
+    extract::{Query, State as AxumState},
+    http::StatusCode,
+    Json,
+    Router,
+};
+use database::traits::suggestions::{Error as DatabaseError, Suggestion as DatabaseSuggestion};
+use serde::{Deserialize, Serialize};
+
+use super::users;
+use crate::router::{errors::AppError, state::State};
+
+const DEFAULT_LIMIT: u64 = 10;
+const MAX_LIMIT: u64 = 50;
+
+#[derive(Deserialize)]
+struct ListParams {
+    user_id: users::Id,
+    limit: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct Response {
+    user_id: users::Id,
+    name: String,
+    mutual_count: u64,
+    distance: u32,
+    reason: String,
+}
+
+impl From<DatabaseSuggestion> for Response {
+    fn from(val: DatabaseSuggestion) -> Self {
+        let reason = match val.mutual_count {
+            0 => "Followed by people in your network".to_owned(),
+            1 => "Followed by 1 person you follow".to_owned(),
+            count => format!("Followed by {count} people you follow"),
+        };
+
+        Response {
+            user_id: val.user_id,
+            name: val.name,
+            mutual_count: val.mutual_count,
+            distance: val.distance,
+            reason,
+        }
+    }
+}
+
+fn into_app_error(err: DatabaseError) -> AppError {
+    match err {
+        DatabaseError::UserNotFound => AppError::new(StatusCode::NOT_FOUND, err),
+        DatabaseError::Unknown => err.into(),
+    }
+}
+
+async fn list(
+    AxumState(state): AxumState<State>,
+    Query(params): Query<ListParams>,
+) -> Result<(StatusCode, Json<Vec<Response>>), AppError> {
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let response = state
+        .repository
+        .list_user_suggestions(params.user_id, limit)
+        .await
+        .map_err(into_app_error)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+static SUBPATH: &str = "/suggestions";
+
+pub(crate) fn get_router(root_path: &str, state: State) -> Router {
+    Router::new()
+        .route(&format!("{root_path}{SUBPATH}"), axum::routing::get(list))
+        .with_state(state)
+}
@@ -0,0 +1,261 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, Query, State as AxumState},
+    http::StatusCode,
+    Json,
+    Router,
+};
+use chrono::{NaiveDateTime, Utc};
+use database::traits::{
+    templates::{
+        Error as DatabaseError,
+        ItemPayload as DatabaseItemPayload,
+        ItemResponse as DatabaseItemResponse,
+        Payload as DatabasePayload,
+        Response as DatabaseResponse,
+    },
+    wishlists::Payload as WishlistPayload,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{items, users, wishlists};
+use crate::router::{
+    errors::AppError,
+    money::{Money, Price},
+    state::State,
+};
+
+type Id = Uuid;
+type ItemId = Uuid;
+
+#[derive(Deserialize)]
+struct CreateItemPayload {
+    name: String,
+    description: Option<String>,
+    url: Option<items::ProductUrl>,
+    price: Option<Price>,
+    quantity: Option<i32>,
+    priority: Option<items::Priority>,
+}
+
+#[derive(Deserialize)]
+struct CreatePayload {
+    user_id: users::Id,
+    name: String,
+    description: Option<String>,
+    #[serde(default)]
+    items: Vec<CreateItemPayload>,
+}
+
+#[derive(Deserialize)]
+struct ActorParams {
+    user_id: users::Id,
+}
+
+#[derive(Serialize)]
+struct ItemResponse {
+    id: ItemId,
+    name: String,
+    description: Option<String>,
+    url: Option<String>,
+    price: Option<Money>,
+    quantity: i32,
+    priority: items::Priority,
+    position: i32,
+}
+
+impl From<DatabaseItemResponse> for ItemResponse {
+    fn from(val: DatabaseItemResponse) -> Self {
+        ItemResponse {
+            id: val.id,
+            name: val.name,
+            description: val.description,
+            url: val.url,
+            price: Money::from_parts(val.price, val.currency),
+            quantity: val.quantity,
+            priority: val.priority.into(),
+            position: val.position,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Response {
+    id: Id,
+    name: String,
+    description: Option<String>,
+    items: Vec<ItemResponse>,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+impl From<DatabaseResponse> for Response {
+    fn from(val: DatabaseResponse) -> Self {
+        Response {
+            id: val.id,
+            name: val.name,
+            description: val.description,
+            items: val.items.into_iter().map(Into::into).collect(),
+            created_at: val.created_at,
+            updated_at: val.updated_at,
+        }
+    }
+}
+
+fn into_app_error(err: DatabaseError) -> AppError {
+    match err {
+        DatabaseError::NotFound => AppError::new(StatusCode::NOT_FOUND, err),
+        DatabaseError::NotAdmin => AppError::new(StatusCode::FORBIDDEN, err),
+        DatabaseError::InvalidQuantity => AppError::new(StatusCode::UNPROCESSABLE_ENTITY, err),
+        DatabaseError::Unknown => err.into(),
+    }
+}
+
+async fn list(
+    AxumState(state): AxumState<State>,
+) -> Result<(StatusCode, Json<Vec<Response>>), AppError> {
+    let response = state
+        .repository
+        .list_templates()
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn create(
+    AxumState(state): AxumState<State>,
+    Json(payload): Json<CreatePayload>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let user = state
+        .repository
+        .get_user(payload.user_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("User not found")))?;
+
+    let items = payload
+        .items
+        .into_iter()
+        .map(|item| {
+            let (price, currency) = item.price.map_or((None, None), |price| {
+                (
+                    Some(price.amount.into()),
+                    Some(price.currency.map_or(user.currency.clone(), Into::into)),
+                )
+            });
+
+            DatabaseItemPayload {
+                id: Uuid::new_v4(),
+                name: item.name,
+                description: item.description,
+                url: item.url.map(|x| x.0),
+                price,
+                currency,
+                quantity: item.quantity.unwrap_or(1),
+                priority: item.priority.map(Into::into).unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    let response = state
+        .repository
+        .create_template(
+            payload.user_id,
+            DatabasePayload {
+                id: Uuid::new_v4(),
+                name: payload.name,
+                description: payload.description,
+                items,
+                created_at: Utc::now().naive_utc(),
+                updated_at: Utc::now().naive_utc(),
+            },
+        )
+        .await
+        .map_err(into_app_error)?
+        .into();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+async fn get(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+) -> Result<(StatusCode, Json<Option<Response>>), AppError> {
+    let response = state.repository.get_template(id).await?.map(Into::into);
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn delete(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(params): Query<ActorParams>,
+) -> Result<(StatusCode, String), AppError> {
+    state
+        .repository
+        .delete_template(params.user_id, id)
+        .await
+        .map_err(into_app_error)?;
+
+    Ok((StatusCode::NO_CONTENT, "Object removed".to_owned()))
+}
+
+async fn instantiate(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Json(payload): Json<wishlists::ClonePayload>,
+) -> Result<(StatusCode, Json<wishlists::Response>), AppError> {
+    let template = state
+        .repository
+        .get_template(id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("Template not found")))?;
+
+    state
+        .repository
+        .get_user(payload.user_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("User not found")))?;
+
+    let response = state
+        .repository
+        .instantiate_template(
+            id,
+            WishlistPayload {
+                id: Uuid::new_v4(),
+                name: payload.name.unwrap_or(template.name),
+                user_id: payload.user_id,
+                event_type: None,
+                event_date: None,
+                created_at: Utc::now().naive_utc(),
+                updated_at: Utc::now().naive_utc(),
+            },
+        )
+        .await
+        .map_err(into_app_error)?
+        .into();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+static SUBPATH: &str = "/templates";
+
+pub(crate) fn get_router(root_path: &str, state: State) -> Router {
+    Router::new()
+        .route(
+            &format!("{root_path}{SUBPATH}"),
+            axum::routing::get(list).post(create),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id"),
+            axum::routing::get(get).delete(delete),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/instantiate"),
+            axum::routing::post(instantiate),
+        )
+        .with_state(state)
+}
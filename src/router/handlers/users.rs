@@ -1,24 +1,140 @@
+use anyhow::anyhow;
 use axum::{
     extract::{Path, Query, State as AxumState},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response as AxumResponse},
     Json,
     Router,
 };
-use chrono::{NaiveDateTime, Utc};
-use database::traits::users::{Payload as DatabasePayload, Response as DatabaseResponse};
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use database::traits::{
+    blocks::Kind as BlockKind,
+    users::{
+        Error as DatabaseError,
+        Patch as DatabasePatch,
+        Payload as DatabasePayload,
+        Response as DatabaseResponse,
+    },
+    Precondition,
+};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::wishlists;
-use crate::router::{errors::AppError, state::State};
+use super::{blocks, items, notifications, subscriptions, wishlists};
+use crate::router::{
+    errors::AppError,
+    etag::{self, IfMatch},
+    money::{Currency, DEFAULT_CURRENCY},
+    patch::{nullable, present},
+    state::State,
+};
 
 pub type Id = Uuid;
 type AvatarId = Uuid;
 type Predicate = String;
 
+const USERNAME_LENGTH: std::ops::RangeInclusive<usize> = 3..=32;
+const MAX_BIO_LENGTH: usize = 500;
+
+// Letters, digits, underscores and dots, starting with a letter or a digit
+#[derive(Deserialize)]
+#[serde(try_from = "String")]
+struct Username(String);
+
+impl TryFrom<String> for Username {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let is_valid = USERNAME_LENGTH.contains(&value.len())
+            && value.starts_with(|c: char| c.is_ascii_alphanumeric())
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+
+        if is_valid {
+            Ok(Username(value))
+        } else {
+            Err(format!(
+                "Username '{value}' must be 3 to 32 letters, digits, underscores or dots"
+            ))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct WishlistsParams {
+    predicate: Option<wishlists::Predicate>,
+}
+
+#[derive(Deserialize)]
+#[serde(try_from = "String")]
+struct Bio(String);
+
+impl TryFrom<String> for Bio {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.chars().count() > MAX_BIO_LENGTH {
+            Err(format!("Bio must not exceed {MAX_BIO_LENGTH} characters"))
+        } else {
+            Ok(Bio(value))
+        }
+    }
+}
+
+// BCP 47 language tag such as 'en' or 'pt-BR', underscores are accepted as separators
+#[derive(Deserialize)]
+#[serde(try_from = "String")]
+struct Locale(String);
+
+impl TryFrom<String> for Locale {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let tag = value.replace('_', "-");
+        let mut subtags = tag.split('-');
+
+        let is_valid = subtags.next().is_some_and(|x| {
+            (2..=3).contains(&x.len()) && x.chars().all(|c| c.is_ascii_alphabetic())
+        }) && subtags
+            .all(|x| (1..=8).contains(&x.len()) && x.chars().all(|c| c.is_ascii_alphanumeric()))
+            && tag.len() <= 35;
+
+        if is_valid {
+            Ok(Locale(tag))
+        } else {
+            Err(format!("'{value}' is not a valid locale"))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(try_from = "NaiveDate")]
+struct Birthday(NaiveDate);
+
+impl TryFrom<NaiveDate> for Birthday {
+    type Error = String;
+
+    fn try_from(value: NaiveDate) -> Result<Self, Self::Error> {
+        if value > Utc::now().date_naive() {
+            Err(format!("Birthday '{value}' must not be in the future"))
+        } else {
+            Ok(Birthday(value))
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct CreatePayload {
     name: String,
+    currency: Option<Currency>,
+    email: Option<String>,
+    #[serde(default)]
+    is_private: bool,
+    username: Option<Username>,
+    bio: Option<Bio>,
+    birthday: Option<Birthday>,
+    locale: Option<Locale>,
 }
 
 impl From<CreatePayload> for DatabasePayload {
@@ -27,6 +143,16 @@ impl From<CreatePayload> for DatabasePayload {
             id: Uuid::new_v4(),
             name: val.name,
             avatar_id: None,
+            currency: val
+                .currency
+                .map_or_else(|| DEFAULT_CURRENCY.to_owned(), Into::into),
+            email: val.email,
+            is_admin: false,
+            is_private: val.is_private,
+            username: val.username.map(|x| x.0),
+            bio: val.bio.map(|x| x.0),
+            birthday: val.birthday.map(|x| x.0),
+            locale: val.locale.map(|x| x.0),
             created_at: Utc::now().naive_utc(),
             updated_at: Utc::now().naive_utc(),
         }
@@ -36,23 +162,84 @@ impl From<CreatePayload> for DatabasePayload {
 #[derive(Deserialize)]
 struct UpdatePayload {
     name: String,
+    currency: Option<Currency>,
+    email: Option<String>,
+    is_private: Option<bool>,
+    username: Option<Username>,
+    bio: Option<Bio>,
+    birthday: Option<Birthday>,
+    locale: Option<Locale>,
+}
+
+#[allow(clippy::option_option)]
+#[derive(Deserialize)]
+struct PatchPayload {
+    #[serde(default, deserialize_with = "present")]
+    name: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    currency: Option<Currency>,
+    #[serde(default, deserialize_with = "nullable")]
+    email: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    is_private: Option<bool>,
+    #[serde(default, deserialize_with = "nullable")]
+    username: Option<Option<Username>>,
+    #[serde(default, deserialize_with = "nullable")]
+    bio: Option<Option<Bio>>,
+    #[serde(default, deserialize_with = "nullable")]
+    birthday: Option<Option<Birthday>>,
+    #[serde(default, deserialize_with = "nullable")]
+    locale: Option<Option<Locale>>,
+}
+
+impl From<PatchPayload> for DatabasePatch {
+    fn from(val: PatchPayload) -> Self {
+        DatabasePatch {
+            name: val.name,
+            currency: val.currency.map(Into::into),
+            email: val.email,
+            is_private: val.is_private,
+            username: val.username.map(|x| x.map(|x| x.0)),
+            bio: val.bio.map(|x| x.map(|x| x.0)),
+            birthday: val.birthday.map(|x| x.map(|x| x.0)),
+            locale: val.locale.map(|x| x.map(|x| x.0)),
+        }
+    }
 }
 
 #[derive(Serialize)]
-struct Response {
+pub(crate) struct Response {
     id: Uuid,
     name: String,
     avatar_id: Option<AvatarId>,
+    currency: String,
+    is_private: bool,
+    username: Option<String>,
+    bio: Option<String>,
+    birthday: Option<NaiveDate>,
+    locale: Option<String>,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
 }
 
+#[derive(Serialize)]
+struct AvailabilityResponse {
+    username: String,
+    is_available: bool,
+}
+
 impl From<DatabaseResponse> for Response {
     fn from(val: DatabaseResponse) -> Self {
         Response {
             id: val.id,
             name: val.name,
             avatar_id: val.avatar_id,
+            currency: val.currency,
+            is_private: val.is_private,
+            username: val.username,
+            bio: val.bio,
+            birthday: val.birthday,
+            locale: val.locale,
             created_at: val.created_at,
             updated_at: val.updated_at,
         }
@@ -63,7 +250,12 @@ async fn create(
     AxumState(state): AxumState<State>,
     Json(payload): Json<CreatePayload>,
 ) -> Result<(StatusCode, Json<Response>), AppError> {
-    let response = state.repository.create_user(payload.into()).await?.into();
+    let response = state
+        .repository
+        .create_user(payload.into())
+        .await
+        .map_err(into_app_error)?
+        .into();
 
     Ok((StatusCode::CREATED, Json(response)))
 }
@@ -86,8 +278,43 @@ async fn list(
 async fn get(
     AxumState(state): AxumState<State>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<AxumResponse, AppError> {
+    match state.repository.get_user(id).await? {
+        Some(user) => Ok(etag::read(&headers, user.version, Response::from(user))),
+        None => Ok((StatusCode::OK, Json(None::<Response>)).into_response()),
+    }
+}
+
+async fn get_by_username(
+    AxumState(state): AxumState<State>,
+    Path(username): Path<String>,
 ) -> Result<(StatusCode, Json<Option<Response>>), AppError> {
-    let response = state.repository.get_user(id).await?.map(Into::into);
+    let response = state
+        .repository
+        .get_user_by_username(username)
+        .await?
+        .map(Into::into);
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn check_username(
+    AxumState(state): AxumState<State>,
+    Path(username): Path<String>,
+) -> Result<(StatusCode, Json<AvailabilityResponse>), AppError> {
+    let username = Username::try_from(username)
+        .map_err(|err| AppError::new(StatusCode::BAD_REQUEST, anyhow!(err)))?;
+
+    let is_available = state
+        .repository
+        .is_username_available(username.0.clone())
+        .await?;
+
+    let response = AvailabilityResponse {
+        username: username.0,
+        is_available,
+    };
 
     Ok((StatusCode::OK, Json(response)))
 }
@@ -95,40 +322,129 @@ async fn get(
 async fn update(
     AxumState(state): AxumState<State>,
     Path(id): Path<Uuid>,
+    IfMatch(version): IfMatch,
     Json(payload): Json<UpdatePayload>,
-) -> Result<(StatusCode, Json<Response>), AppError> {
-    match state.repository.get_user(id).await? {
-        Some(object) => {
-            let response = state
-                .repository
-                .update_user(
-                    id,
-                    DatabasePayload {
+) -> Result<AxumResponse, AppError> {
+    let response = state
+        .repository
+        .unit_of_work(move |repository| {
+            Box::pin(async move {
+                let object = repository
+                    .get_user(id)
+                    .await?
+                    .ok_or_else(|| etag::not_found(version, "User not found"))?;
+
+                repository
+                    .update_user(
                         id,
-                        name: payload.name,
-                        avatar_id: object.avatar_id,
-                        created_at: object.created_at,
-                        updated_at: Utc::now().naive_utc(),
-                    },
-                )
-                .await?
-                .into();
-
-            Ok((StatusCode::OK, Json(response)))
-        }
-        None => todo!(),
-    }
+                        DatabasePayload {
+                            id,
+                            name: payload.name,
+                            avatar_id: object.avatar_id,
+                            currency: payload.currency.map_or(object.currency, Into::into),
+                            email: payload.email.or(object.email),
+                            is_admin: object.is_admin,
+                            is_private: payload.is_private.unwrap_or(object.is_private),
+                            username: payload.username.map(|x| x.0).or(object.username),
+                            bio: payload.bio.map(|x| x.0).or(object.bio),
+                            birthday: payload.birthday.map(|x| x.0).or(object.birthday),
+                            locale: payload.locale.map(|x| x.0).or(object.locale),
+                            created_at: object.created_at,
+                            updated_at: Utc::now().naive_utc(),
+                        },
+                        etag::read_version(version, object.version),
+                    )
+                    .await
+                    .map_err(into_app_error)
+            })
+        })
+        .await?;
+
+    Ok(etag::tagged(
+        StatusCode::OK,
+        response.version,
+        Response::from(response),
+    ))
+}
+
+async fn patch(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+    IfMatch(version): IfMatch,
+    Json(payload): Json<PatchPayload>,
+) -> Result<AxumResponse, AppError> {
+    let response = state
+        .repository
+        .patch_user(id, payload.into(), version)
+        .await
+        .map_err(into_app_error)?;
+
+    Ok(etag::tagged(
+        StatusCode::OK,
+        response.version,
+        Response::from(response),
+    ))
 }
 
 async fn delete(
     AxumState(state): AxumState<State>,
     Path(id): Path<Uuid>,
+    IfMatch(version): IfMatch,
 ) -> Result<(StatusCode, String), AppError> {
-    state.repository.delete_user(id).await?;
+    state
+        .repository
+        .delete_user(id, version)
+        .await
+        .map_err(into_app_error)?;
 
     Ok((StatusCode::NO_CONTENT, "Object removed".to_owned()))
 }
 
+// Erasing can't be undone, so it has to name the version of the account
+async fn erase(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+    IfMatch(precondition): IfMatch,
+) -> Result<(StatusCode, String), AppError> {
+    let Some(Precondition::Version(version)) = precondition else {
+        return Err(AppError::new(
+            StatusCode::PRECONDITION_REQUIRED,
+            anyhow!("Erasing an account requires If-Match with its entity tag"),
+        ));
+    };
+
+    state
+        .repository
+        .erase_user(id, version)
+        .await
+        .map_err(into_app_error)?;
+
+    Ok((StatusCode::NO_CONTENT, "Account erased".to_owned()))
+}
+
+fn into_app_error(err: DatabaseError) -> AppError {
+    match err {
+        DatabaseError::NotFound => AppError::new(StatusCode::NOT_FOUND, err),
+        DatabaseError::UsernameTaken => AppError::new(StatusCode::CONFLICT, err),
+        DatabaseError::VersionMismatch => AppError::new(StatusCode::PRECONDITION_FAILED, err),
+        DatabaseError::Unknown => err.into(),
+    }
+}
+
+async fn restore(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let response = state
+        .repository
+        .restore_user(id)
+        .await
+        .map_err(into_app_error)?
+        .into();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
 async fn list_subscribers(
     AxumState(state): AxumState<State>,
     Path(id): Path<Uuid>,
@@ -161,14 +477,78 @@ async fn list_subscriptions(
     Ok((StatusCode::OK, Json(response)))
 }
 
+async fn list_subscriber_requests(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Vec<subscriptions::Response>>), AppError> {
+    let response = state
+        .repository
+        .list_incoming_subscription_requests(id)
+        .await
+        .map_err(subscriptions::into_app_error)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn list_subscription_requests(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Vec<subscriptions::Response>>), AppError> {
+    let response = state
+        .repository
+        .list_outgoing_subscription_requests(id)
+        .await
+        .map_err(subscriptions::into_app_error)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn list_blocks(
+    state: &State,
+    id: Uuid,
+    kind: BlockKind,
+) -> Result<(StatusCode, Json<Vec<blocks::Response>>), AppError> {
+    let response = state
+        .repository
+        .list_user_blocks(id, kind)
+        .await
+        .map_err(blocks::into_app_error)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn list_blocked(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Vec<blocks::Response>>), AppError> {
+    list_blocks(&state, id, BlockKind::Block).await
+}
+
+async fn list_muted(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Vec<blocks::Response>>), AppError> {
+    list_blocks(&state, id, BlockKind::Mute).await
+}
+
 async fn list_wishlists(
     AxumState(state): AxumState<State>,
     Path(id): Path<Uuid>,
-    Query(predicate): Query<Option<wishlists::Predicate>>,
+    Query(params): Query<WishlistsParams>,
+    Query(viewer): Query<items::ViewerParams>,
 ) -> Result<(StatusCode, Json<Vec<wishlists::Response>>), AppError> {
     let response = state
         .repository
-        .list_user_wishlists(id, predicate)
+        .list_user_wishlists(id, viewer.user_id, params.predicate)
         .await?
         .into_iter()
         .map(Into::into)
@@ -177,6 +557,34 @@ async fn list_wishlists(
     Ok((StatusCode::OK, Json(response)))
 }
 
+async fn list_notifications(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<notifications::ListParams>,
+) -> Result<(StatusCode, Json<Vec<notifications::Response>>), AppError> {
+    let response = state
+        .repository
+        .list_user_notifications(id, params.into())
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn read_notifications(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, String), AppError> {
+    state.repository.mark_user_notifications(id).await?;
+
+    Ok((
+        StatusCode::NO_CONTENT,
+        "Notifications marked as read".to_owned(),
+    ))
+}
+
 static SUBPATH: &str = "/users";
 
 pub(crate) fn get_router(root_path: &str, state: State) -> Router {
@@ -187,7 +595,62 @@ pub(crate) fn get_router(root_path: &str, state: State) -> Router {
         )
         .route(
             &format!("{root_path}{SUBPATH}/:id"),
-            axum::routing::get(get).put(update).delete(delete),
+            axum::routing::get(get)
+                .put(update)
+                .patch(patch)
+                .delete(delete),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/by-username/:username"),
+            axum::routing::get(get_by_username),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/by-username/:username/availability"),
+            axum::routing::get(check_username),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/account"),
+            axum::routing::delete(erase),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/restore"),
+            axum::routing::post(restore),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/subscribers"),
+            axum::routing::get(list_subscribers),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/subscriptions"),
+            axum::routing::get(list_subscriptions),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/subscribers/pending"),
+            axum::routing::get(list_subscriber_requests),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/subscriptions/pending"),
+            axum::routing::get(list_subscription_requests),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/wishlists"),
+            axum::routing::get(list_wishlists),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/blocks"),
+            axum::routing::get(list_blocked),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/mutes"),
+            axum::routing::get(list_muted),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/notifications"),
+            axum::routing::get(list_notifications),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/notifications/read"),
+            axum::routing::post(read_notifications),
         )
         .with_state(state)
 }
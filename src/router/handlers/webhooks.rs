@@ -0,0 +1,280 @@
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, Query, State as AxumState},
+    http::StatusCode,
+    Json,
+    Router,
+};
+use chrono::NaiveDateTime;
+use database::traits::{
+    audit::Json as JsonValue,
+    webhooks::{
+        DeliveryResponse as DatabaseDeliveryResponse,
+        Error as DatabaseError,
+        Payload as DatabasePayload,
+        Response as DatabaseResponse,
+        Status as DatabaseStatus,
+    },
+};
+use rand::RngCore;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{users, wishlists};
+use crate::{
+    outbound,
+    router::{errors::AppError, state::State},
+};
+
+type Id = Uuid;
+
+#[derive(Deserialize)]
+#[serde(try_from = "String")]
+struct WebhookUrl(Url);
+
+// What the host resolves to is checked when the webhook is created
+impl TryFrom<String> for WebhookUrl {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match Url::parse(&value) {
+            Ok(url) if value.len() <= 2048 && outbound::check_url(&url).is_ok() => {
+                Ok(WebhookUrl(url))
+            }
+            _ => Err(format!("'{value}' is not a valid webhook URL")),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CreatePayload {
+    user_id: users::Id,
+    wishlist_id: wishlists::Id,
+    url: WebhookUrl,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct OwnerParams {
+    pub(crate) user_id: users::Id,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Response {
+    id: Id,
+    wishlist_id: wishlists::Id,
+    url: String,
+    // The secret is only revealed once, when the webhook is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl From<DatabaseResponse> for Response {
+    fn from(val: DatabaseResponse) -> Self {
+        Response {
+            id: val.id,
+            wishlist_id: val.wishlist_id,
+            url: val.url,
+            secret: None,
+            created_at: val.created_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl From<DatabaseStatus> for Status {
+    fn from(val: DatabaseStatus) -> Self {
+        match val {
+            DatabaseStatus::Pending => Status::Pending,
+            DatabaseStatus::Delivered => Status::Delivered,
+            DatabaseStatus::Failed => Status::Failed,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DeliveryResponse {
+    id: Uuid,
+    event: String,
+    payload: JsonValue,
+    status: Status,
+    attempts: i32,
+    next_attempt_at: Option<NaiveDateTime>,
+    last_status_code: Option<i16>,
+    last_error: Option<String>,
+    delivered_at: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+}
+
+impl From<DatabaseDeliveryResponse> for DeliveryResponse {
+    fn from(val: DatabaseDeliveryResponse) -> Self {
+        DeliveryResponse {
+            id: val.id,
+            event: val.event,
+            payload: val.payload,
+            next_attempt_at: (val.status == DatabaseStatus::Pending).then_some(val.next_attempt_at),
+            status: val.status.into(),
+            attempts: val.attempts,
+            last_status_code: val.last_status_code,
+            last_error: val.last_error,
+            delivered_at: val.delivered_at,
+            created_at: val.created_at,
+        }
+    }
+}
+
+pub(crate) fn into_app_error(err: DatabaseError) -> AppError {
+    match err {
+        DatabaseError::NotFound
+        | DatabaseError::WishlistNotFound
+        | DatabaseError::DeliveryNotFound => AppError::new(StatusCode::NOT_FOUND, err),
+        DatabaseError::Unknown => err.into(),
+    }
+}
+
+fn generate_secret() -> String {
+    let mut secret = [0_u8; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+
+    hex::encode(secret)
+}
+
+pub(crate) async fn check_wishlist_owner(
+    state: &State,
+    wishlist_id: wishlists::Id,
+    user_id: users::Id,
+) -> Result<(), AppError> {
+    let wishlist = state
+        .repository
+        .get_wishlist(wishlist_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("Wishlist not found")))?;
+
+    if wishlist.user_id != user_id {
+        return Err(AppError::new(
+            StatusCode::FORBIDDEN,
+            anyhow!("Only the owner can manage the webhooks of a wishlist"),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn get_owned(
+    state: &State,
+    id: Id,
+    user_id: users::Id,
+) -> Result<DatabaseResponse, AppError> {
+    let webhook = state
+        .repository
+        .get_webhook(id)
+        .await
+        .map_err(into_app_error)?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("Webhook not found")))?;
+
+    check_wishlist_owner(state, webhook.wishlist_id, user_id).await?;
+
+    Ok(webhook)
+}
+
+async fn create(
+    AxumState(state): AxumState<State>,
+    Json(payload): Json<CreatePayload>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    check_wishlist_owner(&state, payload.wishlist_id, payload.user_id).await?;
+
+    outbound::check_public(&payload.url.0)
+        .await
+        .map_err(|err| AppError::new(StatusCode::UNPROCESSABLE_ENTITY, err))?;
+
+    let webhook = state
+        .repository
+        .create_webhook(DatabasePayload {
+            id: Uuid::new_v4(),
+            wishlist_id: payload.wishlist_id,
+            url: payload.url.0.into(),
+            secret: generate_secret(),
+        })
+        .await
+        .map_err(into_app_error)?;
+
+    let secret = webhook.secret.clone();
+    let response = Response {
+        secret: Some(secret),
+        ..webhook.into()
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+async fn get(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(params): Query<OwnerParams>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let response = get_owned(&state, id, params.user_id).await?.into();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn delete(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(params): Query<OwnerParams>,
+) -> Result<(StatusCode, String), AppError> {
+    get_owned(&state, id, params.user_id).await?;
+
+    state
+        .repository
+        .delete_webhook(id)
+        .await
+        .map_err(into_app_error)?;
+
+    Ok((StatusCode::NO_CONTENT, "Object removed".to_owned()))
+}
+
+async fn list_deliveries(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Id>,
+    Query(params): Query<OwnerParams>,
+) -> Result<(StatusCode, Json<Vec<DeliveryResponse>>), AppError> {
+    get_owned(&state, id, params.user_id).await?;
+
+    let response = state
+        .repository
+        .list_webhook_deliveries(id)
+        .await
+        .map_err(into_app_error)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+static SUBPATH: &str = "/webhooks";
+
+pub(crate) fn get_router(root_path: &str, state: State) -> Router {
+    Router::new()
+        .route(
+            &format!("{root_path}{SUBPATH}"),
+            axum::routing::post(create),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id"),
+            axum::routing::get(get).delete(delete),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/deliveries"),
+            axum::routing::get(list_deliveries),
+        )
+        .with_state(state)
+}
@@ -1,24 +1,115 @@
+use std::{convert::Infallible, str::FromStr};
+
+use anyhow::anyhow;
 use axum::{
+    body::Bytes,
     extract::{Path, Query, State as AxumState},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event as SseEvent, KeepAlive, Sse},
+        IntoResponse,
+        Response as AxumResponse,
+    },
     Json,
     Router,
 };
-use chrono::{NaiveDateTime, Utc};
-use database::traits::wishlists::{Payload as DatabasePayload, Response as DatabaseResponse};
-use serde::{Deserialize, Serialize};
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use database::traits::{
+    audit::{Json as JsonValue, Response as DatabaseHistoryResponse},
+    events::{Event as DatabaseEvent, Kind as DatabaseEventKind},
+    wishlists::{
+        Error as DatabaseError,
+        Filter as DatabaseFilter,
+        Patch as DatabasePatch,
+        Payload as DatabasePayload,
+        Response as DatabaseResponse,
+    },
+};
+use futures::{stream, Stream};
+use serde::{
+    de::{value::Error as DeError, IntoDeserializer},
+    Deserialize,
+    Serialize,
+};
+use tokio::sync::broadcast::error::RecvError;
 use uuid::Uuid;
 
-use super::items;
-use crate::router::{errors::AppError, state::State};
+use super::{items, users, webhooks};
+use crate::{
+    router::{
+        errors::AppError,
+        etag::{self, IfMatch},
+        idempotency::{self, Idempotent, Outcome},
+        patch::{nullable, present},
+        state::State,
+    },
+    transfer::{self, Error as TransferError, Format},
+};
 
 pub type Id = Uuid;
 pub(crate) type Predicate = String;
 
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum EventType {
+    Birthday,
+    Wedding,
+    Anniversary,
+    BabyShower,
+    Graduation,
+    Housewarming,
+    Holiday,
+    Other,
+}
+
+impl From<EventType> for String {
+    fn from(val: EventType) -> Self {
+        match val {
+            EventType::Birthday => "birthday",
+            EventType::Wedding => "wedding",
+            EventType::Anniversary => "anniversary",
+            EventType::BabyShower => "baby_shower",
+            EventType::Graduation => "graduation",
+            EventType::Housewarming => "housewarming",
+            EventType::Holiday => "holiday",
+            EventType::Other => "other",
+        }
+        .to_owned()
+    }
+}
+
+impl FromStr for EventType {
+    type Err = DeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::deserialize(value.into_deserializer())
+    }
+}
+
+#[derive(Deserialize)]
+struct ListParams {
+    predicate: Option<Predicate>,
+    event_type: Option<EventType>,
+    #[serde(default)]
+    upcoming: bool,
+}
+
+impl From<ListParams> for DatabaseFilter {
+    fn from(val: ListParams) -> Self {
+        DatabaseFilter {
+            predicate: val.predicate,
+            event_type: val.event_type.map(Into::into),
+            upcoming: val.upcoming,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct CreatePayload {
     name: String,
     user_id: Uuid,
+    event_type: Option<EventType>,
+    event_date: Option<NaiveDate>,
 }
 
 impl From<CreatePayload> for DatabasePayload {
@@ -27,6 +118,8 @@ impl From<CreatePayload> for DatabasePayload {
             id: Uuid::new_v4(),
             name: val.name,
             user_id: val.user_id,
+            event_type: val.event_type.map(Into::into),
+            event_date: val.event_date,
             created_at: Utc::now().naive_utc(),
             updated_at: Utc::now().naive_utc(),
         }
@@ -36,6 +129,40 @@ impl From<CreatePayload> for DatabasePayload {
 #[derive(Deserialize)]
 struct UpdatePayload {
     name: String,
+    event_type: Option<EventType>,
+    event_date: Option<NaiveDate>,
+}
+
+#[allow(clippy::option_option)]
+#[derive(Deserialize)]
+struct PatchPayload {
+    #[serde(default, deserialize_with = "present")]
+    name: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    event_type: Option<Option<EventType>>,
+    #[serde(default, deserialize_with = "nullable")]
+    event_date: Option<Option<NaiveDate>>,
+}
+
+impl From<PatchPayload> for DatabasePatch {
+    fn from(val: PatchPayload) -> Self {
+        DatabasePatch {
+            name: val.name,
+            event_type: val.event_type.map(|x| x.map(Into::into)),
+            event_date: val.event_date,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ClonePayload {
+    pub(crate) user_id: users::Id,
+    pub(crate) name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OwnerParams {
+    user_id: users::Id,
 }
 
 #[derive(Serialize)]
@@ -43,6 +170,9 @@ pub(crate) struct Response {
     id: Uuid,
     name: String,
     user_id: Uuid,
+    event_type: Option<String>,
+    event_date: Option<NaiveDate>,
+    is_archived: bool,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
 }
@@ -53,19 +183,50 @@ impl From<DatabaseResponse> for Response {
             id: val.id,
             name: val.name,
             user_id: val.user_id,
+            event_type: val.event_type,
+            event_date: val.event_date,
+            is_archived: val.is_archived,
             created_at: val.created_at,
             updated_at: val.updated_at,
         }
     }
 }
 
+#[derive(Serialize)]
+struct HistoryResponse {
+    id: Uuid,
+    actor_id: Option<Uuid>,
+    entity_type: String,
+    entity_id: Uuid,
+    action: String,
+    before: Option<JsonValue>,
+    after: Option<JsonValue>,
+    created_at: NaiveDateTime,
+}
+
+impl From<DatabaseHistoryResponse> for HistoryResponse {
+    fn from(val: DatabaseHistoryResponse) -> Self {
+        HistoryResponse {
+            id: val.id,
+            actor_id: val.actor_id,
+            entity_type: val.entity_type,
+            entity_id: val.entity_id,
+            action: val.action,
+            before: val.before,
+            after: val.after,
+            created_at: val.created_at,
+        }
+    }
+}
+
 async fn list(
     AxumState(state): AxumState<State>,
-    Query(predicate): Query<Option<Predicate>>,
+    Query(params): Query<ListParams>,
+    Query(viewer): Query<items::ViewerParams>,
 ) -> Result<(StatusCode, Json<Vec<Response>>), AppError> {
     let response = state
         .repository
-        .list_wishlists(predicate)
+        .list_wishlists(viewer.user_id, params.into())
         .await?
         .into_iter()
         .map(Into::into)
@@ -76,79 +237,424 @@ async fn list(
 
 async fn create(
     AxumState(state): AxumState<State>,
-    Json(payload): Json<CreatePayload>,
-) -> Result<(StatusCode, Json<Response>), AppError> {
-    let response = state
-        .repository
-        .create_wishlist(payload.into())
-        .await?
-        .into();
+    Idempotent { key, payload }: Idempotent<CreatePayload>,
+) -> Result<AxumResponse, AppError> {
+    let outcome = idempotency::run(&state, SUBPATH, key, move |repository| {
+        Box::pin(async move {
+            let response = repository.create_wishlist(payload.into()).await?;
 
-    Ok((StatusCode::CREATED, Json(response)))
+            Ok((StatusCode::CREATED, Response::from(response)))
+        })
+    })
+    .await?;
+
+    match outcome {
+        Outcome::Done(status, response) => Ok((status, Json(response)).into_response()),
+        Outcome::Replayed(response) => Ok(response),
+    }
 }
 
 async fn get(
     AxumState(state): AxumState<State>,
     Path(id): Path<Uuid>,
-) -> Result<(StatusCode, Json<Option<Response>>), AppError> {
-    let response = state.repository.get_wishlist(id).await?.map(Into::into);
-
-    Ok((StatusCode::OK, Json(response)))
+    Query(viewer): Query<items::ViewerParams>,
+    headers: HeaderMap,
+) -> Result<AxumResponse, AppError> {
+    match state
+        .repository
+        .get_visible_wishlist(id, viewer.user_id)
+        .await?
+    {
+        Some(wishlist) => Ok(etag::read(
+            &headers,
+            wishlist.version,
+            Response::from(wishlist),
+        )),
+        None => Ok((StatusCode::OK, Json(None::<Response>)).into_response()),
+    }
 }
 
 async fn update(
     AxumState(state): AxumState<State>,
     Path(id): Path<Uuid>,
+    Query(actor): Query<items::ActorParams>,
+    IfMatch(version): IfMatch,
     Json(payload): Json<UpdatePayload>,
-) -> Result<(StatusCode, Json<Response>), AppError> {
-    match state.repository.get_wishlist(id).await? {
-        Some(object) => {
-            let response = state
-                .repository
-                .update_wishlist(
-                    id,
-                    DatabasePayload {
+) -> Result<AxumResponse, AppError> {
+    let response = state
+        .repository
+        .unit_of_work(move |repository| {
+            Box::pin(async move {
+                let object = repository
+                    .get_wishlist(id)
+                    .await?
+                    .ok_or_else(|| etag::not_found(version, "Wishlist not found"))?;
+
+                repository
+                    .update_wishlist(
                         id,
-                        name: payload.name,
-                        user_id: object.user_id,
-                        created_at: object.created_at,
-                        updated_at: Utc::now().naive_utc(),
-                    },
-                )
-                .await?
-                .into();
-
-            Ok((StatusCode::OK, Json(response)))
-        }
-        None => todo!(),
-    }
+                        DatabasePayload {
+                            id,
+                            name: payload.name,
+                            user_id: object.user_id,
+                            event_type: payload.event_type.map(Into::into),
+                            event_date: payload.event_date,
+                            created_at: object.created_at,
+                            updated_at: Utc::now().naive_utc(),
+                        },
+                        etag::read_version(version, object.version),
+                        actor.user_id,
+                    )
+                    .await
+                    .map_err(into_app_error)
+            })
+        })
+        .await?;
+
+    Ok(etag::tagged(
+        StatusCode::OK,
+        response.version,
+        Response::from(response),
+    ))
+}
+
+async fn patch(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+    Query(actor): Query<items::ActorParams>,
+    IfMatch(version): IfMatch,
+    Json(payload): Json<PatchPayload>,
+) -> Result<AxumResponse, AppError> {
+    let response = state
+        .repository
+        .patch_wishlist(id, payload.into(), version, actor.user_id)
+        .await
+        .map_err(into_app_error)?;
+
+    Ok(etag::tagged(
+        StatusCode::OK,
+        response.version,
+        Response::from(response),
+    ))
 }
 
 async fn delete(
     AxumState(state): AxumState<State>,
     Path(id): Path<Uuid>,
+    Query(actor): Query<items::ActorParams>,
+    IfMatch(version): IfMatch,
 ) -> Result<(StatusCode, String), AppError> {
-    state.repository.delete_wishlist(id).await?;
+    state
+        .repository
+        .delete_wishlist(id, version, actor.user_id)
+        .await
+        .map_err(into_app_error)?;
 
     Ok((StatusCode::NO_CONTENT, "Object removed".to_owned()))
 }
 
+fn into_app_error(err: DatabaseError) -> AppError {
+    match err {
+        DatabaseError::NotFound | DatabaseError::UserNotFound => {
+            AppError::new(StatusCode::NOT_FOUND, err)
+        }
+        DatabaseError::VersionMismatch => AppError::new(StatusCode::PRECONDITION_FAILED, err),
+        DatabaseError::Archived => AppError::new(StatusCode::CONFLICT, err),
+        DatabaseError::Unknown => err.into(),
+    }
+}
+
+async fn restore(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+    Query(actor): Query<items::ActorParams>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let response = state
+        .repository
+        .restore_wishlist(id, actor.user_id)
+        .await
+        .map_err(into_app_error)?
+        .into();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn clone(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ClonePayload>,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let source = state
+        .repository
+        .get_wishlist(id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("Wishlist not found")))?;
+
+    state
+        .repository
+        .get_user(payload.user_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("User not found")))?;
+
+    let response = state
+        .repository
+        .clone_wishlist(
+            id,
+            DatabasePayload {
+                id: Uuid::new_v4(),
+                name: payload.name.unwrap_or(source.name),
+                user_id: payload.user_id,
+                event_type: None,
+                event_date: None,
+                created_at: Utc::now().naive_utc(),
+                updated_at: Utc::now().naive_utc(),
+            },
+        )
+        .await
+        .map_err(into_app_error)?
+        .into();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
 async fn list_items(
     AxumState(state): AxumState<State>,
     Path(id): Path<Uuid>,
-    Query(predicate): Query<Option<items::Predicate>>,
+    Query(params): Query<items::ListParams>,
+    Query(viewer): Query<items::ViewerParams>,
 ) -> Result<(StatusCode, Json<Vec<items::Response>>), AppError> {
+    let wishlist = state
+        .repository
+        .get_visible_wishlist(id, viewer.user_id)
+        .await?;
+    let conceal = wishlist.map_or(true, |wishlist| viewer.conceals_reservations(&wishlist));
+
     let response = state
         .repository
-        .list_wishlist_items(id, predicate)
+        .list_wishlist_items(id, viewer.user_id, params.try_into()?)
         .await?
         .into_iter()
+        .map(|item| {
+            let item = items::Response::from(item);
+            if conceal {
+                item.conceal_reservations()
+            } else {
+                item
+            }
+        })
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn history(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<OwnerParams>,
+) -> Result<(StatusCode, Json<Vec<HistoryResponse>>), AppError> {
+    let wishlist = state
+        .repository
+        .get_wishlist(id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("Wishlist not found")))?;
+
+    if wishlist.user_id != params.user_id {
+        return Err(AppError::new(
+            StatusCode::FORBIDDEN,
+            anyhow!("Only the owner can view the history of a wishlist"),
+        ));
+    }
+
+    let conceal = items::ViewerParams {
+        user_id: params.user_id,
+    }
+    .conceals_reservations(&wishlist);
+
+    let response = state
+        .repository
+        .list_wishlist_history(id)
+        .await?
+        .into_iter()
+        .filter(|entry| !(conceal && entry.entity_type == "reservations"))
+        .map(Into::into)
+        .collect();
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+#[derive(Serialize)]
+struct EventData {
+    item_id: items::Id,
+    wishlist_id: Id,
+}
+
+fn sse_event(event: &DatabaseEvent) -> SseEvent {
+    let name = match event.kind {
+        DatabaseEventKind::Created => "item_created",
+        DatabaseEventKind::Updated => "item_updated",
+        DatabaseEventKind::Deleted => "item_deleted",
+        DatabaseEventKind::Reserved => "item_reserved",
+        DatabaseEventKind::Unreserved => "item_unreserved",
+    };
+
+    SseEvent::default()
+        .event(name)
+        .json_data(EventData {
+            item_id: event.item_id,
+            wishlist_id: event.wishlist_id,
+        })
+        .unwrap_or_default()
+}
+
+// Clients refetch the items on every event, lagging behind is reported the same way
+async fn events(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+    Query(viewer): Query<items::ViewerParams>,
+) -> Result<Sse<impl Stream<Item = Result<SseEvent, Infallible>>>, AppError> {
+    let wishlist = state
+        .repository
+        .get_visible_wishlist(id, viewer.user_id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("Wishlist not found")))?;
+
+    let is_owner = viewer.user_id == wishlist.user_id;
+    let conceal = viewer.conceals_reservations(&wishlist);
+
+    let receiver = state.events.subscribe();
+    let stream = stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            let event = match receiver.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => {
+                    return Some((Ok(SseEvent::default().event("resync").data("")), receiver))
+                }
+                Err(RecvError::Closed) => return None,
+            };
+
+            let is_reservation = matches!(
+                event.kind,
+                DatabaseEventKind::Reserved | DatabaseEventKind::Unreserved
+            );
+
+            if event.wishlist_id == id
+                && (is_owner || !event.is_hidden)
+                && !(conceal && is_reservation)
+            {
+                return Some((Ok(sse_event(&event)), receiver));
+            }
+        }
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+async fn list_webhooks(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<webhooks::OwnerParams>,
+) -> Result<(StatusCode, Json<Vec<webhooks::Response>>), AppError> {
+    webhooks::check_wishlist_owner(&state, id, params.user_id).await?;
+
+    let response = state
+        .repository
+        .list_wishlist_webhooks(id)
+        .await
+        .map_err(webhooks::into_app_error)?
+        .into_iter()
         .map(Into::into)
         .collect();
 
     Ok((StatusCode::OK, Json(response)))
 }
 
+#[derive(Deserialize)]
+struct ImportParams {
+    user_id: users::Id,
+    #[serde(default)]
+    format: Format,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ExportParams {
+    user_id: users::Id,
+    #[serde(default)]
+    format: Format,
+}
+
+fn transfer_app_error(err: TransferError) -> AppError {
+    match err {
+        TransferError::Malformed(_) => AppError::new(StatusCode::BAD_REQUEST, err),
+        TransferError::Invalid(_) => AppError::new(StatusCode::UNPROCESSABLE_ENTITY, err),
+        TransferError::UserNotFound | TransferError::WishlistNotFound => {
+            AppError::new(StatusCode::NOT_FOUND, err)
+        }
+        TransferError::Unknown => err.into(),
+    }
+}
+
+async fn import(
+    AxumState(state): AxumState<State>,
+    Query(params): Query<ImportParams>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<Response>), AppError> {
+    let response = transfer::import(
+        state.repository.as_ref(),
+        params.user_id,
+        params.format,
+        params.name,
+        &body,
+    )
+    .await
+    .map_err(transfer_app_error)?
+    .into();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+async fn export(
+    AxumState(state): AxumState<State>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ExportParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let wishlist = state
+        .repository
+        .get_wishlist(id)
+        .await?
+        .ok_or_else(|| AppError::new(StatusCode::NOT_FOUND, anyhow!("Wishlist not found")))?;
+
+    // Exports include hidden items
+    if wishlist.user_id != params.user_id {
+        return Err(AppError::new(
+            StatusCode::FORBIDDEN,
+            anyhow!("Only the owner can export a wishlist"),
+        ));
+    }
+
+    let content = transfer::export(state.repository.as_ref(), wishlist, params.format)
+        .await
+        .map_err(transfer_app_error)?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (
+                header::CONTENT_TYPE,
+                params.format.content_type().to_owned(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"wishlist-{id}.{}\"",
+                    params.format.extension()
+                ),
+            ),
+        ],
+        content,
+    ))
+}
+
 static SUBPATH: &str = "/wishlists";
 
 pub(crate) fn get_router(root_path: &str, state: State) -> Router {
@@ -159,7 +665,42 @@ pub(crate) fn get_router(root_path: &str, state: State) -> Router {
         )
         .route(
             &format!("{root_path}{SUBPATH}/:id"),
-            axum::routing::get(get).put(update).delete(delete),
+            axum::routing::get(get)
+                .put(update)
+                .patch(patch)
+                .delete(delete),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/items"),
+            axum::routing::get(list_items),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/import"),
+            axum::routing::post(import),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/export"),
+            axum::routing::get(export),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/restore"),
+            axum::routing::post(restore),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/clone"),
+            axum::routing::post(clone),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/history"),
+            axum::routing::get(history),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/events"),
+            axum::routing::get(events),
+        )
+        .route(
+            &format!("{root_path}{SUBPATH}/:id/webhooks"),
+            axum::routing::get(list_webhooks),
         )
         .with_state(state)
 }
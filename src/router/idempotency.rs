@@ -0,0 +1,299 @@
+//! `Idempotency-Key` support for creating endpoints. The first request with a
+//! key stores its response, retries with the same key and request get it
+//! replayed instead of creating a duplicate, and a different request under the
+//! same key is rejected. Keys expire after a day.
+
+use anyhow::anyhow;
+use axum::{
+    async_trait,
+    body::{Body, Bytes},
+    extract::FromRequest,
+    http::{HeaderMap, Request, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{Duration, Utc};
+use database::{traits::idempotency_keys::Payload, RepositoryTrait};
+use futures::future::BoxFuture;
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{errors::AppError, state::State};
+
+const HEADER: &str = "idempotency-key";
+const REPLAYED_HEADER: &str = "idempotent-replayed";
+const MAX_KEY_LENGTH: usize = 255;
+const TTL_HOURS: i64 = 24;
+
+pub(crate) struct Key {
+    value: String,
+    request_hash: String,
+}
+
+// JSON body of a request along with its idempotency key, if any. The body is
+// hashed as sent together with the query string, which names the acting user,
+// so a retry has to resend both unchanged and nobody else can replay it.
+pub(crate) struct Idempotent<T> {
+    pub(crate) key: Option<Key>,
+    pub(crate) payload: T,
+}
+
+fn parse_key(headers: &HeaderMap) -> Result<Option<String>, AppError> {
+    let Some(value) = headers.get(HEADER) else {
+        return Ok(None);
+    };
+
+    value
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|x| !x.is_empty() && x.len() <= MAX_KEY_LENGTH)
+        .map(|x| Some(x.to_owned()))
+        .ok_or_else(|| {
+            AppError::new(
+                StatusCode::BAD_REQUEST,
+                anyhow!("Idempotency-Key must be 1 to {MAX_KEY_LENGTH} visible ASCII characters"),
+            )
+        })
+}
+
+#[async_trait]
+impl<S, T> FromRequest<S, Body> for Idempotent<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+        let key = parse_key(req.headers()).map_err(IntoResponse::into_response)?;
+        let headers = req.headers().clone();
+        let query = req.uri().query().unwrap_or_default().to_owned();
+
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let request_hash = hex::encode(
+            Sha256::new()
+                .chain_update(query)
+                .chain_update([0])
+                .chain_update(&bytes)
+                .finalize(),
+        );
+
+        let mut req = Request::new(Body::from(bytes));
+        *req.headers_mut() = headers;
+
+        let Json(payload) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        Ok(Self {
+            key: key.map(|value| Key {
+                value,
+                request_hash,
+            }),
+            payload,
+        })
+    }
+}
+
+pub(crate) enum Outcome<T> {
+    Done(StatusCode, T),
+    Replayed(Response),
+}
+
+// Without a key the work simply runs. With one, looking the key up, doing the
+// work and storing its response happen in one unit of work, so nothing is
+// kept when the work fails and the client may retry.
+pub(crate) async fn run<T, F>(
+    state: &State,
+    endpoint: &'static str,
+    key: Option<Key>,
+    work: F,
+) -> Result<Outcome<T>, AppError>
+where
+    T: Serialize + Send + 'static,
+    F: for<'a> FnOnce(
+            &'a (dyn RepositoryTrait + Send + Sync),
+        ) -> BoxFuture<'a, Result<(StatusCode, T), AppError>>
+        + Send
+        + 'static,
+{
+    let Some(key) = key else {
+        let (status, response) = work(&*state.repository).await?;
+        return Ok(Outcome::Done(status, response));
+    };
+
+    state
+        .repository
+        .unit_of_work(move |repository| {
+            Box::pin(async move {
+                if let Some(stored) = repository
+                    .lock_idempotency_key(endpoint, &key.value)
+                    .await?
+                {
+                    if stored.request_hash != key.request_hash {
+                        return Err(AppError::new(
+                            StatusCode::UNPROCESSABLE_ENTITY,
+                            anyhow!("Idempotency-Key was already used with a different request"),
+                        ));
+                    }
+
+                    let status = StatusCode::from_u16(u16::try_from(stored.status_code)?)?;
+
+                    return Ok(Outcome::Replayed(
+                        (status, [(REPLAYED_HEADER, "true")], Json(stored.response))
+                            .into_response(),
+                    ));
+                }
+
+                let (status, response) = work(repository).await?;
+
+                repository
+                    .save_idempotency_key(Payload {
+                        endpoint: endpoint.to_owned(),
+                        key: key.value,
+                        request_hash: key.request_hash,
+                        status_code: i16::try_from(status.as_u16())?,
+                        response: serde_json::to_value(&response)?,
+                        expires_at: Utc::now().naive_utc() + Duration::hours(TTL_HOURS),
+                    })
+                    .await?;
+
+                Ok(Outcome::Done(status, response))
+            })
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{
+        body::{Body, Bytes},
+        extract::FromRequest,
+        http::{Request, StatusCode},
+        response::IntoResponse,
+    };
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    use super::{run, Idempotent, Key, Outcome, HEADER, REPLAYED_HEADER};
+    use crate::{enrichment::fetcher::HttpFetcher, router::state::State, testing};
+
+    const ENDPOINT: &str = "idempotency-tests";
+
+    async fn state() -> State {
+        State::new(
+            testing::repository().await,
+            Arc::new(HttpFetcher::new().unwrap()),
+        )
+    }
+
+    async fn key(key: &str, uri: &str, body: &'static str) -> Key {
+        let request = Request::builder()
+            .uri(uri)
+            .header(HEADER, key)
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap();
+
+        Idempotent::<Value>::from_request(request, &())
+            .await
+            .unwrap_or_else(|_| panic!("Request is valid"))
+            .key
+            .unwrap()
+    }
+
+    // Creates nothing, but tells runs apart by a fresh id
+    async fn create(state: &State, key: Key) -> Result<Outcome<Uuid>, StatusCode> {
+        run(state, ENDPOINT, Some(key), |_| {
+            Box::pin(async { Ok((StatusCode::CREATED, Uuid::new_v4())) })
+        })
+        .await
+        .map_err(|err| err.into_response().status())
+    }
+
+    async fn replayed_id(outcome: Outcome<Uuid>) -> Uuid {
+        let Outcome::Replayed(response) = outcome else {
+            panic!("Response is not replayed");
+        };
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[REPLAYED_HEADER], "true");
+
+        let body: Bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a database"]
+    async fn retry_replays_the_stored_response() {
+        let state = state().await;
+        let value = Uuid::new_v4().to_string();
+        let uri = format!("/items?user_id={}", Uuid::new_v4());
+
+        let Ok(Outcome::Done(StatusCode::CREATED, id)) =
+            create(&state, key(&value, &uri, r#"{"name":"a"}"#).await).await
+        else {
+            panic!("First request is not run");
+        };
+        let outcome = create(&state, key(&value, &uri, r#"{"name":"a"}"#).await)
+            .await
+            .unwrap();
+
+        assert_eq!(replayed_id(outcome).await, id);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a database"]
+    async fn different_body_is_rejected() {
+        let state = state().await;
+        let value = Uuid::new_v4().to_string();
+        let uri = format!("/items?user_id={}", Uuid::new_v4());
+
+        assert!(create(&state, key(&value, &uri, r#"{"name":"a"}"#).await)
+            .await
+            .is_ok());
+        assert_eq!(
+            create(&state, key(&value, &uri, r#"{"name":"b"}"#).await)
+                .await
+                .err(),
+            Some(StatusCode::UNPROCESSABLE_ENTITY)
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a database"]
+    async fn other_user_cannot_replay_the_response() {
+        let state = state().await;
+        let value = Uuid::new_v4().to_string();
+
+        assert!(create(
+            &state,
+            key(
+                &value,
+                &format!("/items?user_id={}", Uuid::new_v4()),
+                r#"{"name":"a"}"#
+            )
+            .await
+        )
+        .await
+        .is_ok());
+        assert_eq!(
+            create(
+                &state,
+                key(
+                    &value,
+                    &format!("/items?user_id={}", Uuid::new_v4()),
+                    r#"{"name":"a"}"#
+                )
+                .await
+            )
+            .await
+            .err(),
+            Some(StatusCode::UNPROCESSABLE_ENTITY)
+        );
+    }
+}
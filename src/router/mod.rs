@@ -1,9 +1,28 @@
 use axum::Router as AxumRouter;
-use handlers::{health, items, users, wishlists};
+use handlers::{
+    blocks,
+    contributions,
+    exports,
+    feed,
+    health,
+    items,
+    notifications,
+    reservations,
+    subscriptions,
+    suggestions,
+    templates,
+    users,
+    webhooks,
+    wishlists,
+};
 use state::State;
 
 mod errors;
-mod handlers;
+mod etag;
+pub(crate) mod handlers;
+mod idempotency;
+pub(crate) mod money;
+mod patch;
 pub mod state;
 
 pub struct Router {
@@ -24,6 +43,31 @@ impl From<Router> for AxumRouter {
             .merge(users::get_router(&value.root_path, value.state.clone()))
             .merge(wishlists::get_router(&value.root_path, value.state.clone()))
             .merge(items::get_router(&value.root_path, value.state.clone()))
+            .merge(reservations::get_router(
+                &value.root_path,
+                value.state.clone(),
+            ))
+            .merge(blocks::get_router(&value.root_path, value.state.clone()))
+            .merge(contributions::get_router(
+                &value.root_path,
+                value.state.clone(),
+            ))
+            .merge(subscriptions::get_router(
+                &value.root_path,
+                value.state.clone(),
+            ))
+            .merge(notifications::get_router(
+                &value.root_path,
+                value.state.clone(),
+            ))
+            .merge(templates::get_router(&value.root_path, value.state.clone()))
+            .merge(webhooks::get_router(&value.root_path, value.state.clone()))
+            .merge(exports::get_router(&value.root_path, value.state.clone()))
+            .merge(feed::get_router(&value.root_path, value.state.clone()))
+            .merge(suggestions::get_router(
+                &value.root_path,
+                value.state.clone(),
+            ))
             .merge(health::get_router(&value.root_path, value.state.clone()))
     }
 }
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+
+pub(crate) static DEFAULT_CURRENCY: &str = "USD";
+
+// Active ISO 4217 currency codes
+static CURRENCIES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD", "CAD",
+    "CDF", "CHF", "CLP", "CNY", "COP", "CRC", "CUP", "CVE", "CZK", "DJF", "DKK", "DOP", "DZD",
+    "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ",
+    "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR", "IQD", "IRR", "ISK", "JMD", "JOD",
+    "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD", "KYD", "KZT", "LAK", "LBP", "LKR",
+    "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK", "MNT", "MOP", "MRU", "MUR", "MVR",
+    "MWK", "MXN", "MYR", "MZN", "NAD", "NGN", "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN",
+    "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR",
+    "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB",
+    "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "UYU", "UZS",
+    "VES", "VND", "VUV", "WST", "XAF", "XCD", "XOF", "XPF", "YER", "ZAR", "ZMW", "ZWL",
+];
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "i64", into = "i64")]
+pub(crate) struct Amount(i64);
+
+impl TryFrom<i64> for Amount {
+    type Error = String;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        if value < 0 {
+            Err(format!("Amount '{value}' must not be negative"))
+        } else {
+            Ok(Amount(value))
+        }
+    }
+}
+
+impl From<Amount> for i64 {
+    fn from(val: Amount) -> Self {
+        val.0
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct Currency(String);
+
+impl TryFrom<String> for Currency {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let code = value.to_ascii_uppercase();
+
+        if CURRENCIES.contains(&code.as_str()) {
+            Ok(Currency(code))
+        } else {
+            Err(format!("Unknown ISO 4217 currency code '{value}'"))
+        }
+    }
+}
+
+impl Currency {
+    fn minor_units(&self) -> usize {
+        match self.0.as_str() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF"
+            | "UGX" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            _ => 2,
+        }
+    }
+
+    // Converts a decimal amount like "1299.99" into minor units of the currency
+    pub(crate) fn parse_amount(&self, value: &str) -> Option<Amount> {
+        let value: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+        let value = if value.contains('.') {
+            value.replace(',', "")
+        } else {
+            value.replace(',', ".")
+        };
+
+        let (whole, fraction) = value.split_once('.').unwrap_or((&value, ""));
+        if whole.is_empty()
+            || !whole
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let fraction: String = fraction
+            .chars()
+            .chain(std::iter::repeat('0'))
+            .take(self.minor_units())
+            .collect();
+
+        format!("{whole}{fraction}")
+            .parse::<i64>()
+            .ok()
+            .and_then(|amount| Amount::try_from(amount).ok())
+    }
+}
+
+impl From<Currency> for String {
+    fn from(val: Currency) -> Self {
+        val.0
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Price {
+    pub(crate) amount: Amount,
+    pub(crate) currency: Option<Currency>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Money {
+    amount: i64,
+    currency: String,
+}
+
+impl Money {
+    pub(crate) fn from_parts(amount: Option<i64>, currency: Option<String>) -> Option<Self> {
+        Some(Money {
+            amount: amount?,
+            currency: currency?,
+        })
+    }
+}
@@ -0,0 +1,24 @@
+//! Field deserializers for JSON merge patch (RFC 7396) bodies, used together
+//! with `#[serde(default)]` so that a missing member stays `None` and keeps the
+//! current value.
+
+use serde::{Deserialize, Deserializer};
+
+// A member that cannot be removed, so `null` is rejected
+pub(crate) fn present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+// A member that `null` clears
+#[allow(clippy::option_option)]
+pub(crate) fn nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -2,23 +2,36 @@ use std::sync::Arc;
 
 use database::{Repository, RepositoryTrait};
 
+use crate::{
+    enrichment::{fetcher::Fetcher, Enricher},
+    events::Bus,
+};
+
 pub struct State {
     pub repository: Arc<dyn RepositoryTrait + Send + Sync>,
+    pub enricher: Arc<Enricher>,
+    pub events: Arc<Bus>,
 }
 
 impl Clone for State {
     fn clone(&self) -> Self {
         State {
             repository: self.repository.clone(),
+            enricher: self.enricher.clone(),
+            events: self.events.clone(),
         }
     }
 }
 
 impl State {
     #[must_use]
-    pub fn new(database_repository: Repository) -> Self {
+    pub fn new(database_repository: Repository, fetcher: Arc<dyn Fetcher + Send + Sync>) -> Self {
+        let repository: Arc<dyn RepositoryTrait + Send + Sync> = Arc::new(database_repository);
+
         State {
-            repository: Arc::new(database_repository),
+            enricher: Arc::new(Enricher::new(repository.clone(), fetcher)),
+            events: Arc::new(Bus::new(repository.clone())),
+            repository,
         }
     }
 }
@@ -0,0 +1,21 @@
+//! Setup for tests running against a migrated database given in
+//! `DATABASE_URL`. Blob storage isn't set up, the tests keep away from it.
+
+use database::{BlobStorageClient, BlobStorageConfig, BlobStorageRegion, Database, Repository};
+
+pub(crate) async fn repository() -> Repository {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let database_connection = Database::connect(url).await.unwrap();
+    let blob_storage_client = BlobStorageClient::from_conf(
+        BlobStorageConfig::builder()
+            .behavior_version_latest()
+            .region(BlobStorageRegion::new("us-east-1"))
+            .build(),
+    );
+
+    Repository::new(
+        database_connection,
+        blob_storage_client,
+        "wishlists".to_owned(),
+    )
+}
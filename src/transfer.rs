@@ -0,0 +1,312 @@
+//! Portable format moving a whole wishlist with its items in and out of the app.
+//!
+//! A JSON document describes the wishlist and lists its items in order:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "name": "Birthday",
+//!   "event_type": "birthday",
+//!   "event_date": "2024-05-01",
+//!   "items": [
+//!     {
+//!       "name": "Headphones",
+//!       "description": "Over-ear, black",
+//!       "url": "https://example.com/headphones",
+//!       "price": 19999,
+//!       "currency": "EUR",
+//!       "quantity": 1,
+//!       "priority": "must_have",
+//!       "is_hidden": false
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Only `version`, `name` and the names of the items are required. The event
+//! type is one of `birthday`, `wedding`, `anniversary`, `baby_shower`,
+//! `graduation`, `housewarming`, `holiday` or `other`. Prices are
+//! in minor units of the currency and come together with it, quantity defaults
+//! to 1 and priority (`nice_to_have`, `normal` or `must_have`) to `normal`.
+//!
+//! A CSV file holds the items only, one per row under the header
+//! `name,description,url,price,currency,quantity,priority,is_hidden` with the
+//! same rules, empty cells standing for missing values. The name of the
+//! wishlist is then given separately.
+
+use chrono::{NaiveDate, Utc};
+use clap::ValueEnum;
+use database::{
+    traits::{
+        items::{Filter as ItemFilter, Payload as ItemPayload},
+        wishlists::{
+            Error as DatabaseError,
+            Payload as WishlistPayload,
+            Response as WishlistResponse,
+        },
+    },
+    RepositoryTrait,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::router::{
+    handlers::{
+        items::{Priority, ProductUrl},
+        wishlists::EventType,
+    },
+    money::Currency,
+};
+
+const VERSION: u32 = 1;
+const CSV_HEADER: [&str; 8] = [
+    "name",
+    "description",
+    "url",
+    "price",
+    "currency",
+    "quantity",
+    "priority",
+    "is_hidden",
+];
+
+#[derive(Debug, Error)]
+pub(crate) enum Error {
+    #[error("Cannot parse the document: {0}")]
+    Malformed(String),
+    #[error("{0}")]
+    Invalid(String),
+    #[error("User not found")]
+    UserNotFound,
+    #[error("Wishlist not found")]
+    WishlistNotFound,
+    #[error("Unknown error")]
+    Unknown,
+}
+
+impl From<DatabaseError> for Error {
+    fn from(value: DatabaseError) -> Self {
+        match value {
+            DatabaseError::UserNotFound => Error::UserNotFound,
+            DatabaseError::NotFound => Error::WishlistNotFound,
+            DatabaseError::VersionMismatch | DatabaseError::Archived | DatabaseError::Unknown => {
+                Error::Unknown
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Format {
+    #[default]
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub(crate) fn content_type(self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            Format::Csv => "text/csv",
+        }
+    }
+
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Document {
+    version: u32,
+    name: String,
+    event_type: Option<EventType>,
+    event_date: Option<NaiveDate>,
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Item {
+    name: String,
+    description: Option<String>,
+    url: Option<ProductUrl>,
+    price: Option<i64>,
+    currency: Option<String>,
+    quantity: Option<i32>,
+    priority: Option<Priority>,
+    is_hidden: Option<bool>,
+}
+
+impl Item {
+    fn into_payload(self, wishlist_id: Uuid) -> Result<ItemPayload, String> {
+        if self.name.trim().is_empty() {
+            return Err("name must not be empty".to_owned());
+        }
+
+        let (price, currency) = match (self.price, self.currency) {
+            (Some(price), Some(currency)) if price >= 0 => {
+                (Some(price), Some(Currency::try_from(currency)?.into()))
+            }
+            (Some(price), Some(_)) => return Err(format!("price '{price}' must not be negative")),
+            (None, None) => (None, None),
+            _ => return Err("price and currency must be given together".to_owned()),
+        };
+
+        let quantity = self.quantity.unwrap_or(1);
+        if quantity < 1 {
+            return Err(format!("quantity '{quantity}' must be positive"));
+        }
+
+        Ok(ItemPayload {
+            id: Uuid::new_v4(),
+            wishlist_id,
+            name: self.name,
+            description: self.description,
+            url: self.url.map(|x| x.0),
+            price,
+            currency,
+            quantity,
+            priority: self.priority.map(Into::into).unwrap_or_default(),
+            is_hidden: self.is_hidden.unwrap_or_default(),
+            is_funded: false,
+            picture_id: None,
+            created_at: Utc::now().naive_utc(),
+            updated_at: Utc::now().naive_utc(),
+        })
+    }
+}
+
+fn read_csv(content: &[u8]) -> Result<Vec<Item>, Error> {
+    csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(content)
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .map_err(|err| Error::Malformed(err.to_string()))
+}
+
+fn write_csv(items: &[Item]) -> Result<Vec<u8>, Error> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(Vec::new());
+
+    writer.write_record(CSV_HEADER).or(Err(Error::Unknown))?;
+    for item in items {
+        writer.serialize(item).or(Err(Error::Unknown))?;
+    }
+
+    writer.into_inner().or(Err(Error::Unknown))
+}
+
+fn decode(format: Format, name: Option<String>, content: &[u8]) -> Result<Document, Error> {
+    let document = match format {
+        Format::Json => {
+            serde_json::from_slice(content).map_err(|err| Error::Malformed(err.to_string()))?
+        }
+        Format::Csv => Document {
+            version: VERSION,
+            name: name.ok_or_else(|| {
+                Error::Invalid("The name of the wishlist is required for CSV".to_owned())
+            })?,
+            event_type: None,
+            event_date: None,
+            items: read_csv(content)?,
+        },
+    };
+
+    if document.version != VERSION {
+        return Err(Error::Invalid(format!(
+            "Unsupported version '{}', expected '{VERSION}'",
+            document.version
+        )));
+    }
+
+    Ok(document)
+}
+
+// The whole document is validated before anything is written, so an import
+// either creates the wishlist with all of its items or nothing at all
+pub(crate) async fn import(
+    repository: &(dyn RepositoryTrait + Send + Sync),
+    user_id: Uuid,
+    format: Format,
+    name: Option<String>,
+    content: &[u8],
+) -> Result<WishlistResponse, Error> {
+    let document = decode(format, name, content)?;
+
+    if document.name.trim().is_empty() {
+        return Err(Error::Invalid(
+            "The name of the wishlist must not be empty".to_owned(),
+        ));
+    }
+
+    let wishlist = WishlistPayload {
+        id: Uuid::new_v4(),
+        name: document.name,
+        user_id,
+        event_type: document.event_type.map(Into::into),
+        event_date: document.event_date,
+        created_at: Utc::now().naive_utc(),
+        updated_at: Utc::now().naive_utc(),
+    };
+
+    let items = document
+        .items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            item.into_payload(wishlist.id)
+                .map_err(|err| Error::Invalid(format!("Item {}: {err}", index + 1)))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(repository.import_wishlist(wishlist, items).await?)
+}
+
+pub(crate) async fn export(
+    repository: &(dyn RepositoryTrait + Send + Sync),
+    wishlist: WishlistResponse,
+    format: Format,
+) -> Result<Vec<u8>, Error> {
+    let items: Vec<_> = repository
+        .list_wishlist_items(wishlist.id, wishlist.user_id, ItemFilter::default())
+        .await?
+        .into_iter()
+        .map(|item| Item {
+            name: item.name,
+            description: item.description,
+            url: item.url.map(ProductUrl),
+            price: item.price,
+            currency: item.currency,
+            quantity: Some(item.quantity),
+            priority: Some(item.priority.into()),
+            is_hidden: Some(item.is_hidden),
+        })
+        .collect();
+
+    match format {
+        Format::Json => {
+            let document = Document {
+                version: VERSION,
+                name: wishlist.name,
+                event_type: wishlist
+                    .event_type
+                    .map(|x| x.parse())
+                    .transpose()
+                    .or(Err(Error::Unknown))?,
+                event_date: wishlist.event_date,
+                items,
+            };
+
+            serde_json::to_vec_pretty(&document).or(Err(Error::Unknown))
+        }
+        Format::Csv => write_csv(&items),
+    }
+}
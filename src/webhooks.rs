@@ -0,0 +1,187 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context;
+use chrono::{Duration as ChronoDuration, Utc};
+use database::{
+    traits::webhooks::{Attempt, DeliveryResponse, Response},
+    RepositoryTrait,
+};
+use hmac::{Hmac, Mac};
+use reqwest::{redirect::Policy, Client, Url};
+use sha2::Sha256;
+use tracing::warn;
+
+use crate::outbound;
+
+const BATCH_SIZE: u64 = 100;
+// Longer than a batch of requests can take, given their timeout
+const CLAIM_MINUTES: i64 = 30;
+const TIMEOUT: Duration = Duration::from_secs(10);
+const BASE_DELAY_SECONDS: i64 = 30;
+const MAX_DELAY_SECONDS: i64 = 6 * 60 * 60;
+const MAX_ERROR_LENGTH: usize = 500;
+
+const SIGNATURE_HEADER: &str = "x-wishlists-signature";
+const EVENT_HEADER: &str = "x-wishlists-event";
+const DELIVERY_HEADER: &str = "x-wishlists-delivery";
+
+pub struct Dispatcher {
+    repository: Arc<dyn RepositoryTrait + Send + Sync>,
+    client: Client,
+    interval: Duration,
+    max_attempts: u32,
+}
+
+impl Dispatcher {
+    pub fn new(
+        repository: Arc<dyn RepositoryTrait + Send + Sync>,
+        interval: Duration,
+        max_attempts: u32,
+    ) -> anyhow::Result<Self> {
+        // Redirects aren't followed, receivers get the delivery at the URL they
+        // registered or not at all
+        let client = outbound::client_builder()
+            .redirect(Policy::none())
+            .timeout(TIMEOUT)
+            .user_agent(concat!("wishlists/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+
+        Ok(Dispatcher {
+            repository,
+            client,
+            interval,
+            max_attempts,
+        })
+    }
+
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = self.dispatch().await {
+                warn!("Cannot dispatch webhooks: {err:#}");
+            }
+        }
+    }
+
+    // Instances running side by side never claim the same deliveries
+    async fn dispatch(&self) -> anyhow::Result<()> {
+        let mut webhooks: HashMap<_, Option<Response>> = HashMap::new();
+
+        loop {
+            let deliveries = self
+                .repository
+                .claim_due_webhook_deliveries(BATCH_SIZE, ChronoDuration::minutes(CLAIM_MINUTES))
+                .await?;
+
+            if deliveries.is_empty() {
+                return Ok(());
+            }
+
+            for delivery in deliveries {
+                let webhook = match webhooks.entry(delivery.webhook_id) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        entry.insert(self.repository.get_webhook(delivery.webhook_id).await?)
+                    }
+                };
+
+                // Deliveries of removed webhooks are removed along with them
+                let Some(webhook) = webhook else {
+                    continue;
+                };
+
+                let result = self.send(webhook, &delivery).await;
+                let attempt = self.attempt(&delivery, result);
+                self.repository
+                    .record_webhook_attempt(delivery.id, attempt)
+                    .await?;
+            }
+        }
+    }
+
+    async fn send(
+        &self,
+        webhook: &Response,
+        delivery: &DeliveryResponse,
+    ) -> anyhow::Result<reqwest::StatusCode> {
+        let url = Url::parse(&webhook.url)?;
+        outbound::check_url(&url)?;
+
+        let body = serde_json::to_vec(&delivery.payload)?;
+        let timestamp = Utc::now().timestamp();
+
+        let response = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(
+                SIGNATURE_HEADER,
+                signature(&webhook.secret, timestamp, &body)?,
+            )
+            .header(EVENT_HEADER, &delivery.event)
+            .header(DELIVERY_HEADER, delivery.id.to_string())
+            .body(body)
+            .send()
+            .await
+            .context("Request failed")?;
+
+        Ok(response.status())
+    }
+
+    // Retries are spaced exponentially, starting at half a minute and capped at six hours
+    fn attempt(
+        &self,
+        delivery: &DeliveryResponse,
+        result: anyhow::Result<reqwest::StatusCode>,
+    ) -> Attempt {
+        let (status_code, error) = match result {
+            Ok(status) if status.is_success() => (Some(status.as_u16()), None),
+            Ok(status) => (
+                Some(status.as_u16()),
+                Some(format!("Unexpected status {status}")),
+            ),
+            Err(err) => (None, Some(format!("{err:#}"))),
+        };
+
+        let retry_at = error.as_ref().and_then(|_| {
+            let attempts = u32::try_from(delivery.attempts).unwrap_or(u32::MAX);
+            if attempts + 1 >= self.max_attempts {
+                return None;
+            }
+
+            let delay = 2_i64
+                .checked_pow(attempts)
+                .and_then(|x| x.checked_mul(BASE_DELAY_SECONDS))
+                .map_or(MAX_DELAY_SECONDS, |x| x.min(MAX_DELAY_SECONDS));
+
+            Some(Utc::now().naive_utc() + ChronoDuration::seconds(delay))
+        });
+
+        Attempt {
+            status_code: status_code.and_then(|x| i16::try_from(x).ok()),
+            error: error.map(|x| x.chars().take(MAX_ERROR_LENGTH).collect()),
+            retry_at,
+        }
+    }
+}
+
+// Receivers recompute the HMAC-SHA256 of "<timestamp>.<body>" with the secret of
+// the webhook, the timestamp lets them reject replayed requests
+fn signature(secret: &str, timestamp: i64, body: &[u8]) -> anyhow::Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())?;
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+
+    Ok(format!(
+        "t={timestamp},v1={}",
+        hex::encode(mac.finalize().into_bytes())
+    ))
+}